@@ -3,7 +3,12 @@ use mpl_token_metadata::state::{
 };
 use solana_program::pubkey::Pubkey;
 
-use crate::{HIDDEN_SETTINGS_SPACE, SPL_TOKEN_ALLOWLIST_SETTINGS_SPACE};
+use crate::{
+    BOT_SIGNER_SPACE, BOT_TAX_SETTINGS_SPACE, BURN_TO_MINT_SETTINGS_SPACE, HIDDEN_SETTINGS_SPACE,
+    MINT_ORDER_SPACE, MINT_RATE_LIMIT_SETTINGS_SPACE, MINT_RATE_LIMIT_STATE_SPACE,
+    OPEN_EDITION_SETTINGS_SPACE, PROGRAMMABLE_NFT_SETTINGS_SPACE,
+    SPL_TOKEN_ALLOWLIST_SETTINGS_SPACE, USD_PRICE_SETTINGS_SPACE,
+};
 
 pub const EXPIRE_OFFSET: i64 = 10 * 60;
 pub const PREFIX: &str = "candy_machine";
@@ -18,6 +23,9 @@ pub const MAX_FREEZE_TIME: i64 = 60 * 60 * 24 * 31; // 1 month
 pub const COLLECTIONS_FEATURE_INDEX: usize = 0;
 pub const FREEZE_FEATURE_INDEX: usize = 1;
 pub const FREEZE_LOCK_FEATURE_INDEX: usize = 2;
+pub const ROLLING_FREEZE_FEATURE_INDEX: usize = 3;
+pub const COMPRESSED_FEATURE_INDEX: usize = 4;
+pub const PAUSED_FEATURE_INDEX: usize = 5;
 
 pub const COLLECTION_PDA_SIZE: usize = 8 + 32 + 32;
 
@@ -26,6 +34,8 @@ pub const CONFIG_LINE_SIZE: usize = 4 + MAX_NAME_LENGTH + 4 + MAX_URI_LENGTH;
 pub const A_TOKEN: Pubkey = solana_program::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 pub const COMPUTE_BUDGET: Pubkey =
     solana_program::pubkey!("ComputeBudget111111111111111111111111111111");
+pub const TOKEN_AUTH_RULES_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 pub const ANTI_BOT_DEV_AUTHORITY: Pubkey =
     solana_program::pubkey!("antiDV8bRvF4XTeRqmyHV1jpHD4Lvz7gKBKBBRQb8ir");
@@ -43,6 +53,32 @@ pub const MAX_ROOT_NUMBER_PER_APPEND_MERKLE_ALLOWLIST_TX: usize = 10;
 
 pub const MAX_OMNI_MINT_WALLETS: usize = 5;
 
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+// Upper bound for the delay between a proposal approval and its execution.
+pub const MAX_MULTISIG_TIMELOCK: i64 = 60 * 60 * 24 * 30; // 30 days
+
+pub const MAX_BOT_TAX_ALLOWED_PROGRAM_IDS: usize = 5;
+
+pub const MAX_BOT_SIGNERS: usize = 3;
+
+// Pyth price account layout, see https://github.com/pyth-network/pyth-sdk-rs.
+pub const PRICE_FEED_MAGIC: u32 = 0xa1b2c3d4;
+pub const PRICE_FEED_ACCOUNT_TYPE: u32 = 3;
+pub const PRICE_FEED_STATUS_TRADING: u32 = 1;
+pub const PRICE_FEED_EXPO_OFFSET: usize = 20;
+pub const PRICE_FEED_TIMESTAMP_OFFSET: usize = 96;
+pub const PRICE_FEED_PRICE_OFFSET: usize = 208;
+pub const PRICE_FEED_CONF_OFFSET: usize = 216;
+pub const PRICE_FEED_STATUS_OFFSET: usize = 224;
+pub const PRICE_FEED_MIN_SIZE: usize = 240;
+
+// USD prices are in millionths of a dollar.
+pub const USD_PRICE_DECIMALS: u32 = 6;
+
+// Fields appended to CandyMachine or CandyMachineData move the config lines,
+// so candy machines created with an older layout have to be moved to this
+// one with migrate_candy_machine before the program can read them.
 pub const CONFIG_ARRAY_START: usize = 8 + // key
 // CandyMachine:
 32 + // bullistic_authority
@@ -70,4 +106,51 @@ HIDDEN_SETTINGS_SPACE + // hidden_settings
 2 + // limit_per_address
 1 + // sequential_mint_order_enabled
 4 + MERKLE_ALLOWLIST_ROOT_LIST_SPACE + // merkle_allowlist_root_list vec
+SPL_TOKEN_ALLOWLIST_SETTINGS_SPACE + // spl_token_allowlist_settings
+OPEN_EDITION_SETTINGS_SPACE + // open_edition_settings
+BOT_TAX_SETTINGS_SPACE + // bot_tax_settings
+1 + // on_chain_reveal_enabled
+33 + // voucher_signer
+BURN_TO_MINT_SETTINGS_SPACE + // burn_to_mint_settings
+2 + // referral_fee_basis_points
+1 + // creator_overrides_enabled
+USD_PRICE_SETTINGS_SPACE + // usd_price_settings
+MINT_RATE_LIMIT_SETTINGS_SPACE + // mint_rate_limit_settings
+9 + // min_items_to_succeed
+PROGRAMMABLE_NFT_SETTINGS_SPACE + // programmable_nft_settings
+33 + // drop
+MINT_ORDER_SPACE + // mint_order
+// CandyMachine:
+MINT_RATE_LIMIT_STATE_SPACE + // mint_rate_limit_state
+4 + BOT_SIGNER_SPACE * MAX_BOT_SIGNERS; // bot_signers
+
+// Config array start of candy machines created before the open edition
+// settings and the fields added after them.
+pub const LEGACY_CONFIG_ARRAY_START: usize = 8 + // key
+// CandyMachine:
+32 + // bullistic_authority
+32 + // creator_authority
+32 + // wallet
+33 + // token mint
+8 + // items redeemed
+// CandyMachineData:
+4 + 6 + // uuid
+8 + // price
+9 + // optional premint_price
+9 + // optional allowlist_price
+4 + MAX_SYMBOL_LENGTH + // u32 len + symbol
+8 + // items available
+2 + // seller fee basis points
+8 + // max supply
+1 + // is mutable
+9 + // allowlist_sale_start_time
+8 + // public_sale_start_time
+8 + // public_sale_end_time
+4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN + // creators vec
+4 + 32 * MAX_OMNI_MINT_WALLETS + // omni_mint_wallets vec
+HIDDEN_SETTINGS_SPACE + // hidden_settings
+1 + // bot_protection_enabled
+2 + // limit_per_address
+1 + // sequential_mint_order_enabled
+4 + MERKLE_ALLOWLIST_ROOT_LIST_SPACE + // merkle_allowlist_root_list vec
 SPL_TOKEN_ALLOWLIST_SETTINGS_SPACE; // spl_token_allowlist_settings
@@ -57,8 +57,8 @@ pub enum CandyError {
     SlotHashesEmpty,
     #[msg("The metadata account has data in it, and this must be empty to mint a new NFT.")]
     MetadataAccountMustBeEmpty,
-    #[msg("Missing set collection during mint IX for Candy Machine with collection set.")]
-    MissingSetCollectionDuringMint,
+    #[msg("Missing collection accounts for Candy Machine with collection set.")]
+    MissingCollectionAccounts,
     #[msg("Can't change collection settings after items have begun to be minted.")]
     NoChangingCollectionDuringMint,
     #[msg(
@@ -89,7 +89,7 @@ pub enum CandyError {
         "Can't withdraw Candy Machine while frozen funds need to be redeemed. Unlock funds first."
     )]
     NoWithdrawWithFrozenFunds,
-    #[msg("Missing required remaining accounts for remove_freeze with token mint.")]
+    #[msg("Missing token program, freeze token account, destination token account or treasury mint in the remaining accounts.")]
     MissingRemoveFreezeTokenAccounts,
     #[msg("Can't withdraw SPL Token from freeze PDA into itself.")]
     InvalidFreezeWithdrawTokenAddress,
@@ -119,4 +119,188 @@ pub enum CandyError {
     InvalidMintPrice,
     #[msg("Invalid allowlist settings. Can only enable a single allowlist feature at a time.")]
     InvalidAllowlistSettings,
+    #[msg("The creator authority signed the mint but is not one of the candy machine creators.")]
+    CreatorAuthorityNotACreator,
+    #[msg("Open edition settings can't be combined with hidden settings.")]
+    InvalidOpenEditionSettings,
+    #[msg("Open edition candy machines do not have config lines.")]
+    OpenEditionConfigsDoNotHaveConfigLines,
+    #[msg("Open edition settings can only be switched on, before the first mint.")]
+    CannotSwitchOpenEditionSettings,
+    #[msg("Master edition account doesn't match the open edition settings.")]
+    MasterEditionMismatch,
+    #[msg("Missing master edition accounts for Candy Machine with open edition settings.")]
+    MissingOpenEditionAccounts,
+    #[msg("Token program must be either the SPL token program or Token-2022.")]
+    IncorrectTokenProgram,
+    #[msg("Can only allow up to 5 extra programs in the bot tax settings.")]
+    TooManyBotTaxAllowedPrograms,
+    #[msg("On-chain reveal is only available for candy machines with hidden settings.")]
+    InvalidOnChainRevealSettings,
+    #[msg("Cannot switch on-chain reveal on or off after minting has started.")]
+    CannotSwitchOnChainReveal,
+    #[msg("On-chain reveal is not enabled for this candy machine.")]
+    OnChainRevealNotEnabled,
+    #[msg("Reveal config lines must be uploaded in order, or from the start again.")]
+    InvalidRevealLineIndex,
+    #[msg("Revealed config lines do not match the hidden settings hash.")]
+    RevealHashMismatch,
+    #[msg("Reveal config lines have already been verified.")]
+    RevealAlreadyVerified,
+    #[msg("Reveal config lines have not been verified yet.")]
+    RevealNotVerified,
+    #[msg("Metadata account is not an unrevealed NFT from this candy machine.")]
+    InvalidRevealMetadata,
+    #[msg("Cannot change the on-chain reveal hash after minting has started.")]
+    CannotChangeRevealHash,
+    #[msg("Invalid mint voucher instruction.")]
+    InvalidMintVoucher,
+    #[msg("Mint voucher is for another candy machine or buyer.")]
+    MintVoucherMismatch,
+    #[msg("Mint voucher has expired.")]
+    MintVoucherExpired,
+    #[msg("Missing voucher nonce account.")]
+    MissingVoucherNonceAccount,
+    #[msg("All mints for this voucher have already been used.")]
+    VoucherMintsAlreadyUsed,
+    #[msg("Buyer info account address is invalid.")]
+    InvalidBuyerInfoAccount,
+    #[msg("The recipient must sign to burn allowlist tokens or NFTs, to freeze the NFT, or to use its allowlist or limit per address quota.")]
+    RecipientSignatureRequired,
+    #[msg("Missing NFT accounts for Candy Machine with burn-to-mint settings.")]
+    MissingBurnToMintAccounts,
+    #[msg("NFT to burn is not a verified member of the burn-to-mint collection.")]
+    InvalidBurnToMintNft,
+    #[msg("Referral fee must be at most 10000 basis points, and requires SOL payments.")]
+    InvalidReferralSettings,
+    #[msg("Referrals are not enabled for this candy machine.")]
+    ReferralsNotEnabled,
+    #[msg("Referral account address is invalid.")]
+    InvalidReferralAccount,
+    #[msg("Buyers cannot refer themselves.")]
+    SelfReferral,
+    #[msg("No referral earnings to claim.")]
+    NoReferralEarnings,
+    #[msg(
+        "Creator overrides are not available for hidden settings or open edition Candy Machines."
+    )]
+    InvalidCreatorOverridesSettings,
+    #[msg("Creator overrides are not enabled for this candy machine.")]
+    CreatorOverridesNotEnabled,
+    #[msg("Missing creator overrides account.")]
+    MissingCreatorOverridesAccount,
+    #[msg("Creator override must have 1 to 5 creators with shares adding up to 100, and valid royalties.")]
+    InvalidCreatorOverride,
+    #[msg(
+        "USD pricing requires SOL payments and a confidence limit of at most 10000 basis points."
+    )]
+    InvalidUsdPriceSettings,
+    #[msg("Missing price feed account for Candy Machine priced in USD.")]
+    MissingPriceFeedAccount,
+    #[msg("Price feed account is invalid or not trading.")]
+    InvalidPriceFeed,
+    #[msg("Price feed is stale.")]
+    StalePriceFeed,
+    #[msg("Price feed confidence interval is too wide.")]
+    PriceFeedConfidenceTooWide,
+    #[msg("Mint rate limit must allow at least 1 mint in a window of at least 1 slot.")]
+    InvalidMintRateLimitSettings,
+    #[msg("Too many mints in the current window, try again in a few slots.")]
+    MintRateLimitExceeded,
+    #[msg("Minimum items to succeed must be between 1 and the number of items available.")]
+    InvalidMinItemsToSucceed,
+    #[msg("All-or-nothing sales need freeze to be set while they are enabled.")]
+    MinItemsToSucceedRequiresFreeze,
+    #[msg("Cannot change the minimum items to succeed after minting has started.")]
+    CannotChangeMinItemsToSucceed,
+    #[msg("Missing refund receipt account for all-or-nothing sale.")]
+    MissingRefundReceiptAccount,
+    #[msg("Cannot remove freeze before the minimum items to succeed are sold.")]
+    ReserveNotMet,
+    #[msg(
+        "Refunds are only available once the public sale ended below its minimum items to succeed."
+    )]
+    RefundNotAvailable,
+    #[msg("Thaw NFTs expects groups of mint, token account, edition and owner accounts.")]
+    InvalidThawNftsAccounts,
+    #[msg("Missing freeze record account for rolling freeze.")]
+    MissingFreezeRecordAccount,
+    #[msg("Freeze record doesn't match the NFT being thawed.")]
+    FreezeRecordMismatch,
+    #[msg(
+        "Compressed minting is not available with freeze, collections, open editions, burn-to-mint, on-chain reveal or programmable NFTs."
+    )]
+    CompressedMintNotSupported,
+    #[msg("Can not change the compressed tree once mint has started.")]
+    NoChangingCompressedTreeDuringMint,
+    #[msg("Missing Bubblegum accounts for compressed mint.")]
+    MissingCompressedMintAccounts,
+    #[msg("Merkle tree doesn't match the candy machine's compressed tree.")]
+    CompressedTreeMismatch,
+    #[msg("Programmable NFTs are not available with open editions, on-chain reveal or all-or-nothing sales.")]
+    InvalidProgrammableNftSettings,
+    #[msg("Cannot switch between programmable and classic NFTs after minting has started.")]
+    CannotSwitchProgrammableNftSettings,
+    #[msg("Missing token record or authorization rules accounts for programmable NFT.")]
+    MissingProgrammableNftAccounts,
+    #[msg("Thaw NFTs doesn't support programmable NFTs, thaw them one at a time.")]
+    ProgrammableNftThawNotSupported,
+    #[msg("Failed to build token metadata instruction.")]
+    TokenMetadataInstructionBuildFailed,
+    #[msg("Candy machine public sale has not ended yet.")]
+    PublicSaleNotEnded,
+    #[msg("Can not close unsold inventory while freeze is on, remove it first.")]
+    NoCloseUnsoldInventoryWithFreeze,
+    #[msg("Candy machine has no unsold inventory.")]
+    NoUnsoldInventory,
+    #[msg("Missing the drop account of the candy machine.")]
+    MissingDropAccount,
+    #[msg("Drop account authority doesn't match the candy machine authority.")]
+    InvalidDropAccount,
+    #[msg("Missing the drop buyer info account.")]
+    MissingDropBuyerInfoAccount,
+    #[msg("Drop supply cap has been reached.")]
+    DropSoldOut,
+    #[msg("Buyer has reached the drop limit per address.")]
+    DropBuyLimitPerAddressExceeded,
+    #[msg("Drop supply cap can't be below the number of items already minted.")]
+    InvalidDropSupplyCap,
+    #[msg(
+        "Signer is neither the candy machine authority nor holds the role for this instruction."
+    )]
+    MissingRole,
+    #[msg("Roles account doesn't belong to the candy machine.")]
+    InvalidRolesPda,
+    #[msg("Candy machine is paused.")]
+    CandyMachinePaused,
+    #[msg("Invalid multisig signers, threshold or timelock.")]
+    InvalidMultisigSettings,
+    #[msg("Signer is not one of the multisig signers.")]
+    NotAMultisigSigner,
+    #[msg("Signer has already approved the proposal.")]
+    ProposalAlreadyApproved,
+    #[msg("Proposal doesn't have enough approvals to execute.")]
+    ProposalNotApproved,
+    #[msg("Proposal timelock has not passed yet.")]
+    ProposalTimelockActive,
+    #[msg("Withdraw destination doesn't match the proposal.")]
+    InvalidWithdrawDestination,
+    #[msg("Candy machine is under multisig, this action requires a proposal.")]
+    ActionRequiresProposal,
+    #[msg("Candy machine authority is not its multisig PDA.")]
+    MultisigNotActive,
+    #[msg("Too many or duplicate bot signers.")]
+    InvalidBotSigners,
+    #[msg("Reserved allowlist range must be between 1 and items available.")]
+    InvalidMintOrder,
+    #[msg("No config lines left in the range of this mint phase.")]
+    MintOrderRangeSoldOut,
+    #[msg("Only the buyer or the candy machine authority can close a buyer info account.")]
+    InvalidBuyerInfoAccountCloser,
+    #[msg("Rent receiver is not the wallet which paid for the buyer info account.")]
+    InvalidRentReceiver,
+    #[msg("Candy machine already uses the current account layout.")]
+    CandyMachineAlreadyMigrated,
+    #[msg("Missing the metadata, mint or master edition account of the NFT.")]
+    MissingNftAccounts,
 }
@@ -97,6 +97,8 @@ pub mod bullistic_candy_machine {
         )
     }
 
+    /// Deprecated: mint_nft sets and verifies the collection itself when the
+    /// collection accounts are passed in.
     pub fn set_collection_during_mint(ctx: Context<SetCollectionDuringMint>) -> Result<()> {
         handle_set_collection_during_mint(ctx)
     }
@@ -107,8 +109,82 @@ pub mod bullistic_candy_machine {
         handle_withdraw_funds(ctx)
     }
 
-    pub fn set_freeze(ctx: Context<SetFreeze>, freeze_time: i64) -> Result<()> {
-        handle_set_freeze(ctx, freeze_time)
+    pub fn close_unsold_inventory(ctx: Context<CloseUnsoldInventory>) -> Result<()> {
+        handle_close_unsold_inventory(ctx)
+    }
+
+    pub fn close_buyer_info_account(ctx: Context<CloseBuyerInfoAccount>) -> Result<()> {
+        handle_close_buyer_info_account(ctx)
+    }
+
+    pub fn migrate_candy_machine(ctx: Context<MigrateCandyMachine>) -> Result<()> {
+        handle_migrate_candy_machine(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        handle_set_paused(ctx, paused)
+    }
+
+    pub fn rotate_bot_signers(
+        ctx: Context<RotateBotSigners>,
+        bot_signers: Vec<BotSigner>,
+    ) -> Result<()> {
+        handle_rotate_bot_signers(ctx, bot_signers)
+    }
+
+    pub fn initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+        handle_initialize_roles(ctx)
+    }
+
+    pub fn set_role(ctx: Context<SetRole>, role: Role, key: Option<Pubkey>) -> Result<()> {
+        handle_set_role(ctx, role, key)
+    }
+
+    pub fn set_multisig(
+        ctx: Context<SetMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        timelock_seconds: i64,
+    ) -> Result<()> {
+        handle_set_multisig(ctx, signers, threshold, timelock_seconds)
+    }
+
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        handle_create_proposal(ctx, action)
+    }
+
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        handle_approve_proposal(ctx)
+    }
+
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        handle_execute_proposal(ctx)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        handle_cancel_proposal(ctx)
+    }
+
+    pub fn initialize_drop(
+        ctx: Context<InitializeDrop>,
+        supply_cap: u64,
+        limit_per_address: u16,
+    ) -> Result<()> {
+        handle_initialize_drop(ctx, supply_cap, limit_per_address)
+    }
+
+    pub fn update_drop(
+        ctx: Context<UpdateDrop>,
+        supply_cap: u64,
+        limit_per_address: u16,
+    ) -> Result<()> {
+        handle_update_drop(ctx, supply_cap, limit_per_address)
+    }
+
+    pub fn set_freeze(ctx: Context<SetFreeze>, freeze_time: i64, rolling: bool) -> Result<()> {
+        handle_set_freeze(ctx, freeze_time, rolling)
     }
 
     pub fn remove_freeze(ctx: Context<RemoveFreeze>) -> Result<()> {
@@ -119,7 +195,55 @@ pub mod bullistic_candy_machine {
         handle_thaw_nft(ctx)
     }
 
+    pub fn thaw_nfts<'info>(ctx: Context<'_, '_, '_, 'info, ThawNFTs<'info>>) -> Result<()> {
+        handle_thaw_nfts(ctx)
+    }
+
     pub fn unlock_funds<'info>(ctx: Context<'_, '_, '_, 'info, UnlockFunds<'info>>) -> Result<()> {
         handle_unlock_funds(ctx)
     }
+
+    pub fn refund_nft<'info>(ctx: Context<'_, '_, '_, 'info, RefundNFT<'info>>) -> Result<()> {
+        handle_refund_nft(ctx)
+    }
+
+    pub fn set_compressed_tree(
+        ctx: Context<SetCompressedTree>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        handle_set_compressed_tree(ctx, max_depth, max_buffer_size)
+    }
+
+    pub fn initialize_reveal(ctx: Context<InitializeReveal>) -> Result<()> {
+        handle_initialize_reveal(ctx)
+    }
+
+    pub fn add_reveal_config_lines(
+        ctx: Context<AddRevealConfigLines>,
+        index: u32,
+        config_lines: Vec<ConfigLine>,
+    ) -> Result<()> {
+        handle_add_reveal_config_lines(ctx, index, config_lines)
+    }
+
+    pub fn reveal_nft(ctx: Context<RevealNft>) -> Result<()> {
+        handle_reveal_nft(ctx)
+    }
+
+    pub fn claim_referral_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+        handle_claim_referral_earnings(ctx)
+    }
+
+    pub fn initialize_creator_overrides(ctx: Context<InitializeCreatorOverrides>) -> Result<()> {
+        handle_initialize_creator_overrides(ctx)
+    }
+
+    pub fn set_creator_overrides(
+        ctx: Context<SetCreatorOverrides>,
+        index: u32,
+        creator_overrides: Vec<Option<CreatorOverride>>,
+    ) -> Result<()> {
+        handle_set_creator_overrides(ctx, index, creator_overrides)
+    }
 }
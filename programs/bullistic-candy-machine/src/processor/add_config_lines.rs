@@ -5,16 +5,19 @@ use arrayref::array_ref;
 use mpl_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
 
 use crate::{
+    assert_authority_or_role,
     constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE},
-    CandyError, CandyMachine, ConfigLine,
+    CandyError, CandyMachine, ConfigLine, Role, RolesPda,
 };
 
 /// Add multiple config lines to the candy machine.
 #[derive(Accounts)]
 pub struct AddConfigLines<'info> {
-    #[account(mut, has_one = bullistic_authority)]
+    #[account(mut)]
     candy_machine: Account<'info, CandyMachine>,
+    /// Bullistic authority, or the holder of the config loader role.
     bullistic_authority: Signer<'info>,
+    roles_pda: Option<Account<'info, RolesPda>>,
 }
 
 pub fn handle_add_config_lines(
@@ -23,6 +26,12 @@ pub fn handle_add_config_lines(
     config_lines: Vec<ConfigLine>,
 ) -> Result<()> {
     let candy_machine = &mut ctx.accounts.candy_machine;
+    assert_authority_or_role(
+        candy_machine,
+        &ctx.accounts.roles_pda,
+        ctx.accounts.bullistic_authority.key,
+        Role::ConfigLoader,
+    )?;
     let account = candy_machine.to_account_info();
     let current_count = get_config_count(&account.data.borrow_mut())?;
     let mut data = account.data.borrow_mut();
@@ -35,6 +44,9 @@ pub fn handle_add_config_lines(
     if candy_machine.data.hidden_settings.is_some() {
         return err!(CandyError::HiddenSettingsConfigsDoNotHaveConfigLines);
     }
+    if candy_machine.data.open_edition_settings.is_some() {
+        return err!(CandyError::OpenEditionConfigsDoNotHaveConfigLines);
+    }
     for line in &config_lines {
         let array_of_zeroes = vec![0u8; MAX_NAME_LENGTH - line.name.len()];
         let name = line.name.clone() + std::str::from_utf8(&array_of_zeroes).unwrap();
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyError, ReferralAccount};
+
+/// Withdraw the referral earnings held in a referral account to the referrer.
+#[derive(Accounts)]
+pub struct ClaimReferralEarnings<'info> {
+    #[account(
+        mut,
+        seeds = [
+            ReferralAccount::PREFIX.as_bytes(),
+            referral_account.candy_machine.as_ref(),
+            referrer.key().as_ref()
+        ],
+        bump,
+        has_one = referrer
+    )]
+    referral_account: Account<'info, ReferralAccount>,
+    #[account(mut)]
+    referrer: Signer<'info>,
+}
+
+pub fn handle_claim_referral_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+    let referral_info = ctx.accounts.referral_account.to_account_info();
+    let referrer = &ctx.accounts.referrer;
+
+    // Everything above the rent exempt minimum is unclaimed earnings.
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(referral_info.data_len());
+    let amount = referral_info
+        .lamports()
+        .saturating_sub(rent_exempt_lamports);
+    if amount == 0 {
+        return err!(CandyError::NoReferralEarnings);
+    }
+
+    **referral_info.lamports.borrow_mut() = rent_exempt_lamports;
+    **referrer.lamports.borrow_mut() = referrer
+        .lamports()
+        .checked_add(amount)
+        .ok_or(CandyError::NumericalOverflowError)?;
+
+    let referral_account = &mut ctx.accounts.referral_account;
+    referral_account.total_claimed = referral_account
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(CandyError::NumericalOverflowError)?;
+
+    msg!("Claimed {} lamports of referral earnings.", amount);
+
+    Ok(())
+}
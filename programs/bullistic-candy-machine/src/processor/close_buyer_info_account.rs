@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{cmp_pubkeys, BuyerInfoAccount, CandyError, CandyMachine, BUYER_INFO_ACCOUNT_PREFIX};
+
+/// Close the buyer info account of a wallet once the sale is over, returning
+/// its rent to the wallet which paid for it.
+#[derive(Accounts)]
+pub struct CloseBuyerInfoAccount<'info> {
+    /// CHECK: Validated in the instruction handler. The candy machine may
+    /// already be closed by withdraw_funds.
+    candy_machine: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = rent_receiver,
+        seeds = [
+            BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
+            candy_machine.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    buyer_info_account: Account<'info, BuyerInfoAccount>,
+    /// CHECK: Any wallet, the buyer info account is derived from it.
+    buyer: UncheckedAccount<'info>,
+    /// CHECK: Validated in the instruction handler against the payer of the
+    /// buyer info account.
+    #[account(mut)]
+    rent_receiver: UncheckedAccount<'info>,
+    authority: Signer<'info>,
+}
+
+pub fn handle_close_buyer_info_account(ctx: Context<CloseBuyerInfoAccount>) -> Result<()> {
+    let candy_machine_info = ctx.accounts.candy_machine.to_account_info();
+    // Once the funds are withdrawn the candy machine is gone and its buyer
+    // info accounts are of no use, so anyone can close them.
+    if !candy_machine_info.data_is_empty() {
+        let candy_machine: Account<CandyMachine> = Account::try_from(&candy_machine_info)?;
+        let authority = ctx.accounts.authority.key();
+        if !cmp_pubkeys(&authority, ctx.accounts.buyer.key)
+            && !cmp_pubkeys(&authority, &candy_machine.bullistic_authority)
+        {
+            return err!(CandyError::InvalidBuyerInfoAccountCloser);
+        }
+
+        let clock = Clock::get()?;
+        if clock.unix_timestamp < candy_machine.data.public_sale_end_time {
+            return err!(CandyError::PublicSaleNotEnded);
+        }
+    }
+
+    let payer = ctx.accounts.buyer_info_account.payer;
+    let expected_rent_receiver = if payer == Pubkey::default() {
+        ctx.accounts.buyer.key()
+    } else {
+        payer
+    };
+    if !cmp_pubkeys(&expected_rent_receiver, ctx.accounts.rent_receiver.key) {
+        return err!(CandyError::InvalidRentReceiver);
+    }
+
+    Ok(())
+}
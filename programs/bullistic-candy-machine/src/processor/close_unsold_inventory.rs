@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE, FREEZE_FEATURE_INDEX};
+use crate::{get_space_for_candy, is_feature_active, CandyError, CandyMachine};
+
+/// Drop the unsold inventory of a candy machine once its sale has ended,
+/// refunding the rent of the config lines that will never be minted.
+#[derive(Accounts)]
+pub struct CloseUnsoldInventory<'info> {
+    #[account(mut, has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+}
+
+pub fn handle_close_unsold_inventory(ctx: Context<CloseUnsoldInventory>) -> Result<()> {
+    let candy_machine = &mut ctx.accounts.candy_machine;
+    let clock = Clock::get()?;
+    if clock.unix_timestamp < candy_machine.data.public_sale_end_time {
+        return err!(CandyError::PublicSaleNotEnded);
+    }
+    // Selling out makes frozen NFTs thaw eligible, so this has to wait
+    // until the freeze is removed.
+    if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX) {
+        return err!(CandyError::NoCloseUnsoldInventoryWithFreeze);
+    }
+
+    let items_available = candy_machine.data.items_available as usize;
+    let items_redeemed = candy_machine.items_redeemed as usize;
+    if items_redeemed >= items_available {
+        return err!(CandyError::NoUnsoldInventory);
+    }
+
+    let candy_machine_info = candy_machine.to_account_info();
+    if candy_machine.data.has_config_lines() {
+        let mut data = candy_machine_info.data.borrow_mut();
+        compact_minted_config_lines(&mut data, items_available, items_redeemed)?;
+    }
+
+    msg!("Closing {} unsold items.", items_available - items_redeemed);
+    candy_machine.data.items_available = candy_machine.items_redeemed;
+
+    let new_space = get_space_for_candy(candy_machine.data.clone())?;
+    candy_machine_info.realloc(new_space, false)?;
+
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(new_space);
+    let refund = candy_machine_info
+        .lamports()
+        .checked_sub(rent_exempt_lamports)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    let authority_info = ctx.accounts.bullistic_authority.to_account_info();
+    **candy_machine_info.lamports.borrow_mut() = rent_exempt_lamports;
+    **authority_info.lamports.borrow_mut() = authority_info
+        .lamports()
+        .checked_add(refund)
+        .ok_or(CandyError::NumericalOverflowError)?;
+
+    Ok(())
+}
+
+/// Moves the config lines that were minted to the front of the config array,
+/// and rewrites the line count and bit masks for a config array holding only
+/// those lines, all of them minted.
+fn compact_minted_config_lines(
+    data: &mut [u8],
+    items_available: usize,
+    items_redeemed: usize,
+) -> Result<()> {
+    let lines_start = CONFIG_ARRAY_START + 4;
+    let mint_bit_mask_start = lines_start
+        + items_available * CONFIG_LINE_SIZE
+        + 4
+        + items_available
+            .checked_div(8)
+            .ok_or(CandyError::NumericalOverflowError)?
+        + 4;
+
+    let mut compacted = 0;
+    for index in 0..items_available {
+        if compacted == items_redeemed {
+            break;
+        }
+        let mask = 1u8 << (7 - index % 8);
+        if data[mint_bit_mask_start + index / 8] & mask == 0 {
+            continue;
+        }
+        if index != compacted {
+            let source = lines_start + index * CONFIG_LINE_SIZE;
+            data.copy_within(
+                source..source + CONFIG_LINE_SIZE,
+                lines_start + compacted * CONFIG_LINE_SIZE,
+            );
+        }
+        compacted += 1;
+    }
+
+    // Everything past the kept lines is rewritten, the account is shrunk
+    // right after.
+    let tail_start = lines_start + items_redeemed * CONFIG_LINE_SIZE;
+    data[tail_start..].fill(0);
+    data[CONFIG_ARRAY_START..lines_start].copy_from_slice(&(items_redeemed as u32).to_le_bytes());
+
+    let bit_mask_len = items_redeemed / 8;
+    data[tail_start..tail_start + 4].copy_from_slice(&(bit_mask_len as u32).to_le_bytes());
+    let new_mint_bit_mask_start = tail_start + 4 + bit_mask_len + 4;
+    for bit_mask_start in &[tail_start + 4, new_mint_bit_mask_start] {
+        for index in 0..items_redeemed {
+            data[*bit_mask_start + index / 8] |= 1u8 << (7 - index % 8);
+        }
+    }
+
+    Ok(())
+}
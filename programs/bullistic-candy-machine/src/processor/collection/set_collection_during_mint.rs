@@ -6,7 +6,9 @@ use solana_program::{
 
 use crate::{cmp_pubkeys, CandyMachine, CollectionPda};
 
-/// Sets and verifies the collection during a candy machine mint
+/// Sets and verifies the collection during a candy machine mint. Deprecated,
+/// only kept for clients that send this instruction right after mint_nft
+/// instead of passing the collection accounts to mint_nft.
 #[derive(Accounts)]
 pub struct SetCollectionDuringMint<'info> {
     #[account(has_one = creator_authority)]
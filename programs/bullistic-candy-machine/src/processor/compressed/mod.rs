@@ -0,0 +1,3 @@
+pub mod set_compressed_tree;
+
+pub use set_compressed_tree::*;
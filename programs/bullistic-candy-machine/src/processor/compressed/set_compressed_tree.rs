@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use mpl_bubblegum::{cpi::accounts::CreateTree, program::Bubblegum};
+use spl_account_compression::{program::SplAccountCompression, Noop};
+
+use crate::{
+    constants::{COLLECTIONS_FEATURE_INDEX, COMPRESSED_FEATURE_INDEX, FREEZE_FEATURE_INDEX},
+    is_feature_active, set_feature_flag, CandyError, CandyMachine, CompressedTree,
+};
+
+/// Create the Bubblegum merkle tree compressed NFTs are minted into, owned by
+/// the candy machine's compressed tree PDA.
+#[derive(Accounts)]
+pub struct SetCompressedTree<'info> {
+    #[account(mut, has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        init,
+        seeds = [
+            CompressedTree::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        space = CompressedTree::SIZE,
+        payer = bullistic_authority
+    )]
+    compressed_tree: Account<'info, CompressedTree>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    tree_authority: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI. It must be allocated beforehand, owned
+    /// by the account compression program, with room for the tree.
+    #[account(mut)]
+    merkle_tree: UncheckedAccount<'info>,
+    log_wrapper: Program<'info, Noop>,
+    compression_program: Program<'info, SplAccountCompression>,
+    bubblegum_program: Program<'info, Bubblegum>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_set_compressed_tree(
+    ctx: Context<SetCompressedTree>,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    let candy_machine = &mut ctx.accounts.candy_machine;
+    candy_machine.assert_not_minted(error!(CandyError::NoChangingCompressedTreeDuringMint))?;
+    if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX)
+        || is_feature_active(&candy_machine.data.uuid, COLLECTIONS_FEATURE_INDEX)
+        || candy_machine.data.open_edition_settings.is_some()
+        || candy_machine.data.burn_to_mint_settings.is_some()
+        || candy_machine.data.on_chain_reveal_enabled
+        || candy_machine.data.programmable_nft_settings.is_some()
+    {
+        return err!(CandyError::CompressedMintNotSupported);
+    }
+
+    let compressed_tree = &mut ctx.accounts.compressed_tree;
+    compressed_tree.candy_machine = candy_machine.key();
+    compressed_tree.merkle_tree = ctx.accounts.merkle_tree.key();
+
+    let candy_key = candy_machine.key();
+    let compressed_tree_seeds = [
+        CompressedTree::PREFIX.as_bytes(),
+        candy_key.as_ref(),
+        &[*ctx.bumps.get("compressed_tree").unwrap()],
+    ];
+    let compressed_tree_signer = [&compressed_tree_seeds[..]];
+    mpl_bubblegum::cpi::create_tree(
+        CpiContext::new_with_signer(
+            ctx.accounts.bubblegum_program.to_account_info(),
+            CreateTree {
+                tree_authority: ctx.accounts.tree_authority.to_account_info(),
+                merkle_tree: ctx.accounts.merkle_tree.to_account_info(),
+                payer: ctx.accounts.bullistic_authority.to_account_info(),
+                tree_creator: compressed_tree.to_account_info(),
+                log_wrapper: ctx.accounts.log_wrapper.to_account_info(),
+                compression_program: ctx.accounts.compression_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &compressed_tree_signer,
+        ),
+        max_depth,
+        max_buffer_size,
+        // Only the compressed tree PDA, as tree delegate, can mint into it.
+        Some(false),
+    )?;
+
+    set_feature_flag(&mut candy_machine.data.uuid, COMPRESSED_FEATURE_INDEX);
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyError, CandyMachine, CreatorOverridesPda};
+
+/// Create the creator overrides PDA for a candy machine with creator overrides
+/// enabled.
+#[derive(Accounts)]
+pub struct InitializeCreatorOverrides<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        init,
+        seeds = [
+            CreatorOverridesPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        space = CreatorOverridesPda::SIZE,
+        payer = bullistic_authority
+    )]
+    creator_overrides_pda: Account<'info, CreatorOverridesPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_creator_overrides(ctx: Context<InitializeCreatorOverrides>) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    if !candy_machine.data.creator_overrides_enabled {
+        return err!(CandyError::CreatorOverridesNotEnabled);
+    }
+
+    let creator_overrides_pda = &mut ctx.accounts.creator_overrides_pda;
+    creator_overrides_pda.candy_machine = candy_machine.key();
+
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub mod initialize_creator_overrides;
+pub mod set_creator_overrides;
+
+pub use initialize_creator_overrides::*;
+pub use set_creator_overrides::*;
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{
+    validate_creator_override, CandyError, CandyMachine, CreatorOverride, CreatorOverridesPda,
+};
+
+/// Set or clear the creator overrides of consecutive config lines, starting
+/// at the given index. Lines without an override use the candy machine
+/// creators and royalties.
+#[derive(Accounts)]
+pub struct SetCreatorOverrides<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            CreatorOverridesPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    creator_overrides_pda: Account<'info, CreatorOverridesPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_set_creator_overrides(
+    ctx: Context<SetCreatorOverrides>,
+    index: u32,
+    creator_overrides: Vec<Option<CreatorOverride>>,
+) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    if !candy_machine.data.creator_overrides_enabled {
+        return err!(CandyError::CreatorOverridesNotEnabled);
+    }
+
+    let last_line = (index as usize)
+        .checked_add(creator_overrides.len())
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if last_line > candy_machine.data.items_available as usize {
+        return err!(CandyError::IndexGreaterThanLength);
+    }
+
+    // Grow the creator overrides PDA to fit the lines being set.
+    let creator_overrides_info = ctx.accounts.creator_overrides_pda.to_account_info();
+    let new_size = CreatorOverridesPda::space_for(last_line);
+    if creator_overrides_info.data_len() < new_size {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(creator_overrides_info.lamports());
+        if rent_due > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.bullistic_authority.to_account_info(),
+                        to: creator_overrides_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        creator_overrides_info.realloc(new_size, false)?;
+    }
+
+    let mut data = creator_overrides_info.data.borrow_mut();
+    for (i, creator_override) in creator_overrides.iter().enumerate() {
+        let position = CreatorOverridesPda::space_for(index as usize + i);
+        let line_data = &mut data[position..position + CreatorOverridesPda::LINE_SIZE];
+        line_data.fill(0);
+
+        if let Some(creator_override) = creator_override {
+            validate_creator_override(creator_override)?;
+
+            let serialized = creator_override.try_to_vec()?;
+            line_data[0] = 1;
+            line_data[1..1 + serialized.len()].copy_from_slice(&serialized);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyError, DropAccount};
+
+/// Create a drop account, which candy machines of the same authority can link
+/// to with their drop setting.
+#[derive(Accounts)]
+pub struct InitializeDrop<'info> {
+    #[account(init, payer = authority, space = DropAccount::SIZE)]
+    drop: Account<'info, DropAccount>,
+    #[account(mut)]
+    authority: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_drop(
+    ctx: Context<InitializeDrop>,
+    supply_cap: u64,
+    limit_per_address: u16,
+) -> Result<()> {
+    let drop = &mut ctx.accounts.drop;
+    drop.authority = ctx.accounts.authority.key();
+    drop.supply_cap = supply_cap;
+    drop.limit_per_address = limit_per_address;
+
+    Ok(())
+}
+
+/// Update the supply cap and per-buyer limit of a drop account.
+#[derive(Accounts)]
+pub struct UpdateDrop<'info> {
+    #[account(mut, has_one = authority)]
+    drop: Account<'info, DropAccount>,
+    authority: Signer<'info>,
+}
+
+pub fn handle_update_drop(
+    ctx: Context<UpdateDrop>,
+    supply_cap: u64,
+    limit_per_address: u16,
+) -> Result<()> {
+    let drop = &mut ctx.accounts.drop;
+    if supply_cap > 0 && supply_cap < drop.items_minted {
+        msg!(
+            "Supply cap {} is below the {} items already minted.",
+            supply_cap,
+            drop.items_minted
+        );
+        return err!(CandyError::InvalidDropSupplyCap);
+    }
+
+    drop.supply_cap = supply_cap;
+    drop.limit_per_address = limit_per_address;
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use mpl_token_metadata::instruction::{burn_nft, thaw_delegated_account};
+use solana_program::program::{invoke, invoke_signed};
+
+use crate::{
+    assert_is_ata, assert_is_token_program, assert_keys_equal, assert_owned_by, spl_token_transfer,
+    unpack_token_mint, CandyError, CandyMachine, FreezePda, RefundReceipt, TokenTransferParams,
+};
+
+/// Burn a frozen NFT of a failed all-or-nothing sale and refund its price
+/// from the freeze escrow.
+///
+/// With a treasury mint, the four remaining accounts listed at the end of the
+/// struct are required, in that order.
+#[derive(Accounts)]
+pub struct RefundNFT<'info> {
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(
+        mut,
+        seeds = [FreezePda::PREFIX.as_bytes(), candy_machine.key().as_ref()],
+        bump,
+        has_one = candy_machine
+    )]
+    freeze_pda: Account<'info, FreezePda>,
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            RefundReceipt::PREFIX.as_bytes(),
+            candy_machine.key().as_ref(),
+            mint.key().as_ref()
+        ],
+        bump,
+        has_one = candy_machine,
+        has_one = mint
+    )]
+    refund_receipt: Account<'info, RefundReceipt>,
+    #[account(mut, has_one = mint, has_one = owner)]
+    token_account: Account<'info, TokenAccount>,
+    #[account(mut)]
+    owner: Signer<'info>,
+    #[account(mut)]
+    mint: Account<'info, Mint>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI
+    #[account(mut)]
+    edition: UncheckedAccount<'info>,
+    /// CHECK: account checked in CPI. Only needed if the NFT is a verified
+    /// member of a collection.
+    #[account(mut)]
+    collection_metadata: Option<UncheckedAccount<'info>>,
+    token_program: Program<'info, Token>,
+    /// CHECK: checked in account constraints
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    // > Only needed if candy machine has a mint set
+    // treasury_token_program
+    // > Only needed if candy machine has a mint set
+    // freeze_ata
+    // > Only needed if candy machine has a mint set
+    // destination_ata
+    // > Only needed if candy machine has a mint set
+    // treasury_mint
+}
+
+pub fn handle_refund_nft<'info>(ctx: Context<'_, '_, '_, 'info, RefundNFT<'info>>) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    let freeze_pda = &mut ctx.accounts.freeze_pda;
+    let refund_receipt = &ctx.accounts.refund_receipt;
+    let token_account = &ctx.accounts.token_account;
+    let owner = &ctx.accounts.owner;
+    let mint = &ctx.accounts.mint;
+    let token_program = &ctx.accounts.token_program;
+    let token_metadata_program = &ctx.accounts.token_metadata_program;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    if !candy_machine.has_sale_failed(current_timestamp) {
+        return err!(CandyError::RefundNotAvailable);
+    }
+
+    let candy_key = candy_machine.key();
+    let freeze_seeds = [
+        FreezePda::PREFIX.as_bytes(),
+        candy_key.as_ref(),
+        &[*ctx.bumps.get("freeze_pda").unwrap()],
+    ];
+
+    // Refunded NFTs are the ones still frozen in escrow.
+    if !token_account.is_frozen() {
+        return err!(CandyError::RefundNotAvailable);
+    }
+    invoke_signed(
+        &thaw_delegated_account(
+            mpl_token_metadata::ID,
+            freeze_pda.key(),
+            token_account.key(),
+            ctx.accounts.edition.key(),
+            mint.key(),
+        ),
+        &[
+            freeze_pda.to_account_info(),
+            token_account.to_account_info(),
+            ctx.accounts.edition.to_account_info(),
+            mint.to_account_info(),
+            token_program.to_account_info(),
+            token_metadata_program.to_account_info(),
+        ],
+        &[&freeze_seeds],
+    )?;
+    freeze_pda.frozen_count = freeze_pda.frozen_count.saturating_sub(1);
+
+    let mut burn_accounts = vec![
+        ctx.accounts.metadata.to_account_info(),
+        owner.to_account_info(),
+        mint.to_account_info(),
+        token_account.to_account_info(),
+        ctx.accounts.edition.to_account_info(),
+        token_program.to_account_info(),
+    ];
+    if let Some(collection_metadata) = &ctx.accounts.collection_metadata {
+        burn_accounts.push(collection_metadata.to_account_info());
+    }
+    burn_accounts.push(token_metadata_program.to_account_info());
+    invoke(
+        &burn_nft(
+            token_metadata_program.key(),
+            ctx.accounts.metadata.key(),
+            owner.key(),
+            mint.key(),
+            token_account.key(),
+            ctx.accounts.edition.key(),
+            token_program.key(),
+            ctx.accounts
+                .collection_metadata
+                .as_ref()
+                .map(|collection_metadata| collection_metadata.key()),
+        ),
+        &burn_accounts,
+    )?;
+
+    // The freeze fee paid by the buyer is held with the escrow, so it's
+    // refunded along with the price.
+    let mut lamports_refund = freeze_pda.freeze_fee;
+    if let Some(treasury_mint_key) = &candy_machine.treasury_mint {
+        if ctx.remaining_accounts.len() != 4 {
+            return err!(CandyError::MissingRemoveFreezeTokenAccounts);
+        }
+        let treasury_token_program = &ctx.remaining_accounts[0];
+        assert_is_token_program(treasury_token_program)?;
+
+        let freeze_ata_info = &ctx.remaining_accounts[1];
+        assert_is_ata(freeze_ata_info, &freeze_pda.key(), treasury_mint_key)?;
+
+        let destination_ata = &ctx.remaining_accounts[2];
+        assert_is_ata(destination_ata, owner.key, treasury_mint_key)?;
+
+        let treasury_mint_info = &ctx.remaining_accounts[3];
+        assert_keys_equal(&treasury_mint_info.key(), treasury_mint_key)?;
+        assert_owned_by(treasury_mint_info, treasury_token_program.key)?;
+        let treasury_mint = unpack_token_mint(treasury_mint_info)?;
+
+        spl_token_transfer(TokenTransferParams {
+            source: freeze_ata_info.to_account_info(),
+            mint: treasury_mint_info.to_account_info(),
+            destination: destination_ata.to_account_info(),
+            authority: freeze_pda.to_account_info(),
+            authority_signer_seeds: &freeze_seeds,
+            token_program: treasury_token_program.to_account_info(),
+            amount: refund_receipt.amount,
+            decimals: treasury_mint.decimals,
+        })?;
+    } else {
+        lamports_refund = lamports_refund
+            .checked_add(refund_receipt.amount)
+            .ok_or(CandyError::NumericalOverflowError)?;
+    }
+
+    let freeze_pda_info = freeze_pda.to_account_info();
+    **freeze_pda_info.lamports.borrow_mut() = freeze_pda_info
+        .lamports()
+        .checked_sub(lamports_refund)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    **owner.lamports.borrow_mut() = owner
+        .lamports()
+        .checked_add(lamports_refund)
+        .ok_or(CandyError::NumericalOverflowError)?;
+
+    msg!("Refunded {} for NFT {}.", refund_receipt.amount, mint.key());
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::AccountsClose;
 
 use crate::{
-    constants::{FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX},
+    constants::{FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX, ROLLING_FREEZE_FEATURE_INDEX},
     remove_feature_flag, CandyError, CandyMachine, FreezePda,
 };
 
@@ -20,6 +20,13 @@ pub struct RemoveFreeze<'info> {
 pub fn handle_remove_freeze(ctx: Context<RemoveFreeze>) -> Result<()> {
     let candy_machine = &mut ctx.accounts.candy_machine;
     let freeze_pda = &mut ctx.accounts.freeze_pda;
+    // Funds of an all-or-nothing sale stay escrowed until its reserve is met.
+    if candy_machine.items_redeemed > 0 && !candy_machine.is_reserve_met() {
+        return err!(CandyError::ReserveNotMet);
+    }
+    if candy_machine.items_redeemed == 0 && candy_machine.data.min_items_to_succeed.is_some() {
+        return err!(CandyError::MinItemsToSucceedRequiresFreeze);
+    }
     freeze_pda.allow_thaw = true;
     remove_feature_flag(&mut candy_machine.data.uuid, FREEZE_FEATURE_INDEX);
 
@@ -30,6 +37,7 @@ pub fn handle_remove_freeze(ctx: Context<RemoveFreeze>) -> Result<()> {
     {
         freeze_pda.close(ctx.accounts.bullistic_authority.to_account_info())?;
         remove_feature_flag(&mut candy_machine.data.uuid, FREEZE_LOCK_FEATURE_INDEX);
+        remove_feature_flag(&mut candy_machine.data.uuid, ROLLING_FREEZE_FEATURE_INDEX);
     }
     Ok(())
 }
@@ -2,8 +2,11 @@ use anchor_lang::prelude::*;
 
 use crate::{
     assert_is_ata,
-    constants::{FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX, MAX_FREEZE_TIME},
-    set_feature_flag, CandyError, CandyMachine, FreezePda,
+    constants::{
+        FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX, MAX_FREEZE_TIME,
+        ROLLING_FREEZE_FEATURE_INDEX,
+    },
+    remove_feature_flag, set_feature_flag, CandyError, CandyMachine, FreezePda,
 };
 
 /// Set the Freeze PDA for the candy machine
@@ -30,7 +33,7 @@ pub struct SetFreeze<'info> {
     // freeze_ata
 }
 
-pub fn handle_set_freeze(ctx: Context<SetFreeze>, freeze_time: i64) -> Result<()> {
+pub fn handle_set_freeze(ctx: Context<SetFreeze>, freeze_time: i64, rolling: bool) -> Result<()> {
     let candy_machine = &mut ctx.accounts.candy_machine;
     candy_machine.assert_not_minted(error!(CandyError::NoChangingFreezeDuringMint))?;
     let freeze_pda = &mut ctx.accounts.freeze_pda;
@@ -49,5 +52,11 @@ pub fn handle_set_freeze(ctx: Context<SetFreeze>, freeze_time: i64) -> Result<()
     }
     set_feature_flag(&mut candy_machine.data.uuid, FREEZE_FEATURE_INDEX);
     set_feature_flag(&mut candy_machine.data.uuid, FREEZE_LOCK_FEATURE_INDEX);
+    // A rolling freeze locks each NFT for freeze_time from its own mint.
+    if rolling {
+        set_feature_flag(&mut candy_machine.data.uuid, ROLLING_FREEZE_FEATURE_INDEX);
+    } else {
+        remove_feature_flag(&mut candy_machine.data.uuid, ROLLING_FREEZE_FEATURE_INDEX);
+    }
     Ok(())
 }
@@ -1,11 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::AccountsClose;
 use anchor_spl::token::{Mint, Token, TokenAccount};
-use mpl_token_metadata::instruction::thaw_delegated_account;
-use solana_program::program::{invoke, invoke_signed};
+use mpl_token_metadata::instruction::{
+    builders::{RevokeBuilder, UnlockBuilder},
+    thaw_delegated_account, InstructionBuilder, RevokeArgs, UnlockArgs,
+};
+use mpl_token_metadata::state::{TokenMetadataAccount, TokenRecord, TokenState};
+use solana_program::{
+    program::{invoke, invoke_signed},
+    sysvar,
+};
 use spl_token::instruction::revoke;
 
-use crate::{cmp_pubkeys, CandyError, CandyMachine, FreezePda};
+use crate::{
+    cmp_pubkeys, find_freeze_record_pda, CandyError, CandyMachine, FreezePda, FreezeRecord,
+};
 
 /// Set the collection PDA for the candy machine
 #[derive(Accounts)]
@@ -29,20 +39,48 @@ pub struct ThawNFT<'info> {
     #[account(address = mpl_token_metadata::id())]
     token_metadata_program: UncheckedAccount<'info>,
     system_program: Program<'info, System>,
+    /// CHECK: account checked in handler. Only needed with a rolling freeze,
+    /// it's closed once the NFT is thawed.
+    #[account(mut)]
+    freeze_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: account checked in CPI. Only needed for programmable NFTs.
+    #[account(mut)]
+    metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: account checked in CPI. Only needed for programmable NFTs,
+    /// which are unlocked in their token record instead of thawed.
+    #[account(mut)]
+    token_record: Option<UncheckedAccount<'info>>,
+    /// CHECK: checked in account constraints. Only needed for programmable NFTs.
+    #[account(address = sysvar::instructions::id())]
+    instruction_sysvar_account: Option<UncheckedAccount<'info>>,
+    /// CHECK: account checked in CPI. Only needed for programmable NFTs with
+    /// a rule set.
+    authorization_rules_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: account checked in CPI. Only needed for programmable NFTs with
+    /// a rule set.
+    authorization_rules: Option<UncheckedAccount<'info>>,
 }
 
 pub fn handle_thaw_nft(ctx: Context<ThawNFT>) -> Result<()> {
     let freeze_pda = &mut ctx.accounts.freeze_pda;
     let candy_machine = &mut ctx.accounts.candy_machine;
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let can_thaw = if candy_machine.data_is_empty() {
-        // shouldn't be possible to get into this state with NFTs still not frozen
-        true
-    } else {
-        let candy_struct: Account<CandyMachine> =
-            Account::try_from(&candy_machine.to_account_info())?;
-        freeze_pda.thaw_eligible(current_timestamp, &candy_struct)
+    let freeze_record = match &ctx.accounts.freeze_record {
+        Some(freeze_record) => Some(load_freeze_record(
+            freeze_record,
+            candy_machine.key,
+            &ctx.accounts.mint.key(),
+        )?),
+        None => None,
     };
+    let can_thaw = can_thaw(
+        freeze_pda,
+        &candy_machine.to_account_info(),
+        current_timestamp,
+        freeze_record
+            .as_ref()
+            .map(|freeze_record| freeze_record.frozen_at),
+    )?;
     msg!("Can thaw: {}", can_thaw);
     if !can_thaw {
         return err!(CandyError::InvalidThawNft);
@@ -59,26 +97,122 @@ pub fn handle_thaw_nft(ctx: Context<ThawNFT>) -> Result<()> {
         candy_machine.key.as_ref(),
         &[*ctx.bumps.get("freeze_pda").unwrap()],
     ];
-    if token_account.is_frozen() {
-        msg!("Token account is frozen! Now attempting to thaw!");
-        invoke_signed(
-            &thaw_delegated_account(
-                mpl_token_metadata::ID,
-                freeze_pda.key(),
-                token_account.key(),
-                edition.key(),
-                mint.key(),
-            ),
-            &[
+    let programmable_nft = match &ctx.accounts.token_record {
+        Some(token_record) => Some(ProgrammableNftAccounts {
+            metadata: ctx
+                .accounts
+                .metadata
+                .as_ref()
+                .ok_or(CandyError::MissingProgrammableNftAccounts)?
+                .to_account_info(),
+            token_record: token_record.to_account_info(),
+            instruction_sysvar_account: ctx
+                .accounts
+                .instruction_sysvar_account
+                .as_ref()
+                .ok_or(CandyError::MissingProgrammableNftAccounts)?
+                .to_account_info(),
+            authorization_rules: match (
+                &ctx.accounts.authorization_rules_program,
+                &ctx.accounts.authorization_rules,
+            ) {
+                (Some(authorization_rules_program), Some(authorization_rules)) => Some((
+                    authorization_rules_program.to_account_info(),
+                    authorization_rules.to_account_info(),
+                )),
+                _ => None,
+            },
+        }),
+        None => None,
+    };
+    let programmable_nft_infos = match &programmable_nft {
+        Some(programmable_nft) => {
+            let mut account_infos = vec![
                 freeze_pda.to_account_info(),
+                owner.to_account_info(),
                 token_account.to_account_info(),
-                edition.to_account_info(),
                 mint.to_account_info(),
+                programmable_nft.metadata.clone(),
+                edition.to_account_info(),
+                programmable_nft.token_record.clone(),
+                payer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                programmable_nft.instruction_sysvar_account.clone(),
                 token_program.to_account_info(),
                 token_metadata_program.to_account_info(),
-            ],
-            &[&freeze_seeds],
-        )?;
+            ];
+            if let Some((authorization_rules_program, authorization_rules)) =
+                &programmable_nft.authorization_rules
+            {
+                account_infos.push(authorization_rules_program.clone());
+                account_infos.push(authorization_rules.clone());
+            }
+            account_infos
+        }
+        None => vec![],
+    };
+    // Programmable NFTs are always frozen, their lock is in the token record.
+    let is_frozen = match &programmable_nft {
+        Some(programmable_nft) => {
+            TokenRecord::from_account_info(&programmable_nft.token_record)?.state
+                == TokenState::Locked
+        }
+        None => token_account.is_frozen(),
+    };
+    if is_frozen {
+        match &programmable_nft {
+            Some(programmable_nft) => {
+                msg!("Token record is locked! Now attempting to unlock!");
+                let mut unlock_builder = UnlockBuilder::new();
+                unlock_builder
+                    .authority(freeze_pda.key())
+                    .token_owner(owner.key())
+                    .token(token_account.key())
+                    .mint(mint.key())
+                    .metadata(programmable_nft.metadata.key())
+                    .edition(edition.key())
+                    .token_record(programmable_nft.token_record.key())
+                    .payer(payer.key())
+                    .system_program(ctx.accounts.system_program.key())
+                    .sysvar_instructions(programmable_nft.instruction_sysvar_account.key())
+                    .spl_token_program(token_program.key());
+                if let Some((authorization_rules_program, authorization_rules)) =
+                    &programmable_nft.authorization_rules
+                {
+                    unlock_builder
+                        .authorization_rules_program(authorization_rules_program.key())
+                        .authorization_rules(authorization_rules.key());
+                }
+                let unlock_ix = unlock_builder
+                    .build(UnlockArgs::V1 {
+                        authorization_data: None,
+                    })
+                    .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                    .instruction();
+                invoke_signed(&unlock_ix, &programmable_nft_infos, &[&freeze_seeds])?;
+            }
+            None => {
+                msg!("Token account is frozen! Now attempting to thaw!");
+                invoke_signed(
+                    &thaw_delegated_account(
+                        mpl_token_metadata::ID,
+                        freeze_pda.key(),
+                        token_account.key(),
+                        edition.key(),
+                        mint.key(),
+                    ),
+                    &[
+                        freeze_pda.to_account_info(),
+                        token_account.to_account_info(),
+                        edition.to_account_info(),
+                        mint.to_account_info(),
+                        token_program.to_account_info(),
+                        token_metadata_program.to_account_info(),
+                    ],
+                    &[&freeze_seeds],
+                )?;
+            }
+        }
         if freeze_pda.freeze_fee > 0 && freeze_pda.frozen_count > 0 {
             transfer(
                 CpiContext::new(
@@ -98,14 +232,92 @@ pub fn handle_thaw_nft(ctx: Context<ThawNFT>) -> Result<()> {
     } else {
         msg!("Token account is not frozen!");
     }
+    if let Some(freeze_record) = freeze_record {
+        freeze_record.close(payer.to_account_info())?;
+    }
     if cmp_pubkeys(&payer.key(), &owner.key()) {
-        msg!("Revoking authority");
-        invoke(
-            &revoke(&spl_token::ID, &token_account.key(), &payer.key(), &[])?,
-            &[token_account.to_account_info(), payer.to_account_info()],
-        )?;
+        match &programmable_nft {
+            Some(programmable_nft) => {
+                msg!("Revoking utility delegate");
+                let mut revoke_builder = RevokeBuilder::new();
+                revoke_builder
+                    .delegate(freeze_pda.key())
+                    .metadata(programmable_nft.metadata.key())
+                    .master_edition(edition.key())
+                    .token_record(programmable_nft.token_record.key())
+                    .mint(mint.key())
+                    .token(token_account.key())
+                    .authority(payer.key())
+                    .payer(payer.key())
+                    .system_program(ctx.accounts.system_program.key())
+                    .sysvar_instructions(programmable_nft.instruction_sysvar_account.key())
+                    .spl_token_program(token_program.key());
+                if let Some((authorization_rules_program, authorization_rules)) =
+                    &programmable_nft.authorization_rules
+                {
+                    revoke_builder
+                        .authorization_rules_program(authorization_rules_program.key())
+                        .authorization_rules(authorization_rules.key());
+                }
+                let revoke_ix = revoke_builder
+                    .build(RevokeArgs::UtilityV1)
+                    .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                    .instruction();
+                invoke(&revoke_ix, &programmable_nft_infos)?;
+            }
+            None => {
+                msg!("Revoking authority");
+                invoke(
+                    &revoke(&spl_token::ID, &token_account.key(), &payer.key(), &[])?,
+                    &[token_account.to_account_info(), payer.to_account_info()],
+                )?;
+            }
+        }
     } else {
         msg!("Cannot revoke delegate authority: token account owner is not signer. Re-run as owner to revoke or just call revoke manually.");
     }
     Ok(())
 }
+
+/// Token metadata accounts needed to unlock a programmable NFT.
+struct ProgrammableNftAccounts<'info> {
+    metadata: AccountInfo<'info>,
+    token_record: AccountInfo<'info>,
+    instruction_sysvar_account: AccountInfo<'info>,
+    authorization_rules: Option<(AccountInfo<'info>, AccountInfo<'info>)>,
+}
+
+/// Whether an NFT can be thawed. With a rolling freeze, its lock is checked
+/// against the time it was frozen at, from its freeze record.
+pub(crate) fn can_thaw(
+    freeze_pda: &FreezePda,
+    candy_machine: &AccountInfo,
+    current_timestamp: i64,
+    frozen_at: Option<i64>,
+) -> Result<bool> {
+    if candy_machine.data_is_empty() {
+        // shouldn't be possible to get into this state with NFTs still not frozen
+        return Ok(true);
+    }
+
+    let candy_struct: Account<CandyMachine> = Account::try_from(candy_machine)?;
+    match frozen_at {
+        Some(frozen_at) if candy_struct.has_rolling_freeze() => {
+            Ok(freeze_pda.nft_thaw_eligible(current_timestamp, &candy_struct, frozen_at))
+        }
+        _ => Ok(freeze_pda.thaw_eligible(current_timestamp, &candy_struct)),
+    }
+}
+
+pub(crate) fn load_freeze_record<'info>(
+    freeze_record: &AccountInfo<'info>,
+    candy_machine: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Account<'info, FreezeRecord>> {
+    let (expected_freeze_record, _) = find_freeze_record_pda(candy_machine, mint);
+    if !cmp_pubkeys(freeze_record.key, &expected_freeze_record) {
+        return err!(CandyError::FreezeRecordMismatch);
+    }
+
+    Account::try_from(freeze_record)
+}
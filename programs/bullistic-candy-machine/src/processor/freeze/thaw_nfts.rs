@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{Token, TokenAccount};
+use mpl_token_metadata::instruction::thaw_delegated_account;
+use solana_program::program::invoke_signed;
+
+use crate::{assert_keys_equal, load_freeze_record, CandyError, CandyMachine, FreezePda};
+
+/// Accounts passed in remaining_accounts for each NFT to thaw, not counting
+/// the freeze record of a rolling freeze.
+pub const THAW_NFTS_ACCOUNTS_PER_NFT: usize = 4;
+
+/// Thaw a batch of frozen NFTs in a single instruction
+#[derive(Accounts)]
+pub struct ThawNFTs<'info> {
+    #[account(mut, seeds = [FreezePda::PREFIX.as_bytes(), candy_machine.key().as_ref()], bump, has_one = candy_machine)]
+    freeze_pda: Account<'info, FreezePda>,
+    /// CHECK: account could be empty so must be unchecked. Checked in freeze_pda constraint.
+    #[account(mut)]
+    candy_machine: UncheckedAccount<'info>,
+    #[account(mut)]
+    payer: Signer<'info>,
+    token_program: Program<'info, Token>,
+    /// CHECK: checked in account constraints
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+    system_program: Program<'info, System>,
+    // > Repeated for each NFT to thaw
+    // mint
+    // token_account (mut)
+    // edition
+    // owner
+    // freeze_record (mut, only needed with a rolling freeze)
+}
+
+pub fn handle_thaw_nfts<'info>(ctx: Context<'_, '_, '_, 'info, ThawNFTs<'info>>) -> Result<()> {
+    let freeze_pda = &mut ctx.accounts.freeze_pda;
+    let candy_machine = &ctx.accounts.candy_machine;
+    let payer = &ctx.accounts.payer;
+    let token_program = &ctx.accounts.token_program;
+    let token_metadata_program = &ctx.accounts.token_metadata_program;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    // shouldn't be possible to get into this state with NFTs still not frozen
+    let candy_struct: Option<Account<CandyMachine>> = if candy_machine.data_is_empty() {
+        None
+    } else {
+        Some(Account::try_from(&candy_machine.to_account_info())?)
+    };
+    // Unlocking a programmable NFT needs its metadata and token record too.
+    if candy_struct.as_ref().map_or(false, |candy_struct| {
+        candy_struct.data.programmable_nft_settings.is_some()
+    }) {
+        return err!(CandyError::ProgrammableNftThawNotSupported);
+    }
+    let rolling_freeze = candy_struct
+        .as_ref()
+        .map_or(false, |candy_struct| candy_struct.has_rolling_freeze());
+    let thaw_eligible = candy_struct.as_ref().map_or(true, |candy_struct| {
+        freeze_pda.thaw_eligible(current_timestamp, candy_struct)
+    });
+    // With a rolling freeze, NFTs whose own lock expired can still be thawed.
+    if !thaw_eligible && !rolling_freeze {
+        return err!(CandyError::InvalidThawNft);
+    }
+
+    let accounts_per_nft = if rolling_freeze {
+        THAW_NFTS_ACCOUNTS_PER_NFT + 1
+    } else {
+        THAW_NFTS_ACCOUNTS_PER_NFT
+    };
+    if ctx.remaining_accounts.is_empty() || ctx.remaining_accounts.len() % accounts_per_nft != 0 {
+        return err!(CandyError::InvalidThawNftsAccounts);
+    }
+
+    let freeze_seeds = [
+        FreezePda::PREFIX.as_bytes(),
+        candy_machine.key.as_ref(),
+        &[*ctx.bumps.get("freeze_pda").unwrap()],
+    ];
+
+    let mut thawed_count: u64 = 0;
+    for nft_accounts in ctx.remaining_accounts.chunks(accounts_per_nft) {
+        let mint = &nft_accounts[0];
+        let token_account_info = &nft_accounts[1];
+        let edition = &nft_accounts[2];
+        let owner = &nft_accounts[3];
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_account_info)?;
+        assert_keys_equal(&token_account.mint, mint.key)?;
+        assert_keys_equal(&token_account.owner, owner.key)?;
+        let freeze_record = if rolling_freeze {
+            Some(load_freeze_record(
+                &nft_accounts[4],
+                candy_machine.key,
+                mint.key,
+            )?)
+        } else {
+            None
+        };
+
+        // Already thawed NFTs are skipped so a partially applied batch can be resent.
+        if !token_account.is_frozen() {
+            msg!("Token account {} is not frozen!", token_account_info.key);
+            if let Some(freeze_record) = freeze_record {
+                freeze_record.close(payer.to_account_info())?;
+            }
+            continue;
+        }
+        if let (Some(candy_struct), Some(freeze_record)) = (&candy_struct, &freeze_record) {
+            if !freeze_pda.nft_thaw_eligible(
+                current_timestamp,
+                candy_struct,
+                freeze_record.frozen_at,
+            ) {
+                return err!(CandyError::InvalidThawNft);
+            }
+        }
+        invoke_signed(
+            &thaw_delegated_account(
+                mpl_token_metadata::ID,
+                freeze_pda.key(),
+                token_account_info.key(),
+                edition.key(),
+                mint.key(),
+            ),
+            &[
+                freeze_pda.to_account_info(),
+                token_account_info.clone(),
+                edition.clone(),
+                mint.clone(),
+                token_program.to_account_info(),
+                token_metadata_program.to_account_info(),
+            ],
+            &[&freeze_seeds],
+        )?;
+        thawed_count += 1;
+        if let Some(freeze_record) = freeze_record {
+            freeze_record.close(payer.to_account_info())?;
+        }
+    }
+    msg!("Thawed {} NFTs", thawed_count);
+
+    // The freeze fee is only held for NFTs still counted as frozen.
+    let refunded_count = thawed_count.min(freeze_pda.frozen_count);
+    freeze_pda.frozen_count -= refunded_count;
+    let fee_refund = freeze_pda
+        .freeze_fee
+        .checked_mul(refunded_count)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if fee_refund > 0 {
+        let freeze_pda_info = freeze_pda.to_account_info();
+        **freeze_pda_info.lamports.borrow_mut() = freeze_pda_info
+            .lamports()
+            .checked_sub(fee_refund)
+            .ok_or(CandyError::NumericalOverflowError)?;
+        **payer.lamports.borrow_mut() = payer
+            .lamports()
+            .checked_add(fee_refund)
+            .ok_or(CandyError::NumericalOverflowError)?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,20 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount, Token};
+use anchor_lang::AccountsClose;
+use solana_program::program::invoke_signed;
 
 use crate::{
-    assert_is_ata, constants::FREEZE_FEATURE_INDEX, remove_feature_flag, spl_token_transfer,
-    CandyError, CandyMachine, FreezePda, TokenTransferParams,
+    assert_is_ata, assert_is_token_program, assert_keys_equal, assert_owned_by,
+    constants::{FREEZE_FEATURE_INDEX, ROLLING_FREEZE_FEATURE_INDEX},
+    remove_feature_flag, spl_token_transfer, unpack_token_mint, CandyError, CandyMachine,
+    FreezePda, TokenTransferParams,
 };
 
-/// Unlocks the funds from mint stuck in the FreezePda
+/// Unlocks the funds from mint stuck in the FreezePda. With a rolling freeze the
+/// funds unlock once the last NFT's lock expired, keeping the FreezePda open
+/// until the remaining NFTs are thawed.
+///
+/// With a treasury mint, the four remaining accounts listed at the end of the
+/// struct are required, in that order.
 #[derive(Accounts)]
 pub struct UnlockFunds<'info> {
     #[account(mut, has_one = bullistic_authority)]
@@ -15,7 +23,6 @@ pub struct UnlockFunds<'info> {
     bullistic_authority: Signer<'info>,
     #[account(
         mut,
-        close = bullistic_authority,
         seeds = [
             FreezePda::PREFIX.as_bytes(),
             candy_machine.to_account_info().key.as_ref()
@@ -30,6 +37,8 @@ pub struct UnlockFunds<'info> {
     // freeze_ata
     // > Only needed if candy machine has a mint set
     // destination_ata
+    // > Only needed if candy machine has a mint set
+    // treasury_mint
 }
 
 pub fn handle_unlock_funds<'info>(
@@ -38,19 +47,25 @@ pub fn handle_unlock_funds<'info>(
     let candy_machine = &mut ctx.accounts.candy_machine;
     let freeze_pda = &mut ctx.accounts.freeze_pda;
     let authority = &mut ctx.accounts.bullistic_authority;
-    if freeze_pda.frozen_count > 0 {
+    let rolling_locks_expired = candy_machine.has_rolling_freeze()
+        && freeze_pda.rolling_locks_expired(Clock::get()?.unix_timestamp);
+    if freeze_pda.frozen_count > 0 && !rolling_locks_expired {
         return err!(CandyError::NoUnlockWithNFTsStillFrozen);
     }
+    if candy_machine.items_redeemed == 0 && candy_machine.data.min_items_to_succeed.is_some() {
+        return err!(CandyError::MinItemsToSucceedRequiresFreeze);
+    }
+    let close_freeze_pda = freeze_pda.frozen_count == 0;
 
     if !freeze_pda.allow_thaw {
         freeze_pda.allow_thaw = true;
     }
     if let Some(mint) = &candy_machine.treasury_mint {
-        if ctx.remaining_accounts.len() != 3 {
+        if ctx.remaining_accounts.len() != 4 {
             return err!(CandyError::MissingRemoveFreezeTokenAccounts);
         }
         let token_program = &ctx.remaining_accounts[0];
-        require_keys_eq!(token_program.key(), Token::id());
+        assert_is_token_program(token_program)?;
 
         let freeze_ata_info = &ctx.remaining_accounts[1];
         let freeze_ata = assert_is_ata(freeze_ata_info, &freeze_pda.key(), mint)?;
@@ -62,6 +77,11 @@ pub fn handle_unlock_funds<'info>(
             CandyError::InvalidFreezeWithdrawTokenAddress
         );
 
+        let treasury_mint_info = &ctx.remaining_accounts[3];
+        assert_keys_equal(&treasury_mint_info.key(), mint)?;
+        assert_owned_by(treasury_mint_info, token_program.key)?;
+        let treasury_mint = unpack_token_mint(treasury_mint_info)?;
+
         let candy_key = candy_machine.key();
         let freeze_seeds = [
             FreezePda::PREFIX.as_bytes(),
@@ -70,25 +90,61 @@ pub fn handle_unlock_funds<'info>(
         ];
         spl_token_transfer(TokenTransferParams {
             source: freeze_ata_info.to_account_info(),
+            mint: treasury_mint_info.to_account_info(),
             destination: destination_ata.to_account_info(),
             authority: freeze_pda.to_account_info(),
             authority_signer_seeds: &freeze_seeds,
             token_program: token_program.to_account_info(),
             amount: freeze_ata.amount,
+            decimals: treasury_mint.decimals,
         })?;
 
-        close_account(CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            CloseAccount {
-                account: freeze_ata_info.to_account_info(),
-                destination: authority.to_account_info(),
-                authority: freeze_pda.to_account_info(),
-            },
-            &[&freeze_seeds],
-        ))?;
+        if close_freeze_pda {
+            invoke_signed(
+                &spl_token_2022::instruction::close_account(
+                    token_program.key,
+                    freeze_ata_info.key,
+                    authority.key,
+                    &freeze_pda.key(),
+                    &[],
+                )?,
+                &[
+                    freeze_ata_info.to_account_info(),
+                    authority.to_account_info(),
+                    freeze_pda.to_account_info(),
+                    token_program.to_account_info(),
+                ],
+                &[&freeze_seeds],
+            )?;
+        }
     }
 
     remove_feature_flag(&mut candy_machine.data.uuid, FREEZE_FEATURE_INDEX);
     remove_feature_flag(&mut candy_machine.data.uuid, FREEZE_FEATURE_INDEX);
+    if close_freeze_pda {
+        freeze_pda.close(authority.to_account_info())?;
+        remove_feature_flag(&mut candy_machine.data.uuid, ROLLING_FREEZE_FEATURE_INDEX);
+    } else {
+        // Keep rent and the freeze fees owed to the NFTs still frozen.
+        let freeze_pda_info = freeze_pda.to_account_info();
+        let reserved = Rent::get()?
+            .minimum_balance(freeze_pda_info.data_len())
+            .checked_add(
+                freeze_pda
+                    .freeze_fee
+                    .checked_mul(freeze_pda.frozen_count)
+                    .ok_or(CandyError::NumericalOverflowError)?,
+            )
+            .ok_or(CandyError::NumericalOverflowError)?;
+        let unlocked = freeze_pda_info.lamports().saturating_sub(reserved);
+        **freeze_pda_info.lamports.borrow_mut() = freeze_pda_info
+            .lamports()
+            .checked_sub(unlocked)
+            .ok_or(CandyError::NumericalOverflowError)?;
+        **authority.lamports.borrow_mut() = authority
+            .lamports()
+            .checked_add(unlocked)
+            .ok_or(CandyError::NumericalOverflowError)?;
+    }
     Ok(())
 }
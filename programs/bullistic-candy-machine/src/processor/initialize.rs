@@ -1,12 +1,16 @@
 use anchor_lang::{prelude::*, Discriminator};
 use mpl_token_metadata::state::{MAX_CREATOR_LIMIT, MAX_SYMBOL_LENGTH};
-use spl_token::state::Mint;
 
 use crate::{
-    assert_initialized, assert_owned_by, cmp_pubkeys,
+    assert_owned_by, cmp_pubkeys,
     constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE, MAX_OMNI_MINT_WALLETS},
-    validate_candy_machine_allowlist_state, validate_mint_phase_times, CandyError, CandyMachine,
-    CandyMachineData,
+    unpack_token_account, unpack_token_mint, validate_candy_machine_allowlist_state,
+    validate_candy_machine_bot_tax_settings, validate_candy_machine_creator_overrides_state,
+    validate_candy_machine_min_items_to_succeed, validate_candy_machine_mint_order,
+    validate_candy_machine_mint_rate_limit_settings, validate_candy_machine_open_edition_state,
+    validate_candy_machine_programmable_nft_settings, validate_candy_machine_referral_settings,
+    validate_candy_machine_reveal_state, validate_candy_machine_usd_price_settings,
+    validate_mint_phase_times, CandyError, CandyMachine, CandyMachineData, MintRateLimitState,
 };
 
 /// Create a new candy machine.
@@ -46,6 +50,23 @@ pub fn handle_initialize_candy_machine(
 
     validate_candy_machine_allowlist_state(&data)?;
 
+    validate_candy_machine_open_edition_state(&data)?;
+
+    validate_candy_machine_bot_tax_settings(&data)?;
+
+    validate_candy_machine_reveal_state(&data)?;
+
+    validate_candy_machine_creator_overrides_state(&data)?;
+
+    validate_candy_machine_mint_rate_limit_settings(&data)?;
+
+    // Freeze can only be set once the candy machine exists.
+    validate_candy_machine_min_items_to_succeed(&data, false)?;
+
+    validate_candy_machine_programmable_nft_settings(&data)?;
+
+    validate_candy_machine_mint_order(&data)?;
+
     let mut candy_machine = CandyMachine {
         data,
         bullistic_authority: ctx.accounts.bullistic_authority.key(),
@@ -53,18 +74,20 @@ pub fn handle_initialize_candy_machine(
         treasury_wallet: ctx.accounts.treasury_wallet.key(),
         treasury_mint: None,
         items_redeemed: 0,
+        mint_rate_limit_state: MintRateLimitState::default(),
+        bot_signers: vec![],
     };
 
     candy_machine.data.uuid = "000000".to_string();
 
     if !ctx.remaining_accounts.is_empty() {
         let treasury_mint_info = &ctx.remaining_accounts[0];
-        let _treasury_mint: Mint = assert_initialized(treasury_mint_info)?;
-        let token_account: spl_token::state::Account =
-            assert_initialized(&ctx.accounts.treasury_wallet)?;
+        unpack_token_mint(treasury_mint_info)?;
+        let token_account = unpack_token_account(&ctx.accounts.treasury_wallet)?;
 
-        assert_owned_by(treasury_mint_info, &spl_token::id())?;
-        assert_owned_by(&ctx.accounts.treasury_wallet, &spl_token::id())?;
+        // The treasury token account has to live under the same token program
+        // as the mint, either the SPL token program or Token-2022.
+        assert_owned_by(&ctx.accounts.treasury_wallet, treasury_mint_info.owner)?;
 
         if !cmp_pubkeys(&token_account.mint, &treasury_mint_info.key()) {
             return err!(CandyError::MintMismatch);
@@ -73,6 +96,9 @@ pub fn handle_initialize_candy_machine(
         candy_machine.treasury_mint = Some(*treasury_mint_info.key);
     }
 
+    validate_candy_machine_referral_settings(&candy_machine.data, candy_machine.treasury_mint)?;
+    validate_candy_machine_usd_price_settings(&candy_machine.data, candy_machine.treasury_mint)?;
+
     let mut array_of_zeroes = vec![];
     while array_of_zeroes.len() < MAX_SYMBOL_LENGTH - candy_machine.data.symbol.len() {
         array_of_zeroes.push(0u8);
@@ -97,9 +123,9 @@ pub fn handle_initialize_candy_machine(
         data[i] = new_data[i];
     }
 
-    // only if we are not using hidden settings we will have space for
-    // the config lines
-    if candy_machine.data.hidden_settings.is_none() {
+    // only if we are not using hidden settings or open editions we will have
+    // space for the config lines
+    if candy_machine.data.has_config_lines() {
         let vec_start = CONFIG_ARRAY_START
             + 4
             + (candy_machine.data.items_available as usize) * CONFIG_LINE_SIZE;
@@ -118,7 +144,7 @@ pub fn handle_initialize_candy_machine(
 }
 
 pub fn get_space_for_candy(data: CandyMachineData) -> Result<usize> {
-    let num = if data.hidden_settings.is_some() {
+    let num = if !data.has_config_lines() {
         CONFIG_ARRAY_START
     } else {
         CONFIG_ARRAY_START
@@ -1,16 +1,18 @@
 use anchor_lang::prelude::*;
 
-use crate::{constants::NUMBER_OF_MERKLE_ROOTS_TO_STORE, CandyError, CandyMachine};
+use crate::{
+    assert_authority_or_role, constants::NUMBER_OF_MERKLE_ROOTS_TO_STORE, CandyError, CandyMachine,
+    Role, RolesPda,
+};
 
 /// Append roots to the candy machine merkle allowlist root list.
 #[derive(Accounts)]
 pub struct AppendMerkleAllowlistRoots<'info> {
+    /// Bullistic authority, or the holder of the allowlist manager role.
     bullistic_authority: Signer<'info>,
-    #[account(
-        mut,
-        has_one = bullistic_authority
-    )]
+    #[account(mut)]
     candy_machine: Account<'info, CandyMachine>,
+    roles_pda: Option<Account<'info, RolesPda>>,
 }
 
 pub fn handle_append_merkle_allowlist_roots(
@@ -18,6 +20,12 @@ pub fn handle_append_merkle_allowlist_roots(
     mut roots_to_append: Vec<[u8; 32]>,
 ) -> Result<()> {
     let candy_machine = &mut ctx.accounts.candy_machine;
+    assert_authority_or_role(
+        candy_machine,
+        &ctx.accounts.roles_pda,
+        ctx.accounts.bullistic_authority.key,
+        Role::AllowlistManager,
+    )?;
 
     if candy_machine.data.spl_token_allowlist_settings.is_some() {
         return Err(CandyError::InvalidAllowlistSettings.into());
@@ -52,16 +60,21 @@ pub fn handle_append_merkle_allowlist_roots(
 /// existing roots list is to clear it and recreate the entire list.
 #[derive(Accounts)]
 pub struct ClearMerkleAllowlistRoots<'info> {
+    /// Bullistic authority, or the holder of the allowlist manager role.
     bullistic_authority: Signer<'info>,
-    #[account(
-        mut,
-        has_one = bullistic_authority
-    )]
+    #[account(mut)]
     candy_machine: Account<'info, CandyMachine>,
+    roles_pda: Option<Account<'info, RolesPda>>,
 }
 
 pub fn handle_clear_merkle_allowlist_roots(ctx: Context<ClearMerkleAllowlistRoots>) -> Result<()> {
     let candy_machine = &mut ctx.accounts.candy_machine;
+    assert_authority_or_role(
+        candy_machine,
+        &ctx.accounts.roles_pda,
+        ctx.accounts.bullistic_authority.key,
+        Role::AllowlistManager,
+    )?;
 
     let existing_root_list_length = candy_machine.data.merkle_allowlist_root_list.len();
 
@@ -0,0 +1,74 @@
+use anchor_lang::{prelude::*, Discriminator};
+use solana_program::{program::invoke, system_instruction};
+
+use crate::{
+    cmp_pubkeys,
+    constants::{CONFIG_ARRAY_START, LEGACY_CONFIG_ARRAY_START},
+    get_space_for_candy, CandyError, CandyMachine, LegacyCandyMachine,
+};
+
+/// Move a candy machine created with the legacy account layout to the
+/// current one. Fields added since keep their default values, and the config
+/// lines are shifted to the current config array start.
+#[derive(Accounts)]
+pub struct MigrateCandyMachine<'info> {
+    /// CHECK: Validated in the instruction handler, it can't be deserialized
+    /// as a CandyMachine until it is migrated.
+    #[account(mut, owner = crate::id())]
+    candy_machine: UncheckedAccount<'info>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_migrate_candy_machine(ctx: Context<MigrateCandyMachine>) -> Result<()> {
+    let candy_machine_info = ctx.accounts.candy_machine.to_account_info();
+    let legacy_candy_machine = {
+        let data = candy_machine_info.try_borrow_data()?;
+        if data.len() < 8 || data[..8] != CandyMachine::discriminator() {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+        LegacyCandyMachine::deserialize(&mut &data[8..])?
+    };
+    if !cmp_pubkeys(
+        &legacy_candy_machine.bullistic_authority,
+        ctx.accounts.bullistic_authority.key,
+    ) {
+        return Err(ErrorCode::ConstraintHasOne.into());
+    }
+
+    // Candy machines of the current layout are at least as large as their
+    // space, which legacy ones fall short of by the added fields.
+    let candy_machine = CandyMachine::from(legacy_candy_machine);
+    let old_space = candy_machine_info.data_len();
+    if old_space >= get_space_for_candy(candy_machine.data.clone())? {
+        return err!(CandyError::CandyMachineAlreadyMigrated);
+    }
+
+    let new_space = old_space + CONFIG_ARRAY_START - LEGACY_CONFIG_ARRAY_START;
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(new_space);
+    let rent_top_up = rent_exempt_lamports.saturating_sub(candy_machine_info.lamports());
+    if rent_top_up > 0 {
+        invoke(
+            &system_instruction::transfer(
+                ctx.accounts.bullistic_authority.key,
+                candy_machine_info.key,
+                rent_top_up,
+            ),
+            &[
+                ctx.accounts.bullistic_authority.to_account_info(),
+                candy_machine_info.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+    candy_machine_info.realloc(new_space, false)?;
+
+    let mut data = candy_machine_info.try_borrow_mut_data()?;
+    data.copy_within(LEGACY_CONFIG_ARRAY_START..old_space, CONFIG_ARRAY_START);
+    data[8..CONFIG_ARRAY_START].fill(0);
+    let candy_machine_data = candy_machine.try_to_vec()?;
+    data[8..8 + candy_machine_data.len()].copy_from_slice(&candy_machine_data);
+
+    Ok(())
+}
@@ -1,17 +1,33 @@
 use std::cell::RefMut;
+use std::ops::Range;
 
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator};
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{Mint, Token};
 use arrayref::array_ref;
+use mpl_bubblegum::{
+    cpi::accounts::MintV1,
+    state::metaplex_adapter::{
+        Creator as BubblegumCreator, MetadataArgs, TokenProgramVersion, TokenStandard,
+    },
+};
 use mpl_token_metadata::instruction::freeze_delegated_account;
-use mpl_token_metadata::state::DataV2;
-use mpl_token_metadata::utils::create_or_allocate_account_raw;
+use mpl_token_metadata::state::{Data, DataV2};
+use mpl_token_metadata::utils::{assert_derivation, create_or_allocate_account_raw};
 use mpl_token_metadata::{
     instruction::{
-        create_master_edition_v3, create_metadata_accounts_v3, update_metadata_accounts_v2,
+        builders::{CreateBuilder, DelegateBuilder, LockBuilder, MintBuilder, UpdateBuilder},
+        burn_nft, create_master_edition_v3, create_metadata_accounts_v3,
+        mint_new_edition_from_master_edition_via_token, set_and_verify_collection, sign_metadata,
+        update_metadata_accounts_v2, CollectionDetailsToggle, CollectionToggle, CreateArgs,
+        DelegateArgs, InstructionBuilder, LockArgs, MintArgs, RuleSetToggle, UpdateArgs,
+        UsesToggle,
+    },
+    pda::{find_master_edition_account, find_metadata_account, find_token_record_account},
+    state::{
+        AssetData, Metadata, PrintSupply, TokenMetadataAccount, EDITION, EDITION_MARKER_BIT_SIZE,
+        MAX_NAME_LENGTH, MAX_URI_LENGTH,
     },
-    state::{MAX_NAME_LENGTH, MAX_URI_LENGTH},
 };
 use solana_program::{
     clock::Clock,
@@ -22,15 +38,17 @@ use solana_program::{
 };
 use spl_token::instruction::{approve, initialize_mint, mint_to};
 
-use crate::constants::{COMPUTE_BUDGET, FREEZE_FEATURE_INDEX};
+use crate::constants::{
+    COMPUTE_BUDGET, FREEZE_FEATURE_INDEX, PAUSED_FEATURE_INDEX, TOKEN_AUTH_RULES_PROGRAM_ID,
+};
 use crate::MintPhase;
 use crate::{
-    constants::{
-        A_TOKEN, BOT_FEE, COLLECTIONS_FEATURE_INDEX, CONFIG_ARRAY_START, CONFIG_LINE_SIZE, PREFIX,
-    },
+    constants::{A_TOKEN, COLLECTIONS_FEATURE_INDEX, CONFIG_ARRAY_START, CONFIG_LINE_SIZE, PREFIX},
     utils::*,
-    BuyerInfoAccount, BuyerMerkleAllowlistProofData, CandyError, CandyMachine, ConfigLine,
-    FreezePda, SplTokenAllowlistMode, BUYER_INFO_ACCOUNT_PREFIX, BUYER_INFO_ACCOUNT_SPACE,
+    BurnToMintMode, BuyerInfoAccount, BuyerMerkleAllowlistProofData, CandyError, CandyMachine,
+    CollectionPda, CompressedTree, ConfigLine, DropAccount, DropBuyerInfoAccount, FreezePda,
+    FreezeRecord, MintOrder, ReferralAccount, RefundReceipt, SplTokenAllowlistMode, VoucherNonce,
+    BUYER_INFO_ACCOUNT_PREFIX, BUYER_INFO_ACCOUNT_SPACE, VOUCHER_NONCE_PREFIX, VOUCHER_NONCE_SPACE,
 };
 
 /// Mint a new NFT pseudo-randomly from the config array.
@@ -58,17 +76,20 @@ pub struct MintNFT<'info> {
     treasury_wallet: UncheckedAccount<'info>,
     // With the following accounts we aren't using anchor macros because they are CPI'd
     // through to token-metadata which will do all the validations we need on them.
+    // Compressed NFTs have no metadata, mint or master edition, so these are
+    // left out when minting them.
     /// CHECK: account checked in CPI
     #[account(mut)]
-    metadata: UncheckedAccount<'info>,
+    metadata: Option<UncheckedAccount<'info>>,
     /// CHECK: account checked in CPI
     #[account(mut)]
-    mint: Signer<'info>,
-    /// CHECK: account checked in CPI
+    mint: Option<Signer<'info>>,
+    /// CHECK: account checked in CPI. If it is passed in as a signer it will
+    /// also be marked as a verified creator on the minted NFT.
     creator_authority: UncheckedAccount<'info>,
     /// CHECK: account checked in CPI
     #[account(mut)]
-    master_edition: UncheckedAccount<'info>,
+    master_edition: Option<UncheckedAccount<'info>>,
     /// CHECK: account checked in CPI
     #[account(address = mpl_token_metadata::id())]
     token_metadata_program: UncheckedAccount<'info>,
@@ -82,21 +103,27 @@ pub struct MintNFT<'info> {
     instruction_sysvar_account: UncheckedAccount<'info>,
     /// CHECK: Validated in the instruction handler.
     bot_signer_authority: UncheckedAccount<'info>,
-    /// CHECK: This account is validated in the instruction handler.
-    #[account(
-        mut,
-        seeds = [
-            BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
-            candy_machine.key().as_ref(),
-            buyer.key().as_ref()
-        ],
-        bump
-    )]
+    /// CHECK: This account is validated in the instruction handler. It is
+    /// derived from the recipient.
+    #[account(mut)]
     buyer_info_account: UncheckedAccount<'info>,
     /// CHECK: This account is validated in the instruction handler.
     #[account(mut)]
     buyer_token_account: UncheckedAccount<'info>,
     ata_program: Program<'info, AssociatedToken>,
+    /// CHECK: Any wallet. If set, the NFT is minted to the recipient and the
+    /// allowlist, voucher and limit per address checks apply to it, while the
+    /// buyer only pays. The recipient has to sign when a mint counts against
+    /// its allowlist or limit per address quota. Otherwise the buyer is the
+    /// recipient.
+    recipient: Option<UncheckedAccount<'info>>,
+    /// CHECK: Any wallet other than the buyer and the recipient. If set, the
+    /// referral share of the mint price goes to its referral account.
+    referrer: Option<UncheckedAccount<'info>>,
+    /// CHECK: This account is validated in the instruction handler. It is
+    /// derived from the referrer, and created on the first referred mint.
+    #[account(mut)]
+    referral_account: Option<UncheckedAccount<'info>>,
     // Some additional remaining_accounts may also be included. See the enum
     // below for details.
 }
@@ -108,14 +135,84 @@ enum RemainingAccounts {
     SplTokenAllowlistTokenAccount,
     // Only needed if candy machine has spl_token_allowlist_settings and mode is BurnEveryTime.
     SplTokenAllowlistTokenMint,
+    // Only needed if candy machine has spl_token_allowlist_settings and mode is BurnEveryTime.
+    // Either the SPL token program or Token-2022, matching the allowlist mint.
+    SplTokenAllowlistTokenProgram,
     // Only needed if candy machine has a treasury mint (uses an SPL token).
     TreasuryTokenAccount,
+    // Only needed if candy machine has a treasury mint (uses an SPL token).
+    TreasuryMint,
+    // Only needed if candy machine has a treasury mint (uses an SPL token).
+    // Either the SPL token program or Token-2022, matching the treasury mint.
+    TreasuryTokenProgram,
     // Only needed if freeze feature is active.
     BuyerNftMintTokenAccount,
     // Only needed if freeze feature is active.
     FreezePda,
     // Only needed if spl token mint is enabled.
     FreezeAta,
+    // Only needed if collections feature is active.
+    CollectionPda,
+    // Only needed if collections feature is active.
+    CollectionMint,
+    // Only needed if collections feature is active.
+    CollectionMetadata,
+    // Only needed if collections feature is active.
+    CollectionMasterEdition,
+    // Only needed if collections feature is active.
+    CollectionAuthorityRecord,
+    // Only needed if candy machine has open_edition_settings.
+    MasterEditionMetadata,
+    // Only needed if candy machine has open_edition_settings.
+    MasterEdition,
+    // Only needed if candy machine has open_edition_settings.
+    MasterEditionTokenAccount,
+    // Only needed if candy machine has open_edition_settings.
+    EditionMarker,
+    // Only needed if candy machine has burn_to_mint_settings and the mint
+    // burns an NFT.
+    BurnNftMetadata,
+    // Only needed if candy machine has burn_to_mint_settings and the mint
+    // burns an NFT.
+    BurnNftMint,
+    // Only needed if candy machine has burn_to_mint_settings and the mint
+    // burns an NFT.
+    BurnNftTokenAccount,
+    // Only needed if candy machine has burn_to_mint_settings and the mint
+    // burns an NFT.
+    BurnNftMasterEdition,
+    // Only needed if candy machine has burn_to_mint_settings and the mint
+    // burns an NFT.
+    BurnNftCollectionMetadata,
+    // Only needed if the candy machine mints compressed NFTs.
+    CompressedTree,
+    // Only needed if the candy machine mints compressed NFTs.
+    TreeAuthority,
+    // Only needed if the candy machine mints compressed NFTs.
+    MerkleTree,
+    // Only needed if the candy machine mints compressed NFTs.
+    LogWrapper,
+    // Only needed if the candy machine mints compressed NFTs.
+    CompressionProgram,
+    // Only needed if the candy machine mints compressed NFTs.
+    BubblegumProgram,
+    // The programmable NFT's token record, and the authorization rules program
+    // and rule set if the candy machine has one, are looked up by key, see
+    // get_programmable_nft_accounts.
+    // The bot tax destination, if set in bot_tax_settings, is passed last. It
+    // is looked up by key, see get_bot_tax_destination.
+    // The voucher nonce PDA, when minting with a voucher, is also looked up
+    // by key.
+    // The creator overrides PDA, if creator overrides are enabled, is also
+    // looked up by key.
+    // The price feed, if the candy machine is priced in USD, is also looked
+    // up by key.
+    // The refund receipt PDA, if the sale is all-or-nothing and the NFT is
+    // frozen, is also looked up by key.
+    // The freeze record PDA, if the freeze is rolling and the NFT is frozen,
+    // is also looked up by key.
+    // Compressed mints skip the mint, token account, metadata and edition
+    // accounts above, which can be any unused accounts.
 }
 
 pub fn handle_mint_nft<'info>(
@@ -127,10 +224,20 @@ pub fn handle_mint_nft<'info>(
     expected_price: u64,
 ) -> Result<()> {
     let candy_pubkey = ctx.accounts.candy_machine.key();
+    let bot_tax_settings = ctx.accounts.candy_machine.data.get_bot_tax_settings();
+    let bot_tax_destination = get_bot_tax_destination(
+        &bot_tax_settings,
+        ctx.accounts.candy_machine.to_account_info(),
+        ctx.remaining_accounts,
+    );
     let candy_machine = &mut ctx.accounts.candy_machine;
     let candy_machine_creator = &ctx.accounts.candy_machine_creator;
     let treasury_wallet = ctx.accounts.treasury_wallet.to_account_info();
     let buyer = &ctx.accounts.buyer;
+    let recipient = match &ctx.accounts.recipient {
+        Some(recipient) => recipient.to_account_info(),
+        None => buyer.to_account_info(),
+    };
     let token_program = &ctx.accounts.token_program;
     let clock = Clock::get()?;
     let recent_slothashes = &ctx.accounts.recent_slothashes;
@@ -139,21 +246,28 @@ pub fn handle_mint_nft<'info>(
     let instruction_sysvar = instruction_sysvar_account_info.data.borrow();
     let current_ix = get_instruction_relative(0, &instruction_sysvar_account_info).unwrap();
     // We must ensure the metadata cannot be passed in with data in it, this must remain the first check before any bot taxes
-    if !ctx.accounts.metadata.data_is_empty() {
-        return err!(CandyError::MetadataAccountMustBeEmpty);
+    match &ctx.accounts.metadata {
+        Some(metadata) if !metadata.data_is_empty() => {
+            return err!(CandyError::MetadataAccountMustBeEmpty);
+        }
+        None if !candy_machine.is_compressed() => return err!(CandyError::MissingNftAccounts),
+        _ => {}
     }
 
     let bot_signer_authority = &ctx.accounts.bot_signer_authority;
-    let is_bot_signer_authority_valid =
-        assert_valid_bot_signer_authority(&bot_signer_authority.key());
+    let is_bot_signer_authority_valid = assert_valid_bot_signer_authority(
+        candy_machine,
+        &bot_signer_authority.key(),
+        clock.unix_timestamp,
+    );
 
     if let Err(_e) = is_bot_signer_authority_valid {
         punish_bots(
             CandyError::InvalidBotSignerAuthority,
             buyer.to_account_info(),
-            ctx.accounts.candy_machine.to_account_info(),
+            bot_tax_destination.clone(),
             ctx.accounts.system_program.to_account_info(),
-            BOT_FEE,
+            bot_tax_settings.fee,
         )?;
         return Ok(());
     }
@@ -162,20 +276,9 @@ pub fn handle_mint_nft<'info>(
         punish_bots(
             CandyError::InvalidBotSignerAuthority,
             buyer.to_account_info(),
-            ctx.accounts.candy_machine.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            BOT_FEE,
-        )?;
-        return Ok(());
-    }
-
-    if get_expected_remaining_accounts_count(candy_machine) < ctx.remaining_accounts.len() {
-        punish_bots(
-            CandyError::IncorrectRemainingAccountsLen,
-            buyer.to_account_info(),
-            ctx.accounts.candy_machine.to_account_info(),
+            bot_tax_destination.clone(),
             ctx.accounts.system_program.to_account_info(),
-            BOT_FEE,
+            bot_tax_settings.fee,
         )?;
         return Ok(());
     }
@@ -184,53 +287,119 @@ pub fn handle_mint_nft<'info>(
         return err!(CandyError::CandyMachineEmpty);
     }
 
+    if is_feature_active(&candy_machine.data.uuid, PAUSED_FEATURE_INDEX) {
+        return err!(CandyError::CandyMachinePaused);
+    }
+
     if !cmp_pubkeys(&recent_slothashes.key(), &SlotHashes::id()) {
         return err!(CandyError::IncorrectSlotHashesPubkey);
     }
 
-    if !cmp_pubkeys(&current_ix.program_id, &crate::id()) {
+    if bot_tax_settings.check_top_level_instruction
+        && !cmp_pubkeys(&current_ix.program_id, &crate::id())
+    {
         punish_bots(
             CandyError::SuspiciousTransaction,
             buyer.to_account_info(),
-            ctx.accounts.candy_machine.to_account_info(),
+            bot_tax_destination.clone(),
             ctx.accounts.system_program.to_account_info(),
-            BOT_FEE,
+            bot_tax_settings.fee,
         )?;
         return Ok(());
     }
-    let next_ix = get_instruction_relative(1, &instruction_sysvar_account_info);
-    match next_ix {
-        Ok(ix) => {
-            let discriminator = &ix.data[0..8];
-            let after_collection_ix = get_instruction_relative(2, &instruction_sysvar_account_info);
-
-            if !cmp_pubkeys(&ix.program_id, &crate::id())
-                || discriminator != [103, 17, 200, 25, 118, 95, 125, 61]
-                || after_collection_ix.is_ok()
-            {
+    let is_collection_active =
+        is_feature_active(&candy_machine.data.uuid, COLLECTIONS_FEATURE_INDEX);
+    // Older clients verify the collection in a set_collection_during_mint
+    // instruction right after the mint, without the collection accounts.
+    let is_legacy_collection_mint = is_collection_active
+        && is_followed_by_set_collection_during_mint(&instruction_sysvar_account_info);
+    if bot_tax_settings.check_trailing_instructions {
+        let trailing_ix_index = if is_legacy_collection_mint { 2 } else { 1 };
+        if let Ok(ix) =
+            get_instruction_relative(trailing_ix_index, &instruction_sysvar_account_info)
+        {
+            if !bot_tax_settings.is_program_allowed(&ix.program_id) {
                 // We fail here. Its much cheaper to fail here than to allow a malicious user to add an ix at the end and then fail.
-                msg!("Failing and halting here due to an extra unauthorized instruction from program ID {}.", ix.program_id.to_string());
+                msg!(
+                    "Failing and halting here due to an extra unauthorized instruction from program ID {}.",
+                    ix.program_id.to_string()
+                );
                 return err!(CandyError::SuspiciousTransaction);
             }
         }
-        Err(_) => {
-            if is_feature_active(&candy_machine.data.uuid, COLLECTIONS_FEATURE_INDEX) {
-                punish_bots(
-                    CandyError::MissingSetCollectionDuringMint,
-                    buyer.to_account_info(),
-                    ctx.accounts.candy_machine.to_account_info(),
-                    ctx.accounts.system_program.to_account_info(),
-                    BOT_FEE,
-                )?;
-                return Ok(());
-            }
-        }
     }
+
+    // The remaining accounts of these settings come after the collection
+    // accounts, so they can't be used without them.
+    if is_legacy_collection_mint
+        && (candy_machine.data.open_edition_settings.is_some()
+            || candy_machine.data.burn_to_mint_settings.is_some()
+            || candy_machine.data.programmable_nft_settings.is_some())
+    {
+        return err!(CandyError::MissingCollectionAccounts);
+    }
+    if is_collection_active
+        && !is_legacy_collection_mint
+        && ctx.remaining_accounts.len() < get_open_edition_accounts_start(candy_machine)
+    {
+        punish_bots(
+            CandyError::MissingCollectionAccounts,
+            buyer.to_account_info(),
+            bot_tax_destination.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            bot_tax_settings.fee,
+        )?;
+        return Ok(());
+    }
+
+    if candy_machine.is_compressed()
+        && (is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX)
+            || is_collection_active
+            || candy_machine.data.open_edition_settings.is_some()
+            || candy_machine.data.burn_to_mint_settings.is_some()
+            || candy_machine.data.on_chain_reveal_enabled
+            || candy_machine.data.programmable_nft_settings.is_some())
+    {
+        return err!(CandyError::CompressedMintNotSupported);
+    }
+
+    if candy_machine.is_compressed()
+        && ctx.remaining_accounts.len() < get_compressed_accounts_start(candy_machine) + 6
+    {
+        punish_bots(
+            CandyError::MissingCompressedMintAccounts,
+            buyer.to_account_info(),
+            bot_tax_destination.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            bot_tax_settings.fee,
+        )?;
+        return Ok(());
+    }
+
+    if candy_machine.data.open_edition_settings.is_some()
+        && ctx.remaining_accounts.len() < get_open_edition_accounts_start(candy_machine) + 4
+    {
+        punish_bots(
+            CandyError::MissingOpenEditionAccounts,
+            buyer.to_account_info(),
+            bot_tax_destination.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            bot_tax_settings.fee,
+        )?;
+        return Ok(());
+    }
+
     let mut idx = 0;
     let num_instructions =
         read_u16(&mut idx, &instruction_sysvar).map_err(|_| ProgramError::InvalidAccountData)?;
 
-    for index in 0..num_instructions {
+    let num_instructions_to_check = if bot_tax_settings.check_transaction_programs {
+        num_instructions
+    } else {
+        0
+    };
+
+    for index in 0..num_instructions_to_check {
         let mut current = 2 + (index * 2) as usize;
         let start = read_u16(&mut current, &instruction_sysvar).unwrap();
 
@@ -241,44 +410,111 @@ pub fn handle_mint_nft<'info>(
 
         if !cmp_pubkeys(&program_id, &crate::id())
             && !cmp_pubkeys(&program_id, &spl_token::id())
+            && !cmp_pubkeys(&program_id, &spl_token_2022::id())
             && !cmp_pubkeys(&program_id, &solana_program::system_program::ID)
             && !cmp_pubkeys(&program_id, &A_TOKEN)
             && !cmp_pubkeys(&program_id, &COMPUTE_BUDGET)
+            && !cmp_pubkeys(&program_id, &solana_program::ed25519_program::id())
+            && !bot_tax_settings.is_program_allowed(&program_id)
         {
             msg!("Transaction had ix with program id {}.", program_id);
             punish_bots(
                 CandyError::SuspiciousTransaction,
                 buyer.to_account_info(),
-                ctx.accounts.candy_machine.to_account_info(),
+                bot_tax_destination.clone(),
                 ctx.accounts.system_program.to_account_info(),
-                BOT_FEE,
+                bot_tax_settings.fee,
             )?;
             return Ok(());
         }
     }
 
+    let mint_voucher = get_mint_voucher(&candy_machine.data, &instruction_sysvar_account_info)?;
+    if let Some(voucher) = &mint_voucher {
+        assert_valid_mint_voucher(voucher, &candy_pubkey, recipient.key, clock.unix_timestamp)?;
+    }
+
     let mint_phase = CandyMachine::get_mint_phase(candy_machine, clock.unix_timestamp);
     let is_mint_phase_valid = validate_mint_phase(
-        buyer,
+        recipient.key,
         &mint_phase,
         candy_machine,
         &buyer_merkle_allowlist_proof_data,
+        mint_voucher.is_some(),
     );
 
     if let Err(candy_error) = is_mint_phase_valid {
         punish_bots(
             candy_error,
             buyer.to_account_info(),
-            ctx.accounts.candy_machine.to_account_info(),
+            bot_tax_destination.clone(),
+            ctx.accounts.system_program.to_account_info(),
+            bot_tax_settings.fee,
+        )?;
+        return Ok(());
+    }
+
+    // Allowlist checks only apply during allowlist mint phase.
+    let is_allowlist_phase = mint_phase == MintPhase::Allowlist;
+
+    let is_buyer_omni_minter = is_omni_minter(recipient.key, candy_machine);
+
+    // Voucher holders were already approved by the voucher signer.
+    let skip_allowlist_checks = is_buyer_omni_minter || mint_voucher.is_some();
+
+    // The burned NFT stands in for the mint price, or for the allowlist proof
+    // during the allowlist phase.
+    let burn_to_mint_collection = match &candy_machine.data.burn_to_mint_settings {
+        Some(settings) => match settings.mode {
+            BurnToMintMode::ReplacesPayment => Some(settings.collection_mint),
+            BurnToMintMode::ReplacesAllowlist if is_allowlist_phase && !skip_allowlist_checks => {
+                Some(settings.collection_mint)
+            }
+            BurnToMintMode::ReplacesAllowlist => None,
+        },
+        None => None,
+    };
+
+    if get_expected_remaining_accounts_count(candy_machine, burn_to_mint_collection.is_some())
+        < ctx.remaining_accounts.len()
+    {
+        punish_bots(
+            CandyError::IncorrectRemainingAccountsLen,
+            buyer.to_account_info(),
+            bot_tax_destination.clone(),
             ctx.accounts.system_program.to_account_info(),
-            BOT_FEE,
+            bot_tax_settings.fee,
         )?;
         return Ok(());
     }
 
-    let price = CandyMachine::get_mint_price(candy_machine, &mint_phase);
+    // A voucher sets its own price, whatever the mint phase.
+    let price = match &mint_voucher {
+        Some(voucher) => voucher.price,
+        None => CandyMachine::get_mint_price(candy_machine, &mint_phase),
+    };
+
+    // USD prices are converted to lamports at the current price feed price,
+    // and the expected price is the most the buyer is willing to pay.
+    let (price, is_price_valid) = match &candy_machine.data.usd_price_settings {
+        Some(usd_price_settings) => {
+            let price_feed_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &usd_price_settings.price_feed))
+                .ok_or(CandyError::MissingPriceFeedAccount)?;
+            let price = get_usd_price_in_lamports(
+                usd_price_settings,
+                price_feed_info,
+                price,
+                clock.unix_timestamp,
+            )?;
+            (price, price <= expected_price)
+        }
+        None => (price, price == expected_price),
+    };
 
-    if price != expected_price {
+    if !is_price_valid {
         msg!(
             "Invalid mint price for mint_phase {:?}: actual mint price = {}, expected price = {}.",
             mint_phase,
@@ -289,6 +525,17 @@ pub fn handle_mint_nft<'info>(
     }
 
     let buyer_info_account = &ctx.accounts.buyer_info_account;
+    let (expected_buyer_info_account, _) = Pubkey::find_program_address(
+        &[
+            BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
+            candy_pubkey.as_ref(),
+            recipient.key.as_ref(),
+        ],
+        &crate::id(),
+    );
+    if !cmp_pubkeys(&expected_buyer_info_account, &buyer_info_account.key()) {
+        return err!(CandyError::InvalidBuyerInfoAccount);
+    }
     let limit_per_address = candy_machine.data.limit_per_address;
 
     let provided_merkle_allowlist_proof = buyer_merkle_allowlist_proof_data.is_some();
@@ -296,11 +543,16 @@ pub fn handle_mint_nft<'info>(
     // Only create the BuyerInfoAccount if the edition has a limit_per_address
     // OR if the buyer provided an allowlist proof.
     let should_create_buyer_info_account = limit_per_address > 0 || provided_merkle_allowlist_proof;
+    // The buyer info account tracks the recipient's allowlist and limit per
+    // address quota, so only the recipient can agree to use it up.
+    if should_create_buyer_info_account && !recipient.is_signer {
+        return err!(CandyError::RecipientSignatureRequired);
+    }
     if should_create_buyer_info_account && buyer_info_account.data_is_empty() {
         let signer_seeds = [
             BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
             &candy_machine.key().to_bytes(),
-            &buyer.key().to_bytes(),
+            &recipient.key().to_bytes(),
             &[buyer_info_account_bump],
         ];
 
@@ -314,17 +566,124 @@ pub fn handle_mint_nft<'info>(
         )?;
 
         write_anchor_account_discriminator::<BuyerInfoAccount>(buyer_info_account)?;
+
+        // Record who the account is for and who paid its rent, so it can be
+        // found and the rent returned once the account is closed.
+        let mut new_buyer_info_account: Account<BuyerInfoAccount> =
+            Account::try_from(buyer_info_account)?;
+        new_buyer_info_account.buyer = recipient.key();
+        new_buyer_info_account.payer = buyer.key();
+        new_buyer_info_account.exit(&crate::id())?;
     }
 
-    // Allowlist checks only apply during allowlist mint phase.
-    let is_allowlist_phase = mint_phase == MintPhase::Allowlist;
+    let drop_accounts = match candy_machine.data.drop {
+        Some(drop_key) => Some(get_drop_accounts(
+            candy_machine,
+            &drop_key,
+            ctx.remaining_accounts,
+            &recipient,
+            buyer,
+            &ctx.accounts.system_program,
+        )?),
+        None => None,
+    };
+
+    let voucher_nonce: Option<Account<VoucherNonce>> = match &mint_voucher {
+        Some(voucher) => {
+            let (voucher_nonce_key, voucher_nonce_bump) =
+                find_voucher_nonce_pda(&candy_pubkey, recipient.key, voucher.nonce);
+            let voucher_nonce_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &voucher_nonce_key))
+                .ok_or(CandyError::MissingVoucherNonceAccount)?;
+
+            if voucher_nonce_info.data_is_empty() {
+                let nonce_bytes = voucher.nonce.to_le_bytes();
+                let signer_seeds = [
+                    VOUCHER_NONCE_PREFIX.as_bytes(),
+                    &candy_pubkey.to_bytes(),
+                    &recipient.key().to_bytes(),
+                    &nonce_bytes,
+                    &[voucher_nonce_bump],
+                ];
+
+                create_or_allocate_account_raw(
+                    *ctx.program_id,
+                    voucher_nonce_info,
+                    &ctx.accounts.system_program,
+                    buyer,
+                    VOUCHER_NONCE_SPACE,
+                    &signer_seeds,
+                )?;
+
+                write_anchor_account_discriminator::<VoucherNonce>(voucher_nonce_info)?;
+            }
+
+            let voucher_nonce: Account<VoucherNonce> = Account::try_from(voucher_nonce_info)?;
+            require!(
+                voucher_nonce.number_minted < voucher.max_amount,
+                CandyError::VoucherMintsAlreadyUsed
+            );
+            Some(voucher_nonce)
+        }
+        None => None,
+    };
+
+    let mut referral_account: Option<Account<ReferralAccount>> = match &ctx.accounts.referrer {
+        Some(referrer) => {
+            if candy_machine.data.referral_fee_basis_points == 0 {
+                return err!(CandyError::ReferralsNotEnabled);
+            }
+            if cmp_pubkeys(referrer.key, buyer.key) || cmp_pubkeys(referrer.key, recipient.key) {
+                return err!(CandyError::SelfReferral);
+            }
+
+            let referral_info = match &ctx.accounts.referral_account {
+                Some(referral_account) => referral_account.to_account_info(),
+                None => return err!(CandyError::InvalidReferralAccount),
+            };
+            let (expected_referral_account, referral_account_bump) =
+                find_referral_account_pda(&candy_pubkey, referrer.key);
+            if !cmp_pubkeys(&expected_referral_account, referral_info.key) {
+                return err!(CandyError::InvalidReferralAccount);
+            }
+
+            let is_new_referral_account = referral_info.data_is_empty();
+            if is_new_referral_account {
+                let signer_seeds = [
+                    ReferralAccount::PREFIX.as_bytes(),
+                    &candy_pubkey.to_bytes(),
+                    &referrer.key().to_bytes(),
+                    &[referral_account_bump],
+                ];
+
+                create_or_allocate_account_raw(
+                    *ctx.program_id,
+                    &referral_info,
+                    &ctx.accounts.system_program,
+                    buyer,
+                    ReferralAccount::SIZE,
+                    &signer_seeds,
+                )?;
 
-    let is_buyer_omni_minter = is_omni_minter(buyer, candy_machine);
+                write_anchor_account_discriminator::<ReferralAccount>(&referral_info)?;
+            }
+
+            let mut referral_account: Account<ReferralAccount> = Account::try_from(&referral_info)?;
+            if is_new_referral_account {
+                referral_account.candy_machine = candy_pubkey;
+                referral_account.referrer = referrer.key();
+            }
+            Some(referral_account)
+        }
+        None => None,
+    };
 
     // Only check the Merkle allowlist proof if the allowlist proof data is provided.
     if let (true, false, Some(proof_data)) = (
         is_allowlist_phase,
-        is_buyer_omni_minter,
+        skip_allowlist_checks,
         buyer_merkle_allowlist_proof_data,
     ) {
         let amount = proof_data.amount;
@@ -346,7 +705,7 @@ pub fn handle_mint_nft<'info>(
 
         let leaf = solana_program::keccak::hashv(&[
             &[0x00],
-            &buyer.key().to_bytes(),
+            &recipient.key().to_bytes(),
             &candy_machine.key().to_bytes(),
             &amount.to_le_bytes(),
         ]);
@@ -369,15 +728,15 @@ pub fn handle_mint_nft<'info>(
         );
 
         msg!(
-            "Valid merkle allowlist proof submitted by {:?} with root index {}.",
-            buyer.key(),
+            "Valid merkle allowlist proof submitted for {:?} with root index {}.",
+            recipient.key(),
             root_index_for_proof
         );
     }
 
     if let (true, false, Some(spl_token_allowlist_settings)) = (
         is_allowlist_phase,
-        is_buyer_omni_minter,
+        skip_allowlist_checks,
         &candy_machine.data.spl_token_allowlist_settings,
     ) {
         let spl_token_allowlist_token_account = get_remaining_account(
@@ -388,7 +747,7 @@ pub fn handle_mint_nft<'info>(
 
         let buyer_allowlist_token_account = assert_is_token_account(
             &spl_token_allowlist_token_account,
-            &buyer.key(),
+            recipient.key,
             &spl_token_allowlist_settings.mint,
         );
 
@@ -396,9 +755,9 @@ pub fn handle_mint_nft<'info>(
             punish_bots(
                 CandyError::NoSplAllowlistToken,
                 buyer.to_account_info(),
-                ctx.accounts.candy_machine.to_account_info(),
+                bot_tax_destination.clone(),
                 ctx.accounts.system_program.to_account_info(),
-                BOT_FEE,
+                bot_tax_settings.fee,
             )?;
             return Ok(());
         }
@@ -409,9 +768,9 @@ pub fn handle_mint_nft<'info>(
             punish_bots(
                 CandyError::NoSplAllowlistToken,
                 buyer.to_account_info(),
-                ctx.accounts.candy_machine.to_account_info(),
+                bot_tax_destination.clone(),
                 ctx.accounts.system_program.to_account_info(),
-                BOT_FEE,
+                bot_tax_settings.fee,
             )?;
             return Ok(());
         }
@@ -434,24 +793,127 @@ pub fn handle_mint_nft<'info>(
                 punish_bots(
                     CandyError::MintMismatch,
                     buyer.to_account_info(),
-                    ctx.accounts.candy_machine.to_account_info(),
+                    bot_tax_destination.clone(),
                     ctx.accounts.system_program.to_account_info(),
-                    BOT_FEE,
+                    bot_tax_settings.fee,
                 )?;
                 return Ok(());
             }
 
+            if !recipient.is_signer {
+                return err!(CandyError::RecipientSignatureRequired);
+            }
+
+            let allowlist_token_program = get_remaining_account(
+                candy_machine,
+                ctx.remaining_accounts,
+                RemainingAccounts::SplTokenAllowlistTokenProgram,
+            );
+            assert_is_token_program(&allowlist_token_program)?;
+            assert_owned_by(&allowlist_token_mint, allowlist_token_program.key)?;
+            let allowlist_mint = unpack_token_mint(&allowlist_token_mint)?;
+
             spl_token_burn(TokenBurnParams {
                 mint: allowlist_token_mint.clone(),
                 source: spl_token_allowlist_token_account.clone(),
                 amount: 1,
-                authority: buyer.to_account_info(),
+                decimals: allowlist_mint.decimals,
+                authority: recipient.clone(),
                 authority_signer_seeds: None,
-                token_program: token_program.to_account_info(),
+                token_program: allowlist_token_program,
             })?;
         }
     }
 
+    if let Some(collection_mint) = burn_to_mint_collection {
+        if ctx.remaining_accounts.len() < get_burn_to_mint_accounts_start(candy_machine) + 5 {
+            return err!(CandyError::MissingBurnToMintAccounts);
+        }
+
+        let burn_nft_metadata = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::BurnNftMetadata,
+        );
+        let burn_nft_mint = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::BurnNftMint,
+        );
+        let burn_nft_token_account = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::BurnNftTokenAccount,
+        );
+        let burn_nft_master_edition = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::BurnNftMasterEdition,
+        );
+        let burn_nft_collection_metadata = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::BurnNftCollectionMetadata,
+        );
+
+        assert_owned_by(&burn_nft_metadata, &mpl_token_metadata::id())?;
+        let metadata: Metadata = Metadata::from_account_info(&burn_nft_metadata)?;
+        let is_collection_member = match &metadata.collection {
+            Some(collection) => {
+                collection.verified && cmp_pubkeys(&collection.key, &collection_mint)
+            }
+            None => false,
+        };
+        if !is_collection_member || !cmp_pubkeys(&metadata.mint, burn_nft_mint.key) {
+            return err!(CandyError::InvalidBurnToMintNft);
+        }
+        assert_keys_equal(
+            &find_metadata_account(&collection_mint).0,
+            burn_nft_collection_metadata.key,
+        )?;
+        assert_is_token_account(&burn_nft_token_account, recipient.key, burn_nft_mint.key)?;
+
+        if !recipient.is_signer {
+            return err!(CandyError::RecipientSignatureRequired);
+        }
+
+        msg!(
+            "Burning NFT {} from collection {}.",
+            burn_nft_mint.key(),
+            collection_mint
+        );
+        invoke(
+            &burn_nft(
+                ctx.accounts.token_metadata_program.key(),
+                burn_nft_metadata.key(),
+                recipient.key(),
+                burn_nft_mint.key(),
+                burn_nft_token_account.key(),
+                burn_nft_master_edition.key(),
+                token_program.key(),
+                Some(burn_nft_collection_metadata.key()),
+            ),
+            &[
+                burn_nft_metadata,
+                recipient.clone(),
+                burn_nft_mint,
+                burn_nft_token_account,
+                burn_nft_master_edition,
+                token_program.to_account_info(),
+                burn_nft_collection_metadata,
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    // Only record the mint once no bot tax can end the transaction early, so
+    // taxed attempts don't use up the rate limit.
+    if let Some(mint_rate_limit_settings) = candy_machine.data.mint_rate_limit_settings.clone() {
+        candy_machine
+            .mint_rate_limit_state
+            .record_mint(&mint_rate_limit_settings, clock.slot)?;
+    }
+
     let (wallet_to_use, freeze_pda): (AccountInfo, Option<Account<FreezePda>>) =
         if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX) {
             if let Some(mint) = candy_machine.treasury_mint {
@@ -490,19 +952,39 @@ pub fn handle_mint_nft<'info>(
             (treasury_wallet, None)
         };
 
-    if let Some(mint) = candy_machine.treasury_mint {
+    // Amount paid into the treasury wallet or freeze escrow.
+    let amount_paid = if let Some(mint) = candy_machine.treasury_mint {
         let token_account_info = get_remaining_account(
             candy_machine,
             ctx.remaining_accounts,
             RemainingAccounts::TreasuryTokenAccount,
         );
 
+        let treasury_mint_info = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::TreasuryMint,
+        );
+        let treasury_token_program = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::TreasuryTokenProgram,
+        );
+        assert_keys_equal(&treasury_mint_info.key(), &mint)?;
+        assert_is_token_program(&treasury_token_program)?;
+        assert_owned_by(&treasury_mint_info, treasury_token_program.key)?;
+        let treasury_mint = unpack_token_mint(&treasury_mint_info)?;
+
         let token_account = assert_is_ata(&token_account_info, &buyer.key(), &mint)?;
 
-        if token_account.amount < price {
+        // With a Token-2022 transfer fee the buyer covers the fee on top of
+        // the price so the treasury receives the full price.
+        let amount = get_transfer_amount_with_fee(&treasury_mint_info, price)?;
+
+        if token_account.amount < amount {
             msg!(
                 "The mint price is {} SPL tokens but the buyer only had {}.",
-                price,
+                amount,
                 token_account.amount
             );
             return err!(CandyError::NotEnoughTokens);
@@ -510,12 +992,15 @@ pub fn handle_mint_nft<'info>(
 
         spl_token_transfer(TokenTransferParams {
             source: token_account_info.clone(),
+            mint: treasury_mint_info.clone(),
             destination: wallet_to_use.to_account_info(),
             authority: buyer.to_account_info(),
             authority_signer_seeds: &[],
-            token_program: token_program.to_account_info(),
-            amount: price,
+            token_program: treasury_token_program,
+            amount,
+            decimals: treasury_mint.decimals,
         })?;
+        price
     } else {
         if ctx.accounts.buyer.lamports() < price {
             msg!(
@@ -525,243 +1010,706 @@ pub fn handle_mint_nft<'info>(
             );
             return err!(CandyError::NotEnoughSOL);
         }
+
+        // The referral share is paid to the referral account, and the rest
+        // goes to the treasury. While the freeze escrow holds the funds, the
+        // full price is escrowed so it can be refunded, and no referral share
+        // is paid until the freeze is removed.
+        let referral_fee = match (&mut referral_account, &freeze_pda) {
+            (Some(referral_account), None) => {
+                let referral_fee = (price as u128)
+                    .checked_mul(candy_machine.data.referral_fee_basis_points as u128)
+                    .and_then(|fee| fee.checked_div(10000))
+                    .ok_or(CandyError::NumericalOverflowError)?
+                    as u64;
+
+                invoke(
+                    &system_instruction::transfer(
+                        &ctx.accounts.buyer.key(),
+                        &referral_account.key(),
+                        referral_fee,
+                    ),
+                    &[
+                        ctx.accounts.buyer.to_account_info(),
+                        referral_account.to_account_info(),
+                        ctx.accounts.system_program.to_account_info(),
+                    ],
+                )?;
+
+                referral_account.number_referred = referral_account
+                    .number_referred
+                    .checked_add(1)
+                    .ok_or(CandyError::NumericalOverflowError)?;
+                referral_account.total_earned = referral_account
+                    .total_earned
+                    .checked_add(referral_fee)
+                    .ok_or(CandyError::NumericalOverflowError)?;
+                referral_fee
+            }
+            _ => 0,
+        };
+
         invoke(
-            &system_instruction::transfer(&ctx.accounts.buyer.key(), &wallet_to_use.key(), price),
+            &system_instruction::transfer(
+                &ctx.accounts.buyer.key(),
+                &wallet_to_use.key(),
+                price - referral_fee,
+            ),
             &[
                 ctx.accounts.buyer.to_account_info(),
                 wallet_to_use.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
-    }
+        price - referral_fee
+    };
 
+    // Compressed NFTs have no mint, token account, metadata or edition.
+    let is_compressed = candy_machine.is_compressed();
+    // Programmable NFTs have their mint and token account created by the
+    // token metadata create and mint instructions instead.
+    let is_programmable = candy_machine.data.programmable_nft_settings.is_some();
     // *** BEGIN CREATE ATA ***
-    let mint = &ctx.accounts.mint;
     let buyer_token_account = &ctx.accounts.buyer_token_account;
     let ata_program = &ctx.accounts.ata_program;
     let system_program = &ctx.accounts.system_program;
     let rent = &ctx.accounts.rent;
     let rent_struct = &Rent::from_account_info(&rent.to_account_info())?;
     let min_rent_lamports = rent_struct.minimum_balance(Mint::LEN).max(1);
-    invoke_signed(
-        &system_instruction::create_account(
-            &buyer.key(),
-            &mint.key(),
-            min_rent_lamports,
-            Mint::LEN as u64,
-            &token_program.key(),
-        ),
-        &[
-            buyer.to_account_info(),
-            mint.to_account_info(),
-            system_program.to_account_info(),
-        ],
-        &[],
-    )?;
-
-    invoke_signed(
-        &initialize_mint(
-            &token_program.key(),
-            &mint.key(),
-            &buyer.key(),
-            Some(&buyer.key()),
-            0,
-        )
-        .unwrap(),
-        &[
-            mint.to_account_info(),
-            rent.to_account_info(),
-            token_program.to_account_info(),
-        ],
-        &[],
-    )?;
-
-    make_ata(
-        buyer_token_account.to_account_info(),
-        buyer.to_account_info(),
-        mint.to_account_info(),
-        buyer.to_account_info(),
-        ata_program.to_account_info(),
-        token_program.to_account_info(),
-        system_program.to_account_info(),
-        rent.to_account_info(),
-        &[],
-    )?;
+    if !is_compressed && !is_programmable {
+        let mint = get_nft_account(&ctx.accounts.mint)?;
+        invoke_signed(
+            &system_instruction::create_account(
+                &buyer.key(),
+                &mint.key(),
+                min_rent_lamports,
+                Mint::LEN as u64,
+                &token_program.key(),
+            ),
+            &[
+                buyer.to_account_info(),
+                mint.to_account_info(),
+                system_program.to_account_info(),
+            ],
+            &[],
+        )?;
 
-    invoke_signed(
-        &mint_to(
-            &token_program.key(),
-            &mint.key(),
-            &buyer_token_account.key(),
-            &buyer.key(),
+        invoke_signed(
+            &initialize_mint(
+                &token_program.key(),
+                &mint.key(),
+                &buyer.key(),
+                Some(&buyer.key()),
+                0,
+            )
+            .unwrap(),
+            &[
+                mint.to_account_info(),
+                rent.to_account_info(),
+                token_program.to_account_info(),
+            ],
             &[],
-            1,
-        )
-        .unwrap(),
-        &[
-            mint.to_account_info(),
+        )?;
+
+        make_ata(
             buyer_token_account.to_account_info(),
+            recipient.clone(),
+            mint.to_account_info(),
             buyer.to_account_info(),
+            ata_program.to_account_info(),
             token_program.to_account_info(),
-        ],
-        &[],
-    )?;
-    // *** END CREATE ATA ***
-
-    // Sequential minting is only allowed in the premint phase for now.
-    let config_line_initial_index =
-        if candy_machine.data.sequential_mint_order_enabled && mint_phase == MintPhase::Premint {
-            candy_machine.items_redeemed as usize
-        } else {
-            let data = recent_slothashes.data.borrow();
-            let most_recent = array_ref![data, 12, 8];
-
-            let index = u64::from_le_bytes(*most_recent);
-            index
-                .checked_rem(candy_machine.data.items_available)
-                .ok_or(CandyError::NumericalOverflowError)? as usize
-        };
-
-    let config_line = get_config_line(
-        candy_machine,
-        config_line_initial_index,
-        candy_machine.items_redeemed,
-    )?;
+            system_program.to_account_info(),
+            rent.to_account_info(),
+            &[],
+        )?;
 
-    candy_machine.items_redeemed = candy_machine
-        .items_redeemed
-        .checked_add(1)
-        .ok_or(CandyError::NumericalOverflowError)?;
+        invoke_signed(
+            &mint_to(
+                &token_program.key(),
+                &mint.key(),
+                &buyer_token_account.key(),
+                &buyer.key(),
+                &[],
+                1,
+            )
+            .unwrap(),
+            &[
+                mint.to_account_info(),
+                buyer_token_account.to_account_info(),
+                buyer.to_account_info(),
+                token_program.to_account_info(),
+            ],
+            &[],
+        )?;
+    }
+    // *** END CREATE ATA ***
 
     let cm_key = candy_machine.key();
     let authority_seeds = [PREFIX.as_bytes(), cm_key.as_ref(), &[creator_bump]];
 
-    // The original creators list only includes the 1 cm creator, which gets
-    // removed after minting in the update instruction below.
-    let creators_for_mint_ix: Vec<mpl_token_metadata::state::Creator> =
-        vec![mpl_token_metadata::state::Creator {
-            address: candy_machine_creator.key(),
-            verified: true,
-            share: 100,
-        }];
-
-    let metadata_infos = vec![
-        ctx.accounts.metadata.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
-        ctx.accounts.buyer.to_account_info(),
-        ctx.accounts.buyer.to_account_info(),
-        ctx.accounts.token_metadata_program.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-        ctx.accounts.rent.to_account_info(),
-        candy_machine_creator.to_account_info(),
-    ];
-
-    let master_edition_infos = vec![
-        ctx.accounts.master_edition.to_account_info(),
-        ctx.accounts.mint.to_account_info(),
-        ctx.accounts.buyer.to_account_info(),
-        ctx.accounts.buyer.to_account_info(),
-        ctx.accounts.metadata.to_account_info(),
-        ctx.accounts.token_metadata_program.to_account_info(),
-        ctx.accounts.token_program.to_account_info(),
-        ctx.accounts.system_program.to_account_info(),
-        ctx.accounts.rent.to_account_info(),
-        candy_machine_creator.to_account_info(),
-    ];
-
-    invoke_signed(
-        &create_metadata_accounts_v3(
-            ctx.accounts.token_metadata_program.key(),
-            ctx.accounts.metadata.key(),
-            ctx.accounts.mint.key(),
-            ctx.accounts.buyer.key(),
-            ctx.accounts.buyer.key(),
-            candy_machine_creator.key(),
-            config_line.name.clone(),
-            candy_machine.data.symbol.clone(),
-            config_line.uri.clone(),
-            Some(creators_for_mint_ix),
-            candy_machine.data.seller_fee_basis_points,
-            true,
-            candy_machine.data.is_mutable,
-            None,
-            None,
-            None,
-        ),
-        metadata_infos.as_slice(),
-        &[&authority_seeds],
-    )?;
-    invoke_signed(
-        &create_master_edition_v3(
-            ctx.accounts.token_metadata_program.key(),
-            ctx.accounts.master_edition.key(),
-            ctx.accounts.mint.key(),
-            candy_machine_creator.key(),
-            ctx.accounts.buyer.key(),
-            ctx.accounts.metadata.key(),
-            ctx.accounts.buyer.key(),
-            Some(candy_machine.data.max_supply),
-        ),
-        master_edition_infos.as_slice(),
-        &[&authority_seeds],
-    )?;
-
-    let creators: Vec<mpl_token_metadata::state::Creator> = candy_machine
-        .data
-        .creators
-        .iter()
-        .map(|creator| mpl_token_metadata::state::Creator {
-            address: creator.address,
-            verified: false,
-            share: creator.share,
-        })
-        .collect();
-
-    let update_data = DataV2 {
-        name: config_line.name,
-        symbol: candy_machine.data.symbol.clone(),
-        uri: config_line.uri,
-        seller_fee_basis_points: candy_machine.data.seller_fee_basis_points,
-        creators: Some(creators),
-        collection: None,
-        uses: None,
-    };
+    if let Some(open_edition_settings) = &candy_machine.data.open_edition_settings {
+        let nft_metadata = get_nft_account(&ctx.accounts.metadata)?;
+        let nft_mint = get_nft_account(&ctx.accounts.mint)?;
+        let nft_master_edition = get_nft_account(&ctx.accounts.master_edition)?;
+        let master_edition_mint = open_edition_settings.master_edition_mint;
+        let master_edition_metadata = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::MasterEditionMetadata,
+        );
+        let master_edition = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::MasterEdition,
+        );
+        let master_edition_token_account = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::MasterEditionTokenAccount,
+        );
+        let edition_marker = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::EditionMarker,
+        );
+
+        // Editions are numbered in mint order, starting from 1.
+        let edition = candy_machine
+            .items_redeemed
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+
+        assert_open_edition_accounts(
+            &master_edition_mint,
+            edition,
+            &master_edition_metadata,
+            &master_edition,
+            &edition_marker,
+        )?;
+        assert_is_ata(
+            &master_edition_token_account,
+            &candy_machine_creator.key(),
+            &master_edition_mint,
+        )?;
+
+        candy_machine.items_redeemed = edition;
 
-    let is_mutable = if !candy_machine.data.is_mutable {
-        Some(false)
+        msg!(
+            "Printing edition {} from master edition mint {}.",
+            edition,
+            master_edition_mint
+        );
+        invoke_signed(
+            &mint_new_edition_from_master_edition_via_token(
+                ctx.accounts.token_metadata_program.key(),
+                nft_metadata.key(),
+                nft_master_edition.key(),
+                master_edition.key(),
+                nft_mint.key(),
+                buyer.key(),
+                buyer.key(),
+                candy_machine_creator.key(),
+                master_edition_token_account.key(),
+                candy_machine.creator_authority,
+                master_edition_metadata.key(),
+                master_edition_mint,
+                edition,
+            ),
+            &[
+                nft_metadata.to_account_info(),
+                nft_master_edition.to_account_info(),
+                master_edition,
+                nft_mint.to_account_info(),
+                edition_marker,
+                buyer.to_account_info(),
+                candy_machine_creator.to_account_info(),
+                master_edition_token_account,
+                ctx.accounts.creator_authority.to_account_info(),
+                master_edition_metadata,
+                ctx.accounts.token_metadata_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+            ],
+            &[&authority_seeds],
+        )?;
     } else {
-        None
-    };
+        let config_line_search =
+            get_config_line_search(candy_machine, &mint_phase, recent_slothashes)?;
 
-    // Now update NFT creators and update_authority.
-    invoke_signed(
-        &update_metadata_accounts_v2(
-            ctx.accounts.token_metadata_program.key(),
-            ctx.accounts.metadata.key(),
-            candy_machine_creator.key(),
-            Some(candy_machine.creator_authority),
-            Some(update_data),
-            Some(true),
-            is_mutable,
-        ),
-        &[
-            ctx.accounts.token_metadata_program.to_account_info(),
-            ctx.accounts.metadata.to_account_info(),
-            candy_machine_creator.to_account_info(),
-        ],
-        &[&authority_seeds],
-    )?;
+        let (config_line, config_line_index) = get_config_line(
+            candy_machine,
+            &config_line_search,
+            candy_machine.items_redeemed,
+        )?;
+
+        candy_machine.items_redeemed = candy_machine
+            .items_redeemed
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+
+        // Collab drops can override the creators and royalties of each config
+        // line, in which case those are used instead of the candy machine ones.
+        let creator_override = if candy_machine.data.creator_overrides_enabled {
+            let (creator_overrides_key, _) = find_creator_overrides_pda(&candy_pubkey);
+            let creator_overrides_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &creator_overrides_key))
+                .ok_or(CandyError::MissingCreatorOverridesAccount)?;
+            get_creator_override(creator_overrides_info, config_line_index)?
+        } else {
+            None
+        };
+        let (seller_fee_basis_points, candy_creators) = match creator_override {
+            Some(creator_override) => (
+                creator_override.seller_fee_basis_points,
+                creator_override.creators,
+            ),
+            None => (
+                candy_machine.data.seller_fee_basis_points,
+                candy_machine.data.creators.clone(),
+            ),
+        };
+
+        if is_compressed {
+            let is_creator_authority_verified = ctx.accounts.creator_authority.is_signer;
+            if is_creator_authority_verified
+                && !candy_creators
+                    .iter()
+                    .any(|creator| cmp_pubkeys(&creator.address, &candy_machine.creator_authority))
+            {
+                return err!(CandyError::CreatorAuthorityNotACreator);
+            }
+            let creators: Vec<BubblegumCreator> = candy_creators
+                .iter()
+                .map(|creator| BubblegumCreator {
+                    address: creator.address,
+                    verified: is_creator_authority_verified
+                        && cmp_pubkeys(&creator.address, &candy_machine.creator_authority),
+                    share: creator.share,
+                })
+                .collect();
+
+            msg!("Minting compressed NFT {}.", config_line.name);
+            mint_compressed_nft(
+                candy_machine,
+                &candy_pubkey,
+                ctx.remaining_accounts,
+                &recipient,
+                &buyer.to_account_info(),
+                &ctx.accounts.creator_authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                MetadataArgs {
+                    name: config_line.name,
+                    symbol: candy_machine.data.symbol.clone(),
+                    uri: config_line.uri,
+                    seller_fee_basis_points,
+                    primary_sale_happened: true,
+                    is_mutable: candy_machine.data.is_mutable,
+                    edition_nonce: None,
+                    token_standard: Some(TokenStandard::NonFungible),
+                    collection: None,
+                    uses: None,
+                    token_program_version: TokenProgramVersion::Original,
+                    creators,
+                },
+            )?;
+        } else {
+            let metadata = get_nft_account(&ctx.accounts.metadata)?;
+            let mint = get_nft_account(&ctx.accounts.mint)?;
+            let master_edition = get_nft_account(&ctx.accounts.master_edition)?;
+
+            // The original creators list only includes the 1 cm creator, which gets
+            // removed after minting in the update instruction below.
+            let creators_for_mint_ix: Vec<mpl_token_metadata::state::Creator> =
+                vec![mpl_token_metadata::state::Creator {
+                    address: candy_machine_creator.key(),
+                    verified: true,
+                    share: 100,
+                }];
+
+            let metadata_infos = vec![
+                metadata.to_account_info(),
+                mint.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+                candy_machine_creator.to_account_info(),
+            ];
+
+            let master_edition_infos = vec![
+                master_edition.to_account_info(),
+                mint.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+                ctx.accounts.buyer.to_account_info(),
+                metadata.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.rent.to_account_info(),
+                candy_machine_creator.to_account_info(),
+            ];
+
+            if let Some(programmable_nft_settings) = &candy_machine.data.programmable_nft_settings {
+                let (token_record, authorization_rules) = get_programmable_nft_accounts(
+                    candy_machine,
+                    ctx.remaining_accounts,
+                    &mint.key(),
+                    &buyer_token_account.key(),
+                )?;
+                let print_supply = if candy_machine.data.max_supply == 0 {
+                    PrintSupply::Zero
+                } else {
+                    PrintSupply::Limited(candy_machine.data.max_supply)
+                };
+
+                // The NFT stays mutable until the update instruction below
+                // sets the final creators and update authority.
+                let create_ix = CreateBuilder::new()
+                    .metadata(metadata.key())
+                    .master_edition(master_edition.key())
+                    .mint(mint.key())
+                    .authority(candy_machine_creator.key())
+                    .payer(buyer.key())
+                    .update_authority(candy_machine_creator.key())
+                    .system_program(system_program.key())
+                    .sysvar_instructions(instruction_sysvar_account.key())
+                    .spl_token_program(token_program.key())
+                    .initialize_mint(true)
+                    .update_authority_as_signer(true)
+                    .build(CreateArgs::V1 {
+                        asset_data: AssetData {
+                            name: config_line.name.clone(),
+                            symbol: candy_machine.data.symbol.clone(),
+                            uri: config_line.uri.clone(),
+                            seller_fee_basis_points,
+                            creators: Some(creators_for_mint_ix),
+                            primary_sale_happened: true,
+                            is_mutable: true,
+                            token_standard:
+                                mpl_token_metadata::state::TokenStandard::ProgrammableNonFungible,
+                            collection: None,
+                            uses: None,
+                            collection_details: None,
+                            rule_set: programmable_nft_settings.rule_set,
+                        },
+                        decimals: Some(0),
+                        print_supply: Some(print_supply),
+                    })
+                    .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                    .instruction();
+                invoke_signed(
+                    &create_ix,
+                    &[
+                        metadata.to_account_info(),
+                        master_edition.to_account_info(),
+                        mint.to_account_info(),
+                        candy_machine_creator.to_account_info(),
+                        buyer.to_account_info(),
+                        system_program.to_account_info(),
+                        instruction_sysvar_account.to_account_info(),
+                        token_program.to_account_info(),
+                        ctx.accounts.token_metadata_program.to_account_info(),
+                    ],
+                    &[&authority_seeds],
+                )?;
+
+                let mut mint_builder = MintBuilder::new();
+                mint_builder
+                    .token(buyer_token_account.key())
+                    .token_owner(recipient.key())
+                    .metadata(metadata.key())
+                    .master_edition(master_edition.key())
+                    .token_record(token_record.key())
+                    .mint(mint.key())
+                    .authority(candy_machine_creator.key())
+                    .payer(buyer.key())
+                    .system_program(system_program.key())
+                    .sysvar_instructions(instruction_sysvar_account.key())
+                    .spl_token_program(token_program.key())
+                    .spl_ata_program(ata_program.key());
+                let mut mint_infos = vec![
+                    buyer_token_account.to_account_info(),
+                    recipient.clone(),
+                    metadata.to_account_info(),
+                    master_edition.to_account_info(),
+                    token_record,
+                    mint.to_account_info(),
+                    candy_machine_creator.to_account_info(),
+                    buyer.to_account_info(),
+                    system_program.to_account_info(),
+                    instruction_sysvar_account.to_account_info(),
+                    token_program.to_account_info(),
+                    ata_program.to_account_info(),
+                    ctx.accounts.token_metadata_program.to_account_info(),
+                ];
+                if let Some((authorization_rules_program, authorization_rules)) =
+                    authorization_rules
+                {
+                    mint_builder
+                        .authorization_rules_program(authorization_rules_program.key())
+                        .authorization_rules(authorization_rules.key());
+                    mint_infos.push(authorization_rules_program);
+                    mint_infos.push(authorization_rules);
+                }
+                let mint_ix = mint_builder
+                    .build(MintArgs::V1 {
+                        amount: 1,
+                        authorization_data: None,
+                    })
+                    .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                    .instruction();
+                invoke_signed(&mint_ix, mint_infos.as_slice(), &[&authority_seeds])?;
+            } else {
+                invoke_signed(
+                    &create_metadata_accounts_v3(
+                        ctx.accounts.token_metadata_program.key(),
+                        metadata.key(),
+                        mint.key(),
+                        ctx.accounts.buyer.key(),
+                        ctx.accounts.buyer.key(),
+                        candy_machine_creator.key(),
+                        config_line.name.clone(),
+                        candy_machine.data.symbol.clone(),
+                        config_line.uri.clone(),
+                        Some(creators_for_mint_ix),
+                        seller_fee_basis_points,
+                        true,
+                        candy_machine.data.is_mutable || candy_machine.data.on_chain_reveal_enabled,
+                        None,
+                        None,
+                        None,
+                    ),
+                    metadata_infos.as_slice(),
+                    &[&authority_seeds],
+                )?;
+                invoke_signed(
+                    &create_master_edition_v3(
+                        ctx.accounts.token_metadata_program.key(),
+                        master_edition.key(),
+                        mint.key(),
+                        candy_machine_creator.key(),
+                        ctx.accounts.buyer.key(),
+                        metadata.key(),
+                        ctx.accounts.buyer.key(),
+                        Some(candy_machine.data.max_supply),
+                    ),
+                    master_edition_infos.as_slice(),
+                    &[&authority_seeds],
+                )?;
+            }
+
+            let creators: Vec<mpl_token_metadata::state::Creator> = candy_creators
+                .iter()
+                .map(|creator| mpl_token_metadata::state::Creator {
+                    address: creator.address,
+                    verified: false,
+                    share: creator.share,
+                })
+                .collect();
+
+            let update_data = DataV2 {
+                name: config_line.name,
+                symbol: candy_machine.data.symbol.clone(),
+                uri: config_line.uri,
+                seller_fee_basis_points,
+                creators: Some(creators),
+                collection: None,
+                uses: None,
+            };
+
+            // NFTs revealed on-chain keep the candy machine creator as update
+            // authority, and stay mutable, until the reveal_nft crank runs.
+            let (update_authority, is_mutable) = if candy_machine.data.on_chain_reveal_enabled {
+                (candy_machine_creator.key(), None)
+            } else if !candy_machine.data.is_mutable {
+                (candy_machine.creator_authority, Some(false))
+            } else {
+                (candy_machine.creator_authority, None)
+            };
+
+            // Now update NFT creators and update_authority.
+            if is_programmable {
+                let update_ix = UpdateBuilder::new()
+                    .authority(candy_machine_creator.key())
+                    .metadata(metadata.key())
+                    .mint(mint.key())
+                    .edition(master_edition.key())
+                    .payer(buyer.key())
+                    .system_program(system_program.key())
+                    .sysvar_instructions(instruction_sysvar_account.key())
+                    .build(UpdateArgs::V1 {
+                        new_update_authority: Some(update_authority),
+                        data: Some(Data {
+                            name: update_data.name,
+                            symbol: update_data.symbol,
+                            uri: update_data.uri,
+                            seller_fee_basis_points: update_data.seller_fee_basis_points,
+                            creators: update_data.creators,
+                        }),
+                        primary_sale_happened: None,
+                        is_mutable,
+                        collection: CollectionToggle::None,
+                        collection_details: CollectionDetailsToggle::None,
+                        uses: UsesToggle::None,
+                        rule_set: RuleSetToggle::None,
+                        authorization_data: None,
+                    })
+                    .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                    .instruction();
+                invoke_signed(
+                    &update_ix,
+                    &[
+                        candy_machine_creator.to_account_info(),
+                        metadata.to_account_info(),
+                        mint.to_account_info(),
+                        master_edition.to_account_info(),
+                        buyer.to_account_info(),
+                        system_program.to_account_info(),
+                        instruction_sysvar_account.to_account_info(),
+                        ctx.accounts.token_metadata_program.to_account_info(),
+                    ],
+                    &[&authority_seeds],
+                )?;
+            } else {
+                invoke_signed(
+                    &update_metadata_accounts_v2(
+                        ctx.accounts.token_metadata_program.key(),
+                        metadata.key(),
+                        candy_machine_creator.key(),
+                        Some(update_authority),
+                        Some(update_data),
+                        Some(true),
+                        is_mutable,
+                    ),
+                    &[
+                        ctx.accounts.token_metadata_program.to_account_info(),
+                        metadata.to_account_info(),
+                        candy_machine_creator.to_account_info(),
+                    ],
+                    &[&authority_seeds],
+                )?;
+            }
+
+            // Opt-in: when the creator_authority co-signs the mint transaction we can
+            // verify it as a creator right away instead of requiring a separate
+            // sign_metadata transaction later.
+            if ctx.accounts.creator_authority.is_signer {
+                let is_creator = candy_creators
+                    .iter()
+                    .any(|creator| cmp_pubkeys(&creator.address, &candy_machine.creator_authority));
+                if !is_creator {
+                    return err!(CandyError::CreatorAuthorityNotACreator);
+                }
+
+                invoke(
+                    &sign_metadata(
+                        ctx.accounts.token_metadata_program.key(),
+                        metadata.key(),
+                        candy_machine.creator_authority,
+                    ),
+                    &[
+                        metadata.to_account_info(),
+                        ctx.accounts.creator_authority.to_account_info(),
+                        ctx.accounts.token_metadata_program.to_account_info(),
+                    ],
+                )?;
+            }
+        }
+    }
+
+    if is_collection_active && !is_legacy_collection_mint {
+        let metadata = get_nft_account(&ctx.accounts.metadata)?;
+        let collection_pda_info = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::CollectionPda,
+        );
+        let collection_mint = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::CollectionMint,
+        );
+        let collection_metadata = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::CollectionMetadata,
+        );
+        let collection_master_edition = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::CollectionMasterEdition,
+        );
+        let collection_authority_record = get_remaining_account(
+            candy_machine,
+            ctx.remaining_accounts,
+            RemainingAccounts::CollectionAuthorityRecord,
+        );
+
+        let seeds = [CollectionPda::PREFIX.as_bytes(), candy_pubkey.as_ref()];
+        let collection_bump = assert_derivation(&crate::id(), &collection_pda_info, &seeds)
+            .map_err(|_| CandyError::MismatchedCollectionPda)?;
+        let collection_pda: Account<CollectionPda> = Account::try_from(&collection_pda_info)?;
+        if !cmp_pubkeys(&collection_pda.mint, &collection_mint.key()) {
+            return err!(CandyError::MismatchedCollectionMint);
+        }
+
+        let collection_signer_seeds = [
+            CollectionPda::PREFIX.as_bytes(),
+            candy_pubkey.as_ref(),
+            &[collection_bump],
+        ];
+        msg!(
+            "About to set and verify collection {}.",
+            collection_mint.key()
+        );
+        invoke_signed(
+            &set_and_verify_collection(
+                ctx.accounts.token_metadata_program.key(),
+                metadata.key(),
+                collection_pda_info.key(),
+                buyer.key(),
+                candy_machine.creator_authority,
+                collection_mint.key(),
+                collection_metadata.key(),
+                collection_master_edition.key(),
+                Some(collection_authority_record.key()),
+            ),
+            &[
+                metadata.to_account_info(),
+                collection_pda_info,
+                buyer.to_account_info(),
+                ctx.accounts.creator_authority.to_account_info(),
+                collection_mint,
+                collection_metadata,
+                collection_master_edition,
+                collection_authority_record,
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            &[&collection_signer_seeds],
+        )?;
+    }
 
     if let Some(mut freeze_pda) = freeze_pda {
         msg!("About to freeze NFT.");
-        let mint_pubkey = ctx.accounts.mint.key();
+        let mint = get_nft_account(&ctx.accounts.mint)?;
+        let metadata = get_nft_account(&ctx.accounts.metadata)?;
+        let master_edition = get_nft_account(&ctx.accounts.master_edition)?;
+        let mint_pubkey = mint.key();
         let nft_token_account_info = get_remaining_account(
             candy_machine,
             ctx.remaining_accounts,
             RemainingAccounts::BuyerNftMintTokenAccount,
         );
-        assert_is_ata(&nft_token_account_info, &buyer.key(), &mint_pubkey)?;
+        assert_is_ata(&nft_token_account_info, recipient.key, &mint_pubkey)?;
+        // Delegating the NFT to the freeze PDA needs the owner's signature.
+        if !recipient.is_signer {
+            return err!(CandyError::RecipientSignatureRequired);
+        }
         let seeds: &[&[u8]] = &[FreezePda::PREFIX.as_bytes(), candy_pubkey.as_ref()];
         let (expected_freeze_key, freeze_bump) = Pubkey::find_program_address(seeds, &crate::id());
         assert_keys_equal(&expected_freeze_key, &freeze_pda.key())?;
@@ -789,46 +1737,195 @@ pub fn handle_mint_nft<'info>(
             freeze_pda.mint_start = Some(clock.unix_timestamp);
         }
 
+        // Record the escrowed amount so it can be refunded if an
+        // all-or-nothing sale fails.
+        if candy_machine.data.min_items_to_succeed.is_some() {
+            let (refund_receipt_key, refund_receipt_bump) =
+                find_refund_receipt_pda(&candy_pubkey, &mint_pubkey);
+            let refund_receipt_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &refund_receipt_key))
+                .ok_or(CandyError::MissingRefundReceiptAccount)?;
+            let signer_seeds = [
+                RefundReceipt::PREFIX.as_bytes(),
+                &candy_pubkey.to_bytes(),
+                &mint_pubkey.to_bytes(),
+                &[refund_receipt_bump],
+            ];
+
+            create_or_allocate_account_raw(
+                *ctx.program_id,
+                refund_receipt_info,
+                &ctx.accounts.system_program,
+                buyer,
+                RefundReceipt::SIZE,
+                &signer_seeds,
+            )?;
+            write_anchor_account_discriminator::<RefundReceipt>(refund_receipt_info)?;
+
+            let mut refund_receipt: Account<RefundReceipt> =
+                Account::try_from(refund_receipt_info)?;
+            refund_receipt.candy_machine = candy_pubkey;
+            refund_receipt.mint = mint_pubkey;
+            refund_receipt.amount = amount_paid;
+            // This re-serializes the account to persist the changes.
+            refund_receipt.exit(&crate::id())?;
+        }
+
+        // With a rolling freeze, each NFT's lock starts when it's minted.
+        if candy_machine.has_rolling_freeze() {
+            let (freeze_record_key, freeze_record_bump) =
+                find_freeze_record_pda(&candy_pubkey, &mint_pubkey);
+            let freeze_record_info = ctx
+                .remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &freeze_record_key))
+                .ok_or(CandyError::MissingFreezeRecordAccount)?;
+            let signer_seeds = [
+                FreezeRecord::PREFIX.as_bytes(),
+                &candy_pubkey.to_bytes(),
+                &mint_pubkey.to_bytes(),
+                &[freeze_record_bump],
+            ];
+
+            create_or_allocate_account_raw(
+                *ctx.program_id,
+                freeze_record_info,
+                &ctx.accounts.system_program,
+                buyer,
+                FreezeRecord::SIZE,
+                &signer_seeds,
+            )?;
+            write_anchor_account_discriminator::<FreezeRecord>(freeze_record_info)?;
+
+            let mut freeze_record: Account<FreezeRecord> = Account::try_from(freeze_record_info)?;
+            freeze_record.candy_machine = candy_pubkey;
+            freeze_record.mint = mint_pubkey;
+            freeze_record.frozen_at = clock.unix_timestamp;
+            // This re-serializes the account to persist the changes.
+            freeze_record.exit(&crate::id())?;
+
+            freeze_pda.last_frozen_at = Some(clock.unix_timestamp);
+        }
+
         let freeze_seeds = [
             FreezePda::PREFIX.as_bytes(),
             candy_pubkey.as_ref(),
             &[freeze_bump],
         ];
-        let mut freeze_ix = freeze_delegated_account(
-            mpl_token_metadata::ID,
-            freeze_pda.key(),
-            nft_token_account_info.key(),
-            ctx.accounts.master_edition.key(),
-            ctx.accounts.mint.key(),
-        );
-        // token metadata ix is sorta bad, so this line fixes it to enable freeze without marking signer as mutable
-        freeze_ix.accounts[0] = AccountMeta::new_readonly(freeze_pda.key(), true);
-
-        invoke(
-            &approve(
-                &spl_token::ID,
+        if is_programmable {
+            // Programmable NFTs are always frozen, so they're locked in their
+            // token record by the freeze PDA as utility delegate instead.
+            let (token_record, authorization_rules) = get_programmable_nft_accounts(
+                candy_machine,
+                ctx.remaining_accounts,
+                &mint_pubkey,
                 &nft_token_account_info.key(),
-                &freeze_pda.key(),
-                &buyer.key(),
-                &[],
-                1,
-            )?,
-            &[
-                nft_token_account_info.to_account_info(),
-                freeze_pda.to_account_info(),
-                buyer.to_account_info(),
-            ],
-        )?;
-        invoke_signed(
-            &freeze_ix,
-            &[
+            )?;
+            let mut delegate_builder = DelegateBuilder::new();
+            delegate_builder
+                .delegate(freeze_pda.key())
+                .metadata(metadata.key())
+                .master_edition(master_edition.key())
+                .token_record(token_record.key())
+                .mint(mint_pubkey)
+                .token(nft_token_account_info.key())
+                .authority(recipient.key())
+                .payer(buyer.key())
+                .system_program(ctx.accounts.system_program.key())
+                .sysvar_instructions(instruction_sysvar_account.key())
+                .spl_token_program(spl_token::ID);
+            let mut lock_builder = LockBuilder::new();
+            lock_builder
+                .authority(freeze_pda.key())
+                .token_owner(recipient.key())
+                .token(nft_token_account_info.key())
+                .mint(mint_pubkey)
+                .metadata(metadata.key())
+                .edition(master_edition.key())
+                .token_record(token_record.key())
+                .payer(buyer.key())
+                .system_program(ctx.accounts.system_program.key())
+                .sysvar_instructions(instruction_sysvar_account.key())
+                .spl_token_program(spl_token::ID);
+            let mut lock_infos = vec![
                 freeze_pda.to_account_info(),
+                recipient.clone(),
                 nft_token_account_info.to_account_info(),
-                ctx.accounts.master_edition.to_account_info(),
-                ctx.accounts.mint.to_account_info(),
-            ],
-            &[&freeze_seeds],
-        )?;
+                mint.to_account_info(),
+                metadata.to_account_info(),
+                master_edition.to_account_info(),
+                token_record,
+                buyer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                instruction_sysvar_account.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ];
+            if let Some((authorization_rules_program, authorization_rules)) = authorization_rules {
+                delegate_builder
+                    .authorization_rules_program(authorization_rules_program.key())
+                    .authorization_rules(authorization_rules.key());
+                lock_builder
+                    .authorization_rules_program(authorization_rules_program.key())
+                    .authorization_rules(authorization_rules.key());
+                lock_infos.push(authorization_rules_program);
+                lock_infos.push(authorization_rules);
+            }
+
+            let delegate_ix = delegate_builder
+                .build(DelegateArgs::UtilityV1 {
+                    amount: 1,
+                    authorization_data: None,
+                })
+                .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                .instruction();
+            let lock_ix = lock_builder
+                .build(LockArgs::V1 {
+                    authorization_data: None,
+                })
+                .map_err(|_| CandyError::TokenMetadataInstructionBuildFailed)?
+                .instruction();
+            invoke(&delegate_ix, lock_infos.as_slice())?;
+            invoke_signed(&lock_ix, lock_infos.as_slice(), &[&freeze_seeds])?;
+        } else {
+            let mut freeze_ix = freeze_delegated_account(
+                mpl_token_metadata::ID,
+                freeze_pda.key(),
+                nft_token_account_info.key(),
+                master_edition.key(),
+                mint.key(),
+            );
+            // token metadata ix is sorta bad, so this line fixes it to enable freeze without marking signer as mutable
+            freeze_ix.accounts[0] = AccountMeta::new_readonly(freeze_pda.key(), true);
+
+            invoke(
+                &approve(
+                    &spl_token::ID,
+                    &nft_token_account_info.key(),
+                    &freeze_pda.key(),
+                    recipient.key,
+                    &[],
+                    1,
+                )?,
+                &[
+                    nft_token_account_info.to_account_info(),
+                    freeze_pda.to_account_info(),
+                    recipient.clone(),
+                ],
+            )?;
+            invoke_signed(
+                &freeze_ix,
+                &[
+                    freeze_pda.to_account_info(),
+                    nft_token_account_info.to_account_info(),
+                    master_edition.to_account_info(),
+                    mint.to_account_info(),
+                ],
+                &[&freeze_seeds],
+            )?;
+        }
         freeze_pda.exit(&crate::id())?;
     }
 
@@ -864,13 +1961,112 @@ pub fn handle_mint_nft<'info>(
         buyer_info_account.exit(&crate::id())?;
     }
 
+    if let Some(referral_account) = referral_account {
+        // This re-serializes the account to persist the changes.
+        referral_account.exit(&crate::id())?;
+    }
+
+    if let Some((mut drop, mut drop_buyer_info)) = drop_accounts {
+        drop.items_minted = drop
+            .items_minted
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+        drop_buyer_info.number_bought = drop_buyer_info
+            .number_bought
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+
+        // This re-serializes the accounts to persist the changes.
+        drop.exit(&crate::id())?;
+        drop_buyer_info.exit(&crate::id())?;
+    }
+
+    if let Some(mut voucher_nonce) = voucher_nonce {
+        voucher_nonce.number_minted = voucher_nonce
+            .number_minted
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+
+        // This re-serializes the account to persist the changes.
+        voucher_nonce.exit(&crate::id())?;
+    }
+
     Ok(())
 }
 
+/// Loads the drop account linked to the candy machine and the buyer info
+/// account of the recipient for the drop, creating the latter on the first
+/// mint, and checks the drop supply cap and limit per address.
+fn get_drop_accounts<'info>(
+    candy_machine: &Account<'info, CandyMachine>,
+    drop_key: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    recipient: &AccountInfo<'info>,
+    buyer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<(
+    Account<'info, DropAccount>,
+    Account<'info, DropBuyerInfoAccount>,
+)> {
+    let drop_info = remaining_accounts
+        .iter()
+        .find(|account| cmp_pubkeys(account.key, drop_key))
+        .ok_or(CandyError::MissingDropAccount)?;
+    let drop: Account<DropAccount> = Account::try_from(drop_info)?;
+    // Only the candy machine authority can link its candy machines to a drop.
+    if !cmp_pubkeys(&drop.authority, &candy_machine.bullistic_authority) {
+        return err!(CandyError::InvalidDropAccount);
+    }
+    if drop.supply_cap > 0 && drop.items_minted >= drop.supply_cap {
+        return err!(CandyError::DropSoldOut);
+    }
+
+    if drop.limit_per_address > 0 && !recipient.is_signer {
+        return err!(CandyError::RecipientSignatureRequired);
+    }
+
+    let (drop_buyer_info_key, drop_buyer_info_bump) =
+        find_drop_buyer_info_pda(drop_key, recipient.key);
+    let drop_buyer_info_info = remaining_accounts
+        .iter()
+        .find(|account| cmp_pubkeys(account.key, &drop_buyer_info_key))
+        .ok_or(CandyError::MissingDropBuyerInfoAccount)?;
+    if drop_buyer_info_info.data_is_empty() {
+        let signer_seeds = [
+            DropBuyerInfoAccount::PREFIX.as_bytes(),
+            &drop_key.to_bytes(),
+            &recipient.key().to_bytes(),
+            &[drop_buyer_info_bump],
+        ];
+
+        create_or_allocate_account_raw(
+            crate::id(),
+            drop_buyer_info_info,
+            system_program,
+            buyer,
+            DropBuyerInfoAccount::SIZE,
+            &signer_seeds,
+        )?;
+
+        write_anchor_account_discriminator::<DropBuyerInfoAccount>(drop_buyer_info_info)?;
+    }
+
+    let drop_buyer_info: Account<DropBuyerInfoAccount> = Account::try_from(drop_buyer_info_info)?;
+    if drop.limit_per_address > 0
+        && !is_omni_minter(recipient.key, candy_machine)
+        && drop_buyer_info.number_bought >= drop.limit_per_address
+    {
+        return err!(CandyError::DropBuyLimitPerAddressExceeded);
+    }
+
+    Ok((drop, drop_buyer_info))
+}
+
 pub fn get_good_index(
     arr: &mut RefMut<&mut [u8]>,
     items_available: usize,
     index: usize,
+    range: &Range<usize>,
     pos: bool,
 ) -> Result<(usize, bool)> {
     let mut index_to_use = index;
@@ -885,7 +2081,7 @@ pub fn get_good_index(
             .ok_or(CandyError::NumericalOverflowError)?
         + 4;
 
-    while taken > 0 && index_to_use < items_available {
+    while taken > 0 && index_to_use < range.end {
         let my_position_in_vec = bit_mask_vec_start
             + index_to_use
                 .checked_div(8)
@@ -899,7 +2095,7 @@ pub fn get_good_index(
                 if pos {
                     index_to_use += eight_remainder;
                 } else {
-                    if index_to_use < 8 {
+                    if index_to_use - (8 - eight_remainder) <= range.start {
                         break;
                     }
                     index_to_use -= reversed;
@@ -922,7 +2118,7 @@ pub fn get_good_index(
                     if pos {
                         index_to_use += 1;
                     } else {
-                        if index_to_use == 0 {
+                        if index_to_use <= range.start {
                             break;
                         }
                         index_to_use -= 1;
@@ -939,31 +2135,162 @@ pub fn get_good_index(
     Ok((index_to_use, found))
 }
 
+/// Where to look for the config line to mint: the index to start from and
+/// the range of indices it can be taken from.
+pub struct ConfigLineSearch {
+    pub index: usize,
+    pub range: Range<usize>,
+    /// If true, indices below the start one are searched first.
+    pub reverse: bool,
+    /// If true, the whole config array is searched when the range is full.
+    pub fallback_to_all: bool,
+}
+
+/// Picks where to search for the config line to mint, following the mint
+/// order of the candy machine.
+pub fn get_config_line_search(
+    candy_machine: &CandyMachine,
+    mint_phase: &MintPhase,
+    recent_slothashes: &AccountInfo,
+) -> Result<ConfigLineSearch> {
+    let items_available = candy_machine.data.items_available as usize;
+    let items_redeemed = candy_machine.items_redeemed as usize;
+    let random_index = |range: &Range<usize>| -> Result<usize> {
+        let data = recent_slothashes.data.borrow();
+        let most_recent = array_ref![data, 12, 8];
+
+        let index = u64::from_le_bytes(*most_recent);
+        Ok(range.start
+            + index
+                .checked_rem(range.len() as u64)
+                .ok_or(CandyError::NumericalOverflowError)? as usize)
+    };
+
+    // Sequential minting in the premint phase applies whatever the mint order.
+    if candy_machine.data.sequential_mint_order_enabled && *mint_phase == MintPhase::Premint {
+        return Ok(ConfigLineSearch {
+            index: items_redeemed,
+            range: 0..items_available,
+            reverse: false,
+            fallback_to_all: false,
+        });
+    }
+
+    let search = match candy_machine.data.mint_order {
+        MintOrder::Random => ConfigLineSearch {
+            index: random_index(&(0..items_available))?,
+            range: 0..items_available,
+            reverse: false,
+            fallback_to_all: false,
+        },
+        MintOrder::Sequential => ConfigLineSearch {
+            index: items_redeemed,
+            range: 0..items_available,
+            reverse: false,
+            fallback_to_all: false,
+        },
+        MintOrder::ReverseSequential => ConfigLineSearch {
+            index: items_available
+                .checked_sub(items_redeemed + 1)
+                .ok_or(CandyError::CandyMachineEmpty)?,
+            range: 0..items_available,
+            reverse: true,
+            fallback_to_all: false,
+        },
+        MintOrder::ReservedRangePerPhase { allowlist_items } => {
+            let allowlist_items = std::cmp::min(allowlist_items as usize, items_available);
+            let (range, fallback_to_all) = if *mint_phase == MintPhase::Allowlist {
+                (0..allowlist_items, false)
+            } else {
+                (allowlist_items..items_available, true)
+            };
+            if range.is_empty() {
+                ConfigLineSearch {
+                    index: random_index(&(0..items_available))?,
+                    range: 0..items_available,
+                    reverse: false,
+                    fallback_to_all: false,
+                }
+            } else {
+                ConfigLineSearch {
+                    index: random_index(&range)?,
+                    range,
+                    reverse: false,
+                    fallback_to_all,
+                }
+            }
+        }
+    };
+
+    Ok(search)
+}
+
+/// Marks the first config line of the range which hasn't been minted yet,
+/// searching both ways from the given index, and returns its index.
+fn find_unminted_index(
+    arr: &mut RefMut<&mut [u8]>,
+    items_available: usize,
+    index: usize,
+    range: &Range<usize>,
+    reverse: bool,
+) -> Result<Option<usize>> {
+    let (index_to_use, good) = get_good_index(arr, items_available, index, range, !reverse)?;
+    if good {
+        return Ok(Some(index_to_use));
+    }
+
+    let (index_to_use, good) = get_good_index(arr, items_available, index, range, reverse)?;
+    if good {
+        return Ok(Some(index_to_use));
+    }
+
+    Ok(None)
+}
+
+/// Returns the config line to mint along with its index in the config array.
 pub fn get_config_line(
     a: &Account<'_, CandyMachine>,
-    index: usize,
+    search: &ConfigLineSearch,
     mint_number: u64,
-) -> Result<ConfigLine> {
+) -> Result<(ConfigLine, usize)> {
     if let Some(hs) = &a.data.hidden_settings {
-        return Ok(ConfigLine {
-            name: hs.name.clone() + "#" + &(mint_number + 1).to_string(),
-            uri: hs.uri.clone(),
-        });
+        return Ok((
+            ConfigLine {
+                name: hs.name.clone() + "#" + &(mint_number + 1).to_string(),
+                uri: hs.uri.clone(),
+            },
+            mint_number as usize,
+        ));
     }
     let a_info = a.to_account_info();
 
     let mut arr = a_info.data.borrow_mut();
 
-    let (mut index_to_use, good) =
-        get_good_index(&mut arr, a.data.items_available as usize, index, true)?;
-    if !good {
-        let (index_to_use_new, good_new) =
-            get_good_index(&mut arr, a.data.items_available as usize, index, false)?;
-        index_to_use = index_to_use_new;
-        if !good_new {
-            return err!(CandyError::CannotFindUsableConfigLine);
-        }
+    let items_available = a.data.items_available as usize;
+    let full_range = 0..items_available;
+    let mut found_index = find_unminted_index(
+        &mut arr,
+        items_available,
+        search.index,
+        &search.range,
+        search.reverse,
+    )?;
+    if found_index.is_none() && search.fallback_to_all {
+        found_index = find_unminted_index(
+            &mut arr,
+            items_available,
+            search.index,
+            &full_range,
+            search.reverse,
+        )?;
     }
+    let index_to_use = match found_index {
+        Some(index_to_use) => index_to_use,
+        None if search.range == full_range || search.fallback_to_all => {
+            return err!(CandyError::CannotFindUsableConfigLine)
+        }
+        None => return err!(CandyError::MintOrderRangeSoldOut),
+    };
 
     if arr[CONFIG_ARRAY_START + 4 + index_to_use * (CONFIG_LINE_SIZE)] == 1 {
         return err!(CandyError::CannotFindUsableConfigLine);
@@ -1008,7 +2335,7 @@ pub fn get_config_line(
         config_line.name
     );
 
-    Ok(config_line)
+    Ok((config_line, index_to_use))
 }
 
 fn get_spl_token_allowlist_remaining_accounts_counter(candy: &CandyMachine) -> usize {
@@ -1016,7 +2343,7 @@ fn get_spl_token_allowlist_remaining_accounts_counter(candy: &CandyMachine) -> u
     if let Some(spl_token_allowlist_settings) = &candy.data.spl_token_allowlist_settings {
         counter += 1;
         if spl_token_allowlist_settings.mode == SplTokenAllowlistMode::BurnEveryTime {
-            counter += 1;
+            counter += 2;
         }
     }
 
@@ -1025,22 +2352,259 @@ fn get_spl_token_allowlist_remaining_accounts_counter(candy: &CandyMachine) -> u
 
 fn get_treasury_remaining_accounts_counter(candy: &CandyMachine) -> usize {
     match candy.treasury_mint {
+        Some(_) => 3,
+        None => 0,
+    }
+}
+
+fn get_freeze_remaining_accounts_counter(candy: &CandyMachine) -> usize {
+    if !is_feature_active(&candy.data.uuid, FREEZE_FEATURE_INDEX) {
+        return 0;
+    }
+
+    match candy.treasury_mint {
+        Some(_) => 3,
+        None => 2,
+    }
+}
+
+fn is_followed_by_set_collection_during_mint(
+    instruction_sysvar_account_info: &AccountInfo,
+) -> bool {
+    match get_instruction_relative(1, instruction_sysvar_account_info) {
+        Ok(ix) => {
+            cmp_pubkeys(&ix.program_id, &crate::id())
+                && ix.data.len() >= 8
+                && ix.data[..8] == crate::instruction::SetCollectionDuringMint::DISCRIMINATOR
+        }
+        Err(_) => false,
+    }
+}
+
+fn get_collection_remaining_accounts_counter(candy: &CandyMachine) -> usize {
+    if is_feature_active(&candy.data.uuid, COLLECTIONS_FEATURE_INDEX) {
+        5
+    } else {
+        0
+    }
+}
+
+fn get_open_edition_accounts_start(candy: &CandyMachine) -> usize {
+    get_spl_token_allowlist_remaining_accounts_counter(candy)
+        + get_treasury_remaining_accounts_counter(candy)
+        + get_freeze_remaining_accounts_counter(candy)
+        + get_collection_remaining_accounts_counter(candy)
+}
+
+fn get_burn_to_mint_accounts_start(candy: &CandyMachine) -> usize {
+    let open_edition_accounts_counter = match candy.data.open_edition_settings {
+        Some(_) => 4,
+        None => 0,
+    };
+
+    get_open_edition_accounts_start(candy) + open_edition_accounts_counter
+}
+
+fn get_compressed_accounts_start(candy: &CandyMachine) -> usize {
+    let burn_to_mint_accounts_counter = match candy.data.burn_to_mint_settings {
+        Some(_) => 5,
+        None => 0,
+    };
+
+    get_burn_to_mint_accounts_start(candy) + burn_to_mint_accounts_counter
+}
+
+/// Checks the master edition accounts passed in for an open edition mint
+/// against the PDAs derived from the configured master edition mint.
+fn assert_open_edition_accounts(
+    master_edition_mint: &Pubkey,
+    edition: u64,
+    master_edition_metadata: &AccountInfo,
+    master_edition: &AccountInfo,
+    edition_marker: &AccountInfo,
+) -> Result<()> {
+    let (expected_metadata, _) = find_metadata_account(master_edition_mint);
+    let (expected_master_edition, _) = find_master_edition_account(master_edition_mint);
+    let edition_marker_number = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    let (expected_edition_marker, _) = Pubkey::find_program_address(
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            master_edition_mint.as_ref(),
+            EDITION.as_bytes(),
+            edition_marker_number.as_bytes(),
+        ],
+        &mpl_token_metadata::id(),
+    );
+
+    if !cmp_pubkeys(&expected_metadata, master_edition_metadata.key)
+        || !cmp_pubkeys(&expected_master_edition, master_edition.key)
+        || !cmp_pubkeys(&expected_edition_marker, edition_marker.key)
+    {
+        return err!(CandyError::MasterEditionMismatch);
+    }
+
+    Ok(())
+}
+
+/// Mints a compressed NFT owned by the recipient into the candy machine's
+/// Bubblegum tree, signing as the tree delegate.
+#[allow(clippy::too_many_arguments)]
+fn mint_compressed_nft<'info>(
+    candy_machine: &CandyMachine,
+    candy_pubkey: &Pubkey,
+    remaining_accounts: &[AccountInfo<'info>],
+    recipient: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    creator_authority: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    metadata: MetadataArgs,
+) -> Result<()> {
+    let compressed_tree_info = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::CompressedTree,
+    );
+    let tree_authority = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::TreeAuthority,
+    );
+    let merkle_tree = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::MerkleTree,
+    );
+    let log_wrapper = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::LogWrapper,
+    );
+    let compression_program = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::CompressionProgram,
+    );
+    let bubblegum_program = get_remaining_account(
+        candy_machine,
+        remaining_accounts,
+        RemainingAccounts::BubblegumProgram,
+    );
+
+    let (expected_compressed_tree, compressed_tree_bump) = find_compressed_tree_pda(candy_pubkey);
+    assert_keys_equal(&expected_compressed_tree, compressed_tree_info.key)?;
+    let compressed_tree: Account<CompressedTree> = Account::try_from(&compressed_tree_info)?;
+    if !cmp_pubkeys(&compressed_tree.merkle_tree, merkle_tree.key) {
+        return err!(CandyError::CompressedTreeMismatch);
+    }
+    assert_keys_equal(bubblegum_program.key, &mpl_bubblegum::id())?;
+
+    let compressed_tree_seeds = [
+        CompressedTree::PREFIX.as_bytes(),
+        candy_pubkey.as_ref(),
+        &[compressed_tree_bump],
+    ];
+    let compressed_tree_signer = [&compressed_tree_seeds[..]];
+    let mut cpi_context = CpiContext::new_with_signer(
+        bubblegum_program,
+        MintV1 {
+            tree_authority,
+            leaf_owner: recipient.clone(),
+            leaf_delegate: recipient.clone(),
+            merkle_tree,
+            payer: buyer.clone(),
+            tree_delegate: compressed_tree_info,
+            log_wrapper,
+            compression_program,
+            system_program: system_program.clone(),
+        },
+        &compressed_tree_signer,
+    );
+    // Bubblegum only marks creators as verified if they sign.
+    if creator_authority.is_signer {
+        cpi_context = cpi_context.with_remaining_accounts(vec![creator_authority.clone()]);
+    }
+
+    mpl_bubblegum::cpi::mint_v1(cpi_context, metadata)
+}
+
+fn get_programmable_remaining_accounts_counter(candy: &CandyMachine) -> usize {
+    match &candy.data.programmable_nft_settings {
+        Some(programmable_nft_settings) if programmable_nft_settings.rule_set.is_some() => 3,
         Some(_) => 1,
         None => 0,
     }
 }
 
+/// Looks up the token record of a programmable NFT's token account, and the
+/// authorization rules accounts if the candy machine has a rule set, by key.
+#[allow(clippy::type_complexity)]
+fn get_programmable_nft_accounts<'a>(
+    candy: &CandyMachine,
+    remaining_accounts: &[AccountInfo<'a>],
+    mint: &Pubkey,
+    token: &Pubkey,
+) -> Result<(AccountInfo<'a>, Option<(AccountInfo<'a>, AccountInfo<'a>)>)> {
+    let find_account = |key: &Pubkey| {
+        remaining_accounts
+            .iter()
+            .find(|account| cmp_pubkeys(account.key, key))
+            .cloned()
+            .ok_or(CandyError::MissingProgrammableNftAccounts)
+    };
+
+    let (token_record_key, _) = find_token_record_account(mint, token);
+    let token_record = find_account(&token_record_key)?;
+
+    let rule_set = candy
+        .data
+        .programmable_nft_settings
+        .as_ref()
+        .and_then(|programmable_nft_settings| programmable_nft_settings.rule_set);
+    let authorization_rules = match rule_set {
+        Some(rule_set) => Some((
+            find_account(&TOKEN_AUTH_RULES_PROGRAM_ID)?,
+            find_account(&rule_set)?,
+        )),
+        None => None,
+    };
+
+    Ok((token_record, authorization_rules))
+}
+
+/// The metadata, mint or master edition account of the NFT, which only
+/// compressed mints leave out.
+fn get_nft_account<'info, T: ToAccountInfo<'info>>(
+    account: &Option<T>,
+) -> Result<AccountInfo<'info>> {
+    account
+        .as_ref()
+        .map(|account| account.to_account_info())
+        .ok_or_else(|| error!(CandyError::MissingNftAccounts))
+}
+
 fn get_remaining_account<'a>(
     candy: &CandyMachine,
     remaining_accounts: &[AccountInfo<'a>],
     account: RemainingAccounts,
 ) -> AccountInfo<'a> {
+    let collection_accounts_start = get_spl_token_allowlist_remaining_accounts_counter(candy)
+        + get_treasury_remaining_accounts_counter(candy)
+        + get_freeze_remaining_accounts_counter(candy);
+
     let account_index: usize = match account {
         RemainingAccounts::SplTokenAllowlistTokenAccount => 0,
         RemainingAccounts::SplTokenAllowlistTokenMint => 1,
+        RemainingAccounts::SplTokenAllowlistTokenProgram => 2,
         RemainingAccounts::TreasuryTokenAccount => {
             get_spl_token_allowlist_remaining_accounts_counter(candy)
         }
+        RemainingAccounts::TreasuryMint => {
+            get_spl_token_allowlist_remaining_accounts_counter(candy) + 1
+        }
+        RemainingAccounts::TreasuryTokenProgram => {
+            get_spl_token_allowlist_remaining_accounts_counter(candy) + 2
+        }
         RemainingAccounts::FreezePda => {
             get_spl_token_allowlist_remaining_accounts_counter(candy)
                 + get_treasury_remaining_accounts_counter(candy)
@@ -1055,23 +2619,45 @@ fn get_remaining_account<'a>(
                 + get_treasury_remaining_accounts_counter(candy)
                 + 2
         }
+        RemainingAccounts::CollectionPda => collection_accounts_start,
+        RemainingAccounts::CollectionMint => collection_accounts_start + 1,
+        RemainingAccounts::CollectionMetadata => collection_accounts_start + 2,
+        RemainingAccounts::CollectionMasterEdition => collection_accounts_start + 3,
+        RemainingAccounts::CollectionAuthorityRecord => collection_accounts_start + 4,
+        RemainingAccounts::MasterEditionMetadata => get_open_edition_accounts_start(candy),
+        RemainingAccounts::MasterEdition => get_open_edition_accounts_start(candy) + 1,
+        RemainingAccounts::MasterEditionTokenAccount => get_open_edition_accounts_start(candy) + 2,
+        RemainingAccounts::EditionMarker => get_open_edition_accounts_start(candy) + 3,
+        RemainingAccounts::BurnNftMetadata => get_burn_to_mint_accounts_start(candy),
+        RemainingAccounts::BurnNftMint => get_burn_to_mint_accounts_start(candy) + 1,
+        RemainingAccounts::BurnNftTokenAccount => get_burn_to_mint_accounts_start(candy) + 2,
+        RemainingAccounts::BurnNftMasterEdition => get_burn_to_mint_accounts_start(candy) + 3,
+        RemainingAccounts::BurnNftCollectionMetadata => get_burn_to_mint_accounts_start(candy) + 4,
+        RemainingAccounts::CompressedTree => get_compressed_accounts_start(candy),
+        RemainingAccounts::TreeAuthority => get_compressed_accounts_start(candy) + 1,
+        RemainingAccounts::MerkleTree => get_compressed_accounts_start(candy) + 2,
+        RemainingAccounts::LogWrapper => get_compressed_accounts_start(candy) + 3,
+        RemainingAccounts::CompressionProgram => get_compressed_accounts_start(candy) + 4,
+        RemainingAccounts::BubblegumProgram => get_compressed_accounts_start(candy) + 5,
     };
 
     remaining_accounts[account_index].clone()
 }
 
-pub fn get_expected_remaining_accounts_count(candy: &CandyMachine) -> usize {
+/// The burn-to-mint accounts only count if the mint burns an NFT, which
+/// depends on the mint phase.
+pub fn get_expected_remaining_accounts_count(candy: &CandyMachine, burns_nft: bool) -> usize {
     let mut expected_count = 0;
 
     if let Some(spl_token_allowlist_settings) = &candy.data.spl_token_allowlist_settings {
         expected_count += 1;
         if spl_token_allowlist_settings.mode == SplTokenAllowlistMode::BurnEveryTime {
-            expected_count += 1;
+            expected_count += 2;
         }
     }
 
     if candy.treasury_mint.is_some() {
-        expected_count += 1;
+        expected_count += 3;
     }
 
     if is_feature_active(&candy.data.uuid, FREEZE_FEATURE_INDEX) {
@@ -1080,5 +2666,54 @@ pub fn get_expected_remaining_accounts_count(candy: &CandyMachine) -> usize {
             expected_count += 1;
         }
     }
+
+    if is_feature_active(&candy.data.uuid, COLLECTIONS_FEATURE_INDEX) {
+        expected_count += 5;
+    }
+
+    if candy.data.open_edition_settings.is_some() {
+        expected_count += 4;
+    }
+
+    if burns_nft {
+        expected_count += 5;
+    }
+
+    if candy.is_compressed() {
+        expected_count += 6;
+    }
+
+    expected_count += get_programmable_remaining_accounts_counter(candy);
+
+    if let Some(bot_tax_settings) = &candy.data.bot_tax_settings {
+        if bot_tax_settings.destination.is_some() {
+            expected_count += 1;
+        }
+    }
+
+    if candy.data.voucher_signer.is_some() {
+        expected_count += 1;
+    }
+
+    if candy.data.creator_overrides_enabled {
+        expected_count += 1;
+    }
+
+    if candy.data.usd_price_settings.is_some() {
+        expected_count += 1;
+    }
+
+    if candy.data.min_items_to_succeed.is_some() {
+        expected_count += 1;
+    }
+
+    if candy.data.drop.is_some() {
+        expected_count += 2;
+    }
+
+    if is_feature_active(&candy.data.uuid, FREEZE_FEATURE_INDEX) && candy.has_rolling_freeze() {
+        expected_count += 1;
+    }
+
     expected_count
 }
@@ -1,17 +1,41 @@
 pub mod add_config_lines;
+pub mod claim_referral_earnings;
+pub mod close_buyer_info_account;
+pub mod close_unsold_inventory;
 pub mod collection;
+pub mod compressed;
+pub mod creator_overrides;
+pub mod drop;
 pub mod freeze;
 pub mod initialize;
 pub mod merkle_allowlist;
+pub mod migrate_candy_machine;
 pub mod mint;
+pub mod multisig;
+pub mod reveal;
+pub mod roles;
+pub mod rotate_bot_signers;
+pub mod set_paused;
 pub mod update;
 pub mod withdraw;
 
 pub use add_config_lines::*;
+pub use claim_referral_earnings::*;
+pub use close_buyer_info_account::*;
+pub use close_unsold_inventory::*;
 pub use collection::*;
+pub use compressed::*;
+pub use creator_overrides::*;
+pub use drop::*;
 pub use freeze::*;
 pub use initialize::*;
 pub use merkle_allowlist::*;
+pub use migrate_candy_machine::*;
 pub use mint::*;
+pub use multisig::*;
+pub use reveal::*;
+pub use roles::*;
+pub use rotate_bot_signers::*;
+pub use set_paused::*;
 pub use update::*;
 pub use withdraw::*;
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyError, CandyMachine, MultisigPda, Proposal};
+
+/// Approve a proposal as one of the multisig signers. The timelock starts once
+/// the proposal reaches the threshold.
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    candy_machine: Box<Account<'info, CandyMachine>>,
+    #[account(
+        seeds = [
+            MultisigPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    multisig_pda: Account<'info, MultisigPda>,
+    #[account(
+        mut,
+        seeds = [
+            Proposal::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    proposal: Box<Account<'info, Proposal>>,
+    signer: Signer<'info>,
+}
+
+pub fn handle_approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+    let multisig_pda = &ctx.accounts.multisig_pda;
+    let proposal = &mut ctx.accounts.proposal;
+    let signer = ctx.accounts.signer.key();
+    if !multisig_pda.is_signer(&signer) {
+        return err!(CandyError::NotAMultisigSigner);
+    }
+    if proposal.approvals.contains(&signer) {
+        return err!(CandyError::ProposalAlreadyApproved);
+    }
+
+    // Approvals of signers removed since then don't count. If that leaves the
+    // proposal below the threshold, its timelock starts over once it reaches
+    // the threshold again.
+    proposal
+        .approvals
+        .retain(|approver| multisig_pda.is_signer(approver));
+    if proposal.approvals.len() < multisig_pda.threshold as usize {
+        proposal.approved_at = None;
+    }
+
+    proposal.approvals.push(signer);
+    let approvals = proposal.approvals.len();
+    if proposal.approved_at.is_none() && approvals >= multisig_pda.threshold as usize {
+        proposal.approved_at = Some(Clock::get()?.unix_timestamp);
+    }
+
+    msg!(
+        "Proposal {} has {} of {} approvals.",
+        proposal.index,
+        approvals,
+        multisig_pda.threshold
+    );
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyMachine, Proposal};
+
+/// Cancel a proposal that hasn't been executed, refunding its rent to the
+/// proposer.
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    candy_machine: Box<Account<'info, CandyMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            Proposal::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump,
+        has_one = candy_machine,
+        has_one = proposer,
+        close = proposer
+    )]
+    proposal: Box<Account<'info, Proposal>>,
+    #[account(mut)]
+    proposer: Signer<'info>,
+}
+
+pub fn handle_cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    msg!("Cancelled proposal {}.", ctx.accounts.proposal.index);
+
+    Ok(())
+}
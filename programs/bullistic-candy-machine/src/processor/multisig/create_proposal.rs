@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{cmp_pubkeys, CandyError, CandyMachine, MultisigPda, Proposal, ProposalAction};
+
+/// Propose an action to the multisig of a candy machine. The proposer must be
+/// one of the signers, and approves the proposal by creating it.
+#[derive(Accounts)]
+#[instruction(action: ProposalAction)]
+pub struct CreateProposal<'info> {
+    candy_machine: Box<Account<'info, CandyMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            MultisigPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    multisig_pda: Account<'info, MultisigPda>,
+    #[account(
+        init,
+        seeds = [
+            Proposal::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref(),
+            &multisig_pda.proposal_count.to_le_bytes()
+        ],
+        bump,
+        space = Proposal::space_for(&action),
+        payer = proposer
+    )]
+    proposal: Box<Account<'info, Proposal>>,
+    #[account(mut)]
+    proposer: Signer<'info>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    let multisig_pda = &mut ctx.accounts.multisig_pda;
+    let proposer = ctx.accounts.proposer.key();
+    if !cmp_pubkeys(
+        &ctx.accounts.candy_machine.bullistic_authority,
+        &multisig_pda.key(),
+    ) {
+        return err!(CandyError::MultisigNotActive);
+    }
+    if !multisig_pda.is_signer(&proposer) {
+        return err!(CandyError::NotAMultisigSigner);
+    }
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.candy_machine = ctx.accounts.candy_machine.key();
+    proposal.index = multisig_pda.proposal_count;
+    proposal.proposer = proposer;
+    proposal.approvals = vec![proposer];
+    if multisig_pda.threshold <= 1 {
+        proposal.approved_at = Some(Clock::get()?.unix_timestamp);
+    }
+    proposal.action = action;
+
+    multisig_pda.proposal_count = multisig_pda
+        .proposal_count
+        .checked_add(1)
+        .ok_or(CandyError::NumericalOverflowError)?;
+
+    msg!("Created proposal {}.", proposal.index);
+
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+
+use crate::{
+    cmp_pubkeys, rotate_candy_machine_bot_signers, update_candy_machine_authority,
+    update_candy_machine_data, validate_multisig_settings, withdraw_candy_machine_funds,
+    CandyError, CandyMachine, MultisigPda, Proposal, ProposalAction,
+};
+
+/// Execute an approved proposal once its timelock has passed. Anyone can
+/// execute it, the proposal rent goes back to the proposer.
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut)]
+    candy_machine: Box<Account<'info, CandyMachine>>,
+    #[account(
+        mut,
+        seeds = [
+            MultisigPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    multisig_pda: Account<'info, MultisigPda>,
+    #[account(
+        mut,
+        seeds = [
+            Proposal::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref(),
+            &proposal.index.to_le_bytes()
+        ],
+        bump,
+        has_one = candy_machine,
+        has_one = proposer,
+        close = proposer
+    )]
+    proposal: Box<Account<'info, Proposal>>,
+    /// CHECK: checked by has_one on the proposal, only receives the rent
+    #[account(mut)]
+    proposer: UncheckedAccount<'info>,
+    /// CHECK: checked against the destination of a withdraw proposal
+    #[account(mut)]
+    destination: Option<UncheckedAccount<'info>>,
+    // > Only for withdraws of a candy machine with a collection
+    // CollectionPda account
+}
+
+pub fn handle_execute_proposal<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+) -> Result<()> {
+    let multisig_pda = &ctx.accounts.multisig_pda;
+    let proposal = &ctx.accounts.proposal;
+    if !cmp_pubkeys(
+        &ctx.accounts.candy_machine.bullistic_authority,
+        &multisig_pda.key(),
+    ) {
+        return err!(CandyError::MultisigNotActive);
+    }
+
+    let approvals = proposal
+        .approvals
+        .iter()
+        .filter(|approver| multisig_pda.is_signer(approver))
+        .count();
+    let approved_at = match proposal.approved_at {
+        Some(approved_at) if approvals >= multisig_pda.threshold as usize => approved_at,
+        _ => return err!(CandyError::ProposalNotApproved),
+    };
+    let unlock_time = approved_at
+        .checked_add(multisig_pda.timelock_seconds)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if Clock::get()?.unix_timestamp < unlock_time {
+        msg!("Proposal can be executed from {}.", unlock_time);
+        return err!(CandyError::ProposalTimelockActive);
+    }
+
+    msg!("Executing proposal {}.", proposal.index);
+    match proposal.action.clone() {
+        ProposalAction::UpdateCandyMachine {
+            data,
+            treasury_wallet,
+            treasury_mint,
+        } => update_candy_machine_data(
+            &mut ctx.accounts.candy_machine,
+            data,
+            treasury_wallet,
+            treasury_mint,
+        )?,
+        ProposalAction::UpdateAuthority { new_authority } => {
+            update_candy_machine_authority(&mut ctx.accounts.candy_machine, new_authority)?
+        }
+        ProposalAction::WithdrawFunds { destination } => {
+            let destination_info = match &ctx.accounts.destination {
+                Some(account) if cmp_pubkeys(account.key, &destination) => {
+                    account.to_account_info()
+                }
+                _ => return err!(CandyError::InvalidWithdrawDestination),
+            };
+            withdraw_candy_machine_funds(
+                &ctx.accounts.candy_machine,
+                &destination_info,
+                ctx.remaining_accounts,
+            )?;
+            ctx.accounts.candy_machine.close(destination_info)?;
+        }
+        ProposalAction::UpdateMultisig {
+            signers,
+            threshold,
+            timelock_seconds,
+        } => {
+            validate_multisig_settings(&signers, threshold, timelock_seconds)?;
+            let multisig_pda = &mut ctx.accounts.multisig_pda;
+            multisig_pda.signers = signers;
+            multisig_pda.threshold = threshold;
+            multisig_pda.timelock_seconds = timelock_seconds;
+        }
+        ProposalAction::RotateBotSigners { bot_signers } => {
+            rotate_candy_machine_bot_signers(&mut ctx.accounts.candy_machine, bot_signers)?
+        }
+    }
+
+    Ok(())
+}
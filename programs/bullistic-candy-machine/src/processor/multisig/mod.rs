@@ -0,0 +1,11 @@
+pub mod approve_proposal;
+pub mod cancel_proposal;
+pub mod create_proposal;
+pub mod execute_proposal;
+pub mod set_multisig;
+
+pub use approve_proposal::*;
+pub use cancel_proposal::*;
+pub use create_proposal::*;
+pub use execute_proposal::*;
+pub use set_multisig::*;
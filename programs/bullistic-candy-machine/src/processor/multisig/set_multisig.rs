@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    update_candy_machine_authority, validate_multisig_settings, CandyMachine, MultisigPda,
+};
+
+/// Turn on the multisig mode of a candy machine. The bullistic authority is
+/// handed over to the multisig PDA, so withdraws, authority changes and
+/// updates only go through approved proposals from then on.
+///
+/// A multisig PDA left from an earlier multisig, which handed the authority
+/// back, is reused. Its proposal count is kept, so new proposals don't
+/// collide with proposals still open from before.
+#[derive(Accounts)]
+pub struct SetMultisig<'info> {
+    #[account(mut, has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            MultisigPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        space = MultisigPda::SIZE,
+        payer = bullistic_authority
+    )]
+    multisig_pda: Account<'info, MultisigPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_set_multisig(
+    ctx: Context<SetMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    timelock_seconds: i64,
+) -> Result<()> {
+    validate_multisig_settings(&signers, threshold, timelock_seconds)?;
+
+    let multisig_pda = &mut ctx.accounts.multisig_pda;
+    multisig_pda.candy_machine = ctx.accounts.candy_machine.key();
+    multisig_pda.signers = signers;
+    multisig_pda.threshold = threshold;
+    multisig_pda.timelock_seconds = timelock_seconds;
+
+    update_candy_machine_authority(&mut ctx.accounts.candy_machine, multisig_pda.key())
+}
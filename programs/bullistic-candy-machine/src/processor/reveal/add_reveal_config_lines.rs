@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use mpl_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
+
+use crate::{
+    get_reveal_hash_commitment, hash_reveal_config_line, CandyError, CandyMachine, ConfigLine,
+    RevealPda,
+};
+
+/// Upload the revealed config lines of a hidden settings candy machine, in
+/// mint order. Once every item is uploaded the lines are checked against the
+/// hidden settings hash.
+#[derive(Accounts)]
+pub struct AddRevealConfigLines<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            RevealPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    reveal_pda: Account<'info, RevealPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_add_reveal_config_lines(
+    ctx: Context<AddRevealConfigLines>,
+    index: u32,
+    config_lines: Vec<ConfigLine>,
+) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    let reveal_pda = &mut ctx.accounts.reveal_pda;
+
+    let hidden_settings = match &candy_machine.data.hidden_settings {
+        Some(hidden_settings) if candy_machine.data.on_chain_reveal_enabled => hidden_settings,
+        _ => return err!(CandyError::OnChainRevealNotEnabled),
+    };
+
+    if reveal_pda.verified {
+        return err!(CandyError::RevealAlreadyVerified);
+    }
+
+    // Lines are hashed in order, so uploads either continue where the last
+    // one stopped or start over from the first line.
+    if index != 0 && index != reveal_pda.lines_uploaded {
+        return err!(CandyError::InvalidRevealLineIndex);
+    }
+
+    let items_available = candy_machine.data.items_available as usize;
+    let lines_uploaded = (index as usize)
+        .checked_add(config_lines.len())
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if lines_uploaded > items_available {
+        return err!(CandyError::IndexGreaterThanLength);
+    }
+
+    // Grow the reveal PDA to fit the uploaded lines.
+    let reveal_info = reveal_pda.to_account_info();
+    let new_size = RevealPda::space_for(lines_uploaded);
+    if reveal_info.data_len() < new_size {
+        let rent_due = Rent::get()?
+            .minimum_balance(new_size)
+            .saturating_sub(reveal_info.lamports());
+        if rent_due > 0 {
+            transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.bullistic_authority.to_account_info(),
+                        to: reveal_info.clone(),
+                    },
+                ),
+                rent_due,
+            )?;
+        }
+        reveal_info.realloc(new_size, false)?;
+    }
+
+    let mut running_hash = if index == 0 {
+        [0; 32]
+    } else {
+        reveal_pda.running_hash
+    };
+
+    {
+        let mut data = reveal_info.data.borrow_mut();
+        for (i, config_line) in config_lines.iter().enumerate() {
+            if config_line.name.len() > MAX_NAME_LENGTH || config_line.uri.len() > MAX_URI_LENGTH {
+                return err!(CandyError::InvalidString);
+            }
+
+            running_hash = hash_reveal_config_line(&running_hash, config_line)?;
+
+            let position = RevealPda::space_for(index as usize + i);
+            let line_data = &mut data[position..position + RevealPda::LINE_SIZE];
+            let uri = config_line.uri.as_bytes();
+            line_data.fill(0);
+            line_data[..4].copy_from_slice(&(uri.len() as u32).to_le_bytes());
+            line_data[4..4 + uri.len()].copy_from_slice(uri);
+        }
+    }
+
+    reveal_pda.running_hash = running_hash;
+    reveal_pda.lines_uploaded = lines_uploaded as u32;
+
+    if lines_uploaded == items_available {
+        if get_reveal_hash_commitment(&running_hash) != hidden_settings.hash {
+            return err!(CandyError::RevealHashMismatch);
+        }
+        reveal_pda.verified = true;
+    }
+
+    Ok(())
+}
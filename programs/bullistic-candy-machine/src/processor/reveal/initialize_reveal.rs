@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyError, CandyMachine, RevealPda};
+
+/// Create the reveal PDA for a hidden settings candy machine.
+#[derive(Accounts)]
+pub struct InitializeReveal<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        init,
+        seeds = [
+            RevealPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        space = RevealPda::SIZE,
+        payer = bullistic_authority
+    )]
+    reveal_pda: Account<'info, RevealPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_reveal(ctx: Context<InitializeReveal>) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    if !candy_machine.data.on_chain_reveal_enabled {
+        return err!(CandyError::OnChainRevealNotEnabled);
+    }
+
+    let reveal_pda = &mut ctx.accounts.reveal_pda;
+    reveal_pda.candy_machine = candy_machine.key();
+    reveal_pda.lines_uploaded = 0;
+    reveal_pda.running_hash = [0; 32];
+    reveal_pda.verified = false;
+
+    Ok(())
+}
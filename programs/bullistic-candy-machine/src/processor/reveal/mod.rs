@@ -0,0 +1,7 @@
+pub mod add_reveal_config_lines;
+pub mod initialize_reveal;
+pub mod reveal_nft;
+
+pub use add_reveal_config_lines::*;
+pub use initialize_reveal::*;
+pub use reveal_nft::*;
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use arrayref::array_ref;
+use mpl_token_metadata::{
+    instruction::update_metadata_accounts_v2,
+    state::{DataV2, Metadata, TokenMetadataAccount},
+};
+use solana_program::program::invoke_signed;
+
+use crate::{cmp_pubkeys, constants::PREFIX, CandyError, CandyMachine, RevealPda};
+
+/// Permissionless crank which rewrites the URI of a minted hidden settings NFT
+/// from the verified reveal PDA, and hands its update authority over to the
+/// creator authority.
+#[derive(Accounts)]
+pub struct RevealNft<'info> {
+    candy_machine: Account<'info, CandyMachine>,
+    /// CHECK: account constraints checked in account trait
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            candy_machine.key().as_ref()
+        ],
+        bump
+    )]
+    candy_machine_creator: UncheckedAccount<'info>,
+    #[account(
+        seeds = [
+            RevealPda::PREFIX.as_bytes(),
+            candy_machine.key().as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    reveal_pda: Account<'info, RevealPda>,
+    /// CHECK: deserialized and checked in the handler
+    #[account(mut, owner = mpl_token_metadata::id())]
+    metadata: UncheckedAccount<'info>,
+    /// CHECK: checked in account constraints
+    #[account(address = mpl_token_metadata::id())]
+    token_metadata_program: UncheckedAccount<'info>,
+}
+
+pub fn handle_reveal_nft(ctx: Context<RevealNft>) -> Result<()> {
+    let candy_machine = &ctx.accounts.candy_machine;
+    let candy_machine_creator = &ctx.accounts.candy_machine_creator;
+    let reveal_pda = &ctx.accounts.reveal_pda;
+
+    if !reveal_pda.verified {
+        return err!(CandyError::RevealNotVerified);
+    }
+
+    let metadata: Metadata = Metadata::from_account_info(&ctx.accounts.metadata.to_account_info())?;
+
+    // Unrevealed NFTs keep the candy machine creator as update authority, so
+    // nothing but this program could have changed their name.
+    if !cmp_pubkeys(&metadata.update_authority, &candy_machine_creator.key()) {
+        return err!(CandyError::InvalidRevealMetadata);
+    }
+
+    // Hidden settings NFTs are named "name#N", with N the 1-based mint number.
+    let name = metadata.data.name.trim_matches(char::from(0)).to_string();
+    let mint_number = name
+        .rsplit_once('#')
+        .and_then(|(_, number)| number.parse::<u64>().ok())
+        .filter(|mint_number| *mint_number > 0)
+        .ok_or(CandyError::InvalidRevealMetadata)?;
+    let index = (mint_number - 1) as usize;
+    if index >= reveal_pda.lines_uploaded as usize {
+        return err!(CandyError::InvalidRevealMetadata);
+    }
+
+    let uri = {
+        let reveal_info = reveal_pda.to_account_info();
+        let data = reveal_info.data.borrow();
+        let position = RevealPda::space_for(index);
+        let uri_len = u32::from_le_bytes(*array_ref![data, position, 4]) as usize;
+        match String::from_utf8(data[position + 4..position + 4 + uri_len].to_vec()) {
+            Ok(uri) => uri,
+            Err(_) => return err!(CandyError::InvalidString),
+        }
+    };
+
+    msg!("Revealing NFT {} with uri = '{}'.", name, uri);
+
+    let update_data = DataV2 {
+        name,
+        symbol: metadata.data.symbol.trim_matches(char::from(0)).to_string(),
+        uri,
+        seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+        creators: metadata.data.creators,
+        collection: metadata.collection,
+        uses: metadata.uses,
+    };
+
+    let is_mutable = if !candy_machine.data.is_mutable {
+        Some(false)
+    } else {
+        None
+    };
+
+    let candy_key = candy_machine.key();
+    let authority_seeds = [
+        PREFIX.as_bytes(),
+        candy_key.as_ref(),
+        &[*ctx.bumps.get("candy_machine_creator").unwrap()],
+    ];
+
+    invoke_signed(
+        &update_metadata_accounts_v2(
+            ctx.accounts.token_metadata_program.key(),
+            ctx.accounts.metadata.key(),
+            candy_machine_creator.key(),
+            Some(candy_machine.creator_authority),
+            Some(update_data),
+            None,
+            is_mutable,
+        ),
+        &[
+            ctx.accounts.token_metadata_program.to_account_info(),
+            ctx.accounts.metadata.to_account_info(),
+            candy_machine_creator.to_account_info(),
+        ],
+        &[&authority_seeds],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyMachine, RolesPda};
+
+/// Create the roles PDA of a candy machine, with every role unassigned.
+#[derive(Accounts)]
+pub struct InitializeRoles<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    #[account(mut)]
+    bullistic_authority: Signer<'info>,
+    #[account(
+        init,
+        seeds = [
+            RolesPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        space = RolesPda::SIZE,
+        payer = bullistic_authority
+    )]
+    roles_pda: Account<'info, RolesPda>,
+    system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_roles(ctx: Context<InitializeRoles>) -> Result<()> {
+    let roles_pda = &mut ctx.accounts.roles_pda;
+    roles_pda.candy_machine = ctx.accounts.candy_machine.key();
+
+    Ok(())
+}
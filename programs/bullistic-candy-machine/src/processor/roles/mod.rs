@@ -0,0 +1,5 @@
+pub mod initialize_roles;
+pub mod set_role;
+
+pub use initialize_roles::*;
+pub use set_role::*;
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{CandyMachine, Role, RolesPda};
+
+/// Assign a role to a key, or unassign it. Only the bullistic authority,
+/// which owns the candy machine, manages roles.
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    #[account(has_one = bullistic_authority)]
+    candy_machine: Account<'info, CandyMachine>,
+    bullistic_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            RolesPda::PREFIX.as_bytes(),
+            candy_machine.to_account_info().key.as_ref()
+        ],
+        bump,
+        has_one = candy_machine
+    )]
+    roles_pda: Account<'info, RolesPda>,
+}
+
+pub fn handle_set_role(ctx: Context<SetRole>, role: Role, key: Option<Pubkey>) -> Result<()> {
+    ctx.accounts.roles_pda.set_role(role, key);
+
+    match key {
+        Some(key) => msg!("Assigned role {:?} to {}.", role, key),
+        None => msg!("Unassigned role {:?}.", role),
+    }
+
+    Ok(())
+}
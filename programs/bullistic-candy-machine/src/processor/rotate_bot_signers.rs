@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_authority_or_role, validate_bot_signers, BotSigner, CandyMachine, Role, RolesPda,
+};
+
+/// Replace the keys accepted as the bot signer authority of a candy machine,
+/// e.g. to rotate a leaked key without redeploying the program. With no keys,
+/// the default bot signer authority is accepted.
+#[derive(Accounts)]
+pub struct RotateBotSigners<'info> {
+    #[account(mut)]
+    candy_machine: Account<'info, CandyMachine>,
+    /// Bullistic authority, or the holder of the bot signer manager role.
+    bullistic_authority: Signer<'info>,
+    roles_pda: Option<Account<'info, RolesPda>>,
+}
+
+pub fn handle_rotate_bot_signers(
+    ctx: Context<RotateBotSigners>,
+    bot_signers: Vec<BotSigner>,
+) -> Result<()> {
+    assert_authority_or_role(
+        &ctx.accounts.candy_machine,
+        &ctx.accounts.roles_pda,
+        ctx.accounts.bullistic_authority.key,
+        Role::BotSignerManager,
+    )?;
+
+    rotate_candy_machine_bot_signers(&mut ctx.accounts.candy_machine, bot_signers)
+}
+
+/// Validate and apply new bot signers, shared by rotate_bot_signers and the
+/// multisig proposals.
+pub fn rotate_candy_machine_bot_signers(
+    candy_machine: &mut CandyMachine,
+    bot_signers: Vec<BotSigner>,
+) -> Result<()> {
+    validate_bot_signers(&bot_signers)?;
+    candy_machine.bot_signers = bot_signers;
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::PAUSED_FEATURE_INDEX;
+use crate::{
+    assert_authority_or_role, remove_feature_flag, set_feature_flag, CandyMachine, Role, RolesPda,
+};
+
+/// Pause or resume minting.
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    candy_machine: Account<'info, CandyMachine>,
+    /// Bullistic authority, or the holder of the pauser role.
+    bullistic_authority: Signer<'info>,
+    roles_pda: Option<Account<'info, RolesPda>>,
+}
+
+pub fn handle_set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let candy_machine = &mut ctx.accounts.candy_machine;
+    assert_authority_or_role(
+        candy_machine,
+        &ctx.accounts.roles_pda,
+        ctx.accounts.bullistic_authority.key,
+        Role::Pauser,
+    )?;
+
+    if paused {
+        set_feature_flag(&mut candy_machine.data.uuid, PAUSED_FEATURE_INDEX);
+    } else {
+        remove_feature_flag(&mut candy_machine.data.uuid, PAUSED_FEATURE_INDEX);
+    }
+
+    Ok(())
+}
@@ -2,8 +2,13 @@ use anchor_lang::prelude::*;
 
 use crate::constants::FREEZE_FEATURE_INDEX;
 use crate::{
-    is_feature_active, validate_candy_machine_allowlist_state, validate_mint_phase_times,
-    CandyError, CandyMachine, CandyMachineData,
+    is_feature_active, validate_candy_machine_allowlist_state,
+    validate_candy_machine_bot_tax_settings, validate_candy_machine_creator_overrides_state,
+    validate_candy_machine_min_items_to_succeed, validate_candy_machine_mint_order,
+    validate_candy_machine_mint_rate_limit_settings, validate_candy_machine_open_edition_state,
+    validate_candy_machine_programmable_nft_settings, validate_candy_machine_referral_settings,
+    validate_candy_machine_reveal_state, validate_candy_machine_usd_price_settings,
+    validate_mint_phase_times, CandyError, CandyMachine, CandyMachineData, MintRateLimitState,
 };
 
 /// Update the candy machine state.
@@ -25,16 +30,23 @@ pub fn handle_update_authority(
     ctx: Context<UpdateCandyMachine>,
     new_authority: Option<Pubkey>,
 ) -> Result<()> {
-    let candy_machine = &mut ctx.accounts.candy_machine;
-
     if let Some(new_auth) = new_authority {
-        if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX)
-            && candy_machine.bullistic_authority != new_auth
-        {
-            return err!(CandyError::NoChangingAuthorityWithFreeze);
-        }
-        candy_machine.bullistic_authority = new_auth;
+        update_candy_machine_authority(&mut ctx.accounts.candy_machine, new_auth)?;
+    }
+
+    Ok(())
+}
+
+pub fn update_candy_machine_authority(
+    candy_machine: &mut CandyMachine,
+    new_authority: Pubkey,
+) -> Result<()> {
+    if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX)
+        && candy_machine.bullistic_authority != new_authority
+    {
+        return err!(CandyError::NoChangingAuthorityWithFreeze);
     }
+    candy_machine.bullistic_authority = new_authority;
 
     Ok(())
 }
@@ -44,23 +56,59 @@ pub fn handle_update_candy_machine(
     ctx: Context<UpdateCandyMachine>,
     data: CandyMachineData,
 ) -> Result<()> {
-    let candy_machine = &mut ctx.accounts.candy_machine;
+    let treasury_wallet = ctx.accounts.treasury_wallet.key();
+    let treasury_mint = ctx
+        .remaining_accounts
+        .get(0)
+        .map(|account_info| account_info.key());
+
+    update_candy_machine_data(
+        &mut ctx.accounts.candy_machine,
+        data,
+        treasury_wallet,
+        treasury_mint,
+    )
+}
 
+/// Validate and apply new settings, shared by update_candy_machine and the
+/// multisig proposals.
+pub fn update_candy_machine_data(
+    candy_machine: &mut CandyMachine,
+    data: CandyMachineData,
+    treasury_wallet: Pubkey,
+    treasury_mint: Option<Pubkey>,
+) -> Result<()> {
     // Note: there is currently no validation to ensure an update doesn't change
     // any sale time settings after sales have already begun.
     validate_mint_phase_times(&data)?;
 
     validate_candy_machine_allowlist_state(&data)?;
 
-    if data.items_available != candy_machine.data.items_available && data.hidden_settings.is_none()
-    {
+    validate_candy_machine_open_edition_state(&data)?;
+
+    validate_candy_machine_bot_tax_settings(&data)?;
+
+    validate_candy_machine_reveal_state(&data)?;
+
+    validate_candy_machine_creator_overrides_state(&data)?;
+
+    validate_candy_machine_mint_rate_limit_settings(&data)?;
+
+    validate_candy_machine_min_items_to_succeed(
+        &data,
+        is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX),
+    )?;
+
+    validate_candy_machine_programmable_nft_settings(&data)?;
+
+    validate_candy_machine_mint_order(&data)?;
+
+    if data.items_available != candy_machine.data.items_available && data.has_config_lines() {
         return err!(CandyError::CannotChangeNumberOfLines);
     }
 
-    let treasury_mint = ctx
-        .remaining_accounts
-        .get(0)
-        .map(|account_info| account_info.key());
+    validate_candy_machine_referral_settings(&data, treasury_mint)?;
+    validate_candy_machine_usd_price_settings(&data, treasury_mint)?;
 
     if candy_machine.data.items_available > 0
         && candy_machine.data.hidden_settings.is_none()
@@ -69,6 +117,47 @@ pub fn handle_update_candy_machine(
         return err!(CandyError::CannotSwitchToHiddenSettings);
     }
 
+    // Open edition candy machines are created without space for config lines,
+    // so the settings can only be switched on, and only before the first mint.
+    if candy_machine.data.open_edition_settings.is_some() != data.open_edition_settings.is_some()
+        && (candy_machine.items_redeemed > 0 || candy_machine.data.open_edition_settings.is_some())
+    {
+        return err!(CandyError::CannotSwitchOpenEditionSettings);
+    }
+
+    if candy_machine.items_redeemed > 0
+        && candy_machine.data.on_chain_reveal_enabled != data.on_chain_reveal_enabled
+    {
+        return err!(CandyError::CannotSwitchOnChainReveal);
+    }
+
+    if candy_machine.items_redeemed > 0
+        && candy_machine.data.programmable_nft_settings.is_some()
+            != data.programmable_nft_settings.is_some()
+    {
+        return err!(CandyError::CannotSwitchProgrammableNftSettings);
+    }
+
+    // Buyers of an all-or-nothing sale rely on the reserve it started with.
+    if candy_machine.items_redeemed > 0
+        && candy_machine.data.min_items_to_succeed != data.min_items_to_succeed
+    {
+        return err!(CandyError::CannotChangeMinItemsToSucceed);
+    }
+
+    // The hidden settings hash is the provenance commitment checked on reveal.
+    if candy_machine.items_redeemed > 0 && candy_machine.data.on_chain_reveal_enabled {
+        let old_hash = candy_machine
+            .data
+            .hidden_settings
+            .as_ref()
+            .map(|hs| hs.hash);
+        let new_hash = data.hidden_settings.as_ref().map(|hs| hs.hash);
+        if old_hash != new_hash {
+            return err!(CandyError::CannotChangeRevealHash);
+        }
+    }
+
     let old_uuid = candy_machine.data.uuid.clone();
     if is_feature_active(&old_uuid, FREEZE_FEATURE_INDEX)
         && candy_machine.treasury_mint != treasury_mint
@@ -76,7 +165,21 @@ pub fn handle_update_candy_machine(
         return err!(CandyError::NoChangingTokenWithFreeze);
     }
 
-    candy_machine.treasury_wallet = ctx.accounts.treasury_wallet.key();
+    // Buckets cover a share of the window, so they're stale once it changes.
+    let old_window_slots = candy_machine
+        .data
+        .mint_rate_limit_settings
+        .as_ref()
+        .map(|settings| settings.window_slots);
+    let new_window_slots = data
+        .mint_rate_limit_settings
+        .as_ref()
+        .map(|settings| settings.window_slots);
+    if old_window_slots != new_window_slots {
+        candy_machine.mint_rate_limit_state = MintRateLimitState::default();
+    }
+
+    candy_machine.treasury_wallet = treasury_wallet;
     candy_machine.data = data;
     candy_machine.data.uuid = old_uuid;
     candy_machine.treasury_mint = treasury_mint;
@@ -2,15 +2,23 @@ use anchor_lang::prelude::*;
 use anchor_lang::AccountsClose;
 
 use crate::constants::{FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX};
-use crate::{cmp_pubkeys, is_feature_active, CandyError, CandyMachine, CollectionPda};
+use crate::{
+    assert_authority_or_role, cmp_pubkeys, find_multisig_pda, is_feature_active, CandyError,
+    CandyMachine, CollectionPda, Role, RolesPda,
+};
 
-/// Withdraw SOL from candy machine account.
+/// Withdraw SOL from candy machine account, to the authority wallet.
 #[derive(Accounts)]
 pub struct WithdrawFunds<'info> {
-    #[account(mut, close = bullistic_authority, has_one = bullistic_authority)]
+    #[account(mut, close = authority_wallet)]
     candy_machine: Account<'info, CandyMachine>,
-    #[account(mut, address = candy_machine.bullistic_authority)]
+    /// Bullistic authority, or the holder of the treasurer role.
+    #[account(mut)]
     bullistic_authority: Signer<'info>,
+    /// CHECK: the candy machine authority, which the funds always go to
+    #[account(mut, address = candy_machine.bullistic_authority)]
+    authority_wallet: UncheckedAccount<'info>,
+    roles_pda: Option<Account<'info, RolesPda>>,
     // > Only if collection
     // CollectionPda account
 }
@@ -20,6 +28,34 @@ pub fn handle_withdraw_funds<'info>(
 ) -> Result<()> {
     let authority = &ctx.accounts.bullistic_authority;
     let candy_machine = &ctx.accounts.candy_machine;
+    // Under multisig only the authority PDA may withdraw, through a proposal.
+    if cmp_pubkeys(
+        &candy_machine.bullistic_authority,
+        &find_multisig_pda(&candy_machine.key()).0,
+    ) {
+        return err!(CandyError::ActionRequiresProposal);
+    }
+    assert_authority_or_role(
+        candy_machine,
+        &ctx.accounts.roles_pda,
+        authority.key,
+        Role::Treasurer,
+    )?;
+
+    withdraw_candy_machine_funds(
+        candy_machine,
+        &ctx.accounts.authority_wallet.to_account_info(),
+        ctx.remaining_accounts,
+    )
+}
+
+/// Checks shared by withdraw_funds and the multisig proposals, which also
+/// close the collection PDA if passed. The caller closes the candy machine.
+pub fn withdraw_candy_machine_funds<'info>(
+    candy_machine: &Account<'info, CandyMachine>,
+    destination: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
     if is_feature_active(&candy_machine.data.uuid, FREEZE_FEATURE_INDEX) {
         return err!(CandyError::NoWithdrawWithFreeze);
     }
@@ -27,10 +63,10 @@ pub fn handle_withdraw_funds<'info>(
         return err!(CandyError::NoWithdrawWithFrozenFunds);
     }
 
-    if !ctx.remaining_accounts.is_empty() {
+    if !remaining_accounts.is_empty() {
         let candy_key = candy_machine.key();
         let seeds = [CollectionPda::PREFIX.as_bytes(), candy_key.as_ref()];
-        let collection_pda = &ctx.remaining_accounts[0];
+        let collection_pda = &remaining_accounts[0];
         if !cmp_pubkeys(
             &collection_pda.key(),
             &Pubkey::find_program_address(&seeds, &crate::id()).0,
@@ -39,7 +75,7 @@ pub fn handle_withdraw_funds<'info>(
         }
         let collection_pda: Account<CollectionPda> =
             Account::try_from(&collection_pda.to_account_info())?;
-        collection_pda.close(authority.to_account_info())?;
+        collection_pda.close(destination.clone())?;
     }
 
     Ok(())
@@ -1,7 +1,12 @@
-use crate::constants::FREEZE_FEE;
-use crate::CandyError;
+use crate::constants::{
+    BOT_FEE, COMPRESSED_FEATURE_INDEX, FREEZE_FEE, MAX_BOT_TAX_ALLOWED_PROGRAM_IDS,
+    MAX_MULTISIG_SIGNERS, ROLLING_FREEZE_FEATURE_INDEX,
+};
+use crate::{is_feature_active, CandyError};
 use anchor_lang::prelude::*;
-use mpl_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
+use mpl_token_metadata::state::{
+    MAX_CREATOR_LEN, MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_URI_LENGTH,
+};
 
 /// Candy machine state and config data.
 #[account]
@@ -13,6 +18,10 @@ pub struct CandyMachine {
     pub treasury_mint: Option<Pubkey>,
     pub items_redeemed: u64,
     pub data: CandyMachineData,
+    // Mints counted against the mint rate limit, if set.
+    pub mint_rate_limit_state: MintRateLimitState,
+    // Keys accepted as the bot signer authority, the default one if empty.
+    pub bot_signers: Vec<BotSigner>,
     // After this is additional account space which contains the config lines
     // and related data, which is deserialized manually as a byte array.
 }
@@ -40,12 +49,70 @@ pub struct CandyMachineData {
     pub bot_protection_enabled: bool,
     // Denotes the limit per address, 0 if unlimited.
     pub limit_per_address: u16,
-    // If true, minting in the pre-mint phase is in sequential order.
+    // If true, minting in the pre-mint phase is in sequential order,
+    // whatever the mint order.
     pub sequential_mint_order_enabled: bool,
     // Vector of merkle tree root hashes for address based allowlist.
     pub merkle_allowlist_root_list: Vec<[u8; 32]>,
     // SPL token allowlist settings.
     pub spl_token_allowlist_settings: Option<SplTokenAllowlistSettings>,
+    // If set, mints print numbered editions from a single master edition
+    // instead of using config lines.
+    pub open_edition_settings: Option<OpenEditionSettings>,
+    // Bot tax policy. If not set, BOT_FEE is paid to the candy machine and
+    // every transaction check is enabled.
+    pub bot_tax_settings: Option<BotTaxSettings>,
+    // If true, hidden settings NFTs keep the candy machine creator as update
+    // authority until they are revealed on-chain from the reveal PDA.
+    pub on_chain_reveal_enabled: bool,
+    // If set, buyers can mint with a voucher signed by this key. The voucher
+    // is verified from an Ed25519 program instruction right before the mint.
+    pub voucher_signer: Option<Pubkey>,
+    // If set, holders burn an NFT from a verified collection to mint, in
+    // place of the mint price or of the allowlist proof.
+    pub burn_to_mint_settings: Option<BurnToMintSettings>,
+    // Share of the mint price, in basis points, which goes to the referrer
+    // passed in the mint ix. 0 if referrals are disabled.
+    pub referral_fee_basis_points: u16,
+    // If enabled, config lines may override the creators and royalties of
+    // their NFT, stored in the creator overrides PDA.
+    pub creator_overrides_enabled: bool,
+    // If set, prices are in millionths of a US dollar and converted to
+    // lamports at mint time using the price feed.
+    pub usd_price_settings: Option<UsdPriceSettings>,
+    // If set, caps how many NFTs can be minted in a rolling window of slots.
+    pub mint_rate_limit_settings: Option<MintRateLimitSettings>,
+    // If set, the sale is all-or-nothing: if fewer items are minted by the
+    // end of the public sale, buyers can refund their frozen NFTs.
+    pub min_items_to_succeed: Option<u64>,
+    // If set, NFTs are minted as programmable NFTs, with royalties enforced
+    // by the optional rule set, and frozen by locking their token record.
+    pub programmable_nft_settings: Option<ProgrammableNftSettings>,
+    // If set, mints also count towards the supply cap and per-buyer limit of
+    // this drop account, shared with the other candy machines of the drop.
+    pub drop: Option<Pubkey>,
+    // Order in which config lines are minted.
+    pub mint_order: MintOrder,
+}
+
+impl CandyMachineData {
+    /// Candy machines using hidden settings or open editions do not store
+    /// config lines.
+    pub fn has_config_lines(&self) -> bool {
+        self.hidden_settings.is_none() && self.open_edition_settings.is_none()
+    }
+
+    pub fn get_bot_tax_settings(&self) -> BotTaxSettings {
+        self.bot_tax_settings.clone().unwrap_or_default()
+    }
+
+    /// Whether burning an NFT stands in for the allowlist proof.
+    pub fn burns_for_allowlist(&self) -> bool {
+        matches!(
+            &self.burn_to_mint_settings,
+            Some(settings) if settings.mode == BurnToMintMode::ReplacesAllowlist
+        )
+    }
 }
 
 impl CandyMachine {
@@ -57,6 +124,34 @@ impl CandyMachine {
         }
     }
 
+    /// Whether the candy machine sold its minimum number of items to succeed,
+    /// which is always the case when it doesn't have one.
+    pub fn is_reserve_met(&self) -> bool {
+        self.data
+            .min_items_to_succeed
+            .map_or(true, |min_items_to_succeed| {
+                self.items_redeemed >= min_items_to_succeed
+            })
+    }
+
+    /// Whether the public sale ended without selling the minimum number of
+    /// items to succeed.
+    pub fn has_sale_failed(&self, now: i64) -> bool {
+        now >= self.data.public_sale_end_time && !self.is_reserve_met()
+    }
+
+    /// Whether each frozen NFT's lock runs from its own mint time instead of
+    /// the first mint.
+    pub fn has_rolling_freeze(&self) -> bool {
+        is_feature_active(&self.data.uuid, ROLLING_FREEZE_FEATURE_INDEX)
+    }
+
+    /// Whether NFTs are minted as compressed NFTs into the candy machine's
+    /// Bubblegum tree.
+    pub fn is_compressed(&self) -> bool {
+        is_feature_active(&self.data.uuid, COMPRESSED_FEATURE_INDEX)
+    }
+
     pub fn get_mint_phase(&self, now: i64) -> MintPhase {
         let allowlist_sale_start_time = self.data.allowlist_sale_start_time;
         let public_sale_start_time = self.data.public_sale_start_time;
@@ -83,6 +178,12 @@ impl CandyMachine {
     }
 
     pub fn get_mint_price(&self, mint_phase: &MintPhase) -> u64 {
+        if let Some(burn_to_mint_settings) = &self.data.burn_to_mint_settings {
+            if burn_to_mint_settings.mode == BurnToMintMode::ReplacesPayment {
+                return 0;
+            }
+        }
+
         let price = self.data.price;
         let premint_price = self.data.premint_price;
         let allowlist_price = self.data.allowlist_price;
@@ -126,6 +227,66 @@ pub const HIDDEN_SETTINGS_SPACE: usize = 1 + // Option
 4 + MAX_URI_LENGTH + // uri length,
 32; // hash
 
+/// Open edition settings for printing editions from a single master edition.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct OpenEditionSettings {
+    /// Mint of the master edition NFT, held by the candy machine creator PDA.
+    pub master_edition_mint: Pubkey,
+}
+
+pub const OPEN_EDITION_SETTINGS_SPACE: usize = 1 + // Option
+32; // master_edition_mint
+
+/// Bot tax policy applied when a mint transaction looks suspicious.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BotTaxSettings {
+    /// Lamports charged for each taxed mint attempt.
+    pub fee: u64,
+    /// Account receiving bot taxes, the candy machine itself if not set. It
+    /// must be passed as the last remaining account of the mint ix.
+    pub destination: Option<Pubkey>,
+    /// Extra programs allowed to have instructions in the mint transaction,
+    /// e.g. memo or priority fee relay programs.
+    pub allowed_program_ids: Vec<Pubkey>,
+    /// Tax mints which aren't a top level instruction of the transaction.
+    pub check_top_level_instruction: bool,
+    /// Fail mints followed by an instruction from a program which isn't
+    /// allowed.
+    pub check_trailing_instructions: bool,
+    /// Tax mints whose transaction has instructions from programs which
+    /// aren't allowed.
+    pub check_transaction_programs: bool,
+}
+
+impl Default for BotTaxSettings {
+    fn default() -> Self {
+        BotTaxSettings {
+            fee: BOT_FEE,
+            destination: None,
+            allowed_program_ids: vec![],
+            check_top_level_instruction: true,
+            check_trailing_instructions: true,
+            check_transaction_programs: true,
+        }
+    }
+}
+
+impl BotTaxSettings {
+    pub fn is_program_allowed(&self, program_id: &Pubkey) -> bool {
+        self.allowed_program_ids
+            .iter()
+            .any(|allowed_program_id| allowed_program_id == program_id)
+    }
+}
+
+pub const BOT_TAX_SETTINGS_SPACE: usize = 1 + // Option
+8 + // fee
+33 + // optional destination
+4 + 32 * MAX_BOT_TAX_ALLOWED_PROGRAM_IDS + // allowed_program_ids vec
+1 + // check_top_level_instruction
+1 + // check_trailing_instructions
+1; // check_transaction_programs
+
 pub const BUYER_INFO_ACCOUNT_PREFIX: &str = "buyer_info_account";
 
 #[account]
@@ -135,12 +296,48 @@ pub struct BuyerInfoAccount {
     pub number_bought_merkle_allowlist_phase: u16,
     /// Number bought during the public phase.
     pub number_bought_public_phase: u16,
+    /// Wallet the account is derived from. Along with the payer, it is
+    /// unset for accounts created before they were recorded.
+    pub buyer: Pubkey,
+    /// Wallet which paid the rent of the account, it gets it back when the
+    /// account is closed. The buyer paid for accounts where it is unset.
+    pub payer: Pubkey,
 }
 
 pub const BUYER_INFO_ACCOUNT_SPACE: usize = 8 + // Discriminator
 2 + // number_bought_merkle_allowlist_phase
 2 + // number_bought_public_phase
-64; // padding
+32 + // buyer
+32; // payer
+
+/// Mint voucher signed off-chain by the candy machine voucher signer. The
+/// borsh serialized voucher is the message of the Ed25519 instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintVoucher {
+    pub candy_machine: Pubkey,
+    pub buyer: Pubkey,
+    /// Maximum number of mints allowed with this voucher.
+    pub max_amount: u16,
+    /// Mint price for the buyer, replacing the mint phase price.
+    pub price: u64,
+    /// Unix timestamp after which the voucher can no longer be used.
+    pub expiry: i64,
+    /// Distinguishes vouchers issued to the same buyer.
+    pub nonce: u64,
+}
+
+pub const VOUCHER_NONCE_PREFIX: &str = "voucher_nonce";
+
+/// Tracks how many mints were made with a voucher, to prevent replays.
+#[account]
+#[derive(Default)]
+pub struct VoucherNonce {
+    /// Number of mints made with the voucher.
+    pub number_minted: u16,
+}
+
+pub const VOUCHER_NONCE_SPACE: usize = 8 + // Discriminator
+2; // number_minted
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MintPhase {
@@ -173,6 +370,44 @@ pub enum SplTokenAllowlistMode {
     NeverBurn,
 }
 
+/// Burn-to-mint settings for redeeming NFTs from another collection.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BurnToMintSettings {
+    pub mode: BurnToMintMode,
+    /// Mint of the collection the burned NFTs must be verified members of.
+    pub collection_mint: Pubkey,
+}
+
+pub const BURN_TO_MINT_SETTINGS_SPACE: usize = 1 + // Option
+1 + // mode
+32; // collection_mint
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Eq, PartialEq, Debug)]
+pub enum BurnToMintMode {
+    /// Every mint burns an NFT and the mint price is waived.
+    ReplacesPayment,
+    /// Mints in the allowlist phase burn an NFT instead of showing an
+    /// allowlist proof. The mint price is still paid.
+    ReplacesAllowlist,
+}
+
+/// Settings for candy machines priced in USD and paid in SOL.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct UsdPriceSettings {
+    /// SOL/USD price feed account, using the Pyth price account layout.
+    pub price_feed: Pubkey,
+    /// Mints fail if the price feed was last published longer ago than this.
+    pub max_price_age_seconds: u32,
+    /// Mints fail if the price feed confidence interval is wider than this
+    /// share of the price.
+    pub max_confidence_basis_points: u16,
+}
+
+pub const USD_PRICE_SETTINGS_SPACE: usize = 1 + // Option
+32 + // price_feed
+4 + // max_price_age_seconds
+2; // max_confidence_basis_points
+
 /// Collection PDA account
 #[account]
 #[derive(Default, Debug)]
@@ -185,6 +420,297 @@ impl CollectionPda {
     pub const PREFIX: &'static str = "collection";
 }
 
+/// Reveal PDA account
+///
+/// Holds the final config lines of a hidden settings candy machine using
+/// on-chain reveal. The header is followed by the revealed URI of each item,
+/// in mint order.
+#[account]
+#[derive(Default, Debug)]
+pub struct RevealPda {
+    pub candy_machine: Pubkey,  // 32
+    pub lines_uploaded: u32,    // 4
+    pub running_hash: [u8; 32], // 32
+    pub verified: bool,         // 1
+}
+
+impl RevealPda {
+    pub const SIZE: usize = 8 + 32 + 4 + 32 + 1;
+
+    pub const LINE_SIZE: usize = 4 + MAX_URI_LENGTH;
+
+    pub const PREFIX: &'static str = "reveal";
+
+    pub fn space_for(lines: usize) -> usize {
+        RevealPda::SIZE + lines * RevealPda::LINE_SIZE
+    }
+}
+
+/// Creator overrides PDA account
+///
+/// Holds the creators and royalties of config lines which don't use the candy
+/// machine ones, e.g. for collab drops. The header is followed by one slot per
+/// config line, in config line order.
+#[account]
+#[derive(Default, Debug)]
+pub struct CreatorOverridesPda {
+    pub candy_machine: Pubkey, // 32
+}
+
+impl CreatorOverridesPda {
+    pub const SIZE: usize = 8 + 32;
+
+    // 1 byte flag for whether the line has an override, then the override.
+    pub const LINE_SIZE: usize = 1 + 2 + 4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN;
+
+    pub const PREFIX: &'static str = "creator_overrides";
+
+    pub fn space_for(lines: usize) -> usize {
+        CreatorOverridesPda::SIZE + lines * CreatorOverridesPda::LINE_SIZE
+    }
+}
+
+/// Creators and royalties used instead of the candy machine ones for a config
+/// line.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CreatorOverride {
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+}
+
+/// Roles PDA account
+///
+/// Keys allowed to run some of the admin instructions of a candy machine on
+/// top of the bullistic authority, which owns the candy machine and manages
+/// the roles.
+#[account]
+#[derive(Default, Debug)]
+pub struct RolesPda {
+    pub candy_machine: Pubkey,              // 32
+    pub config_loader: Option<Pubkey>,      // 33
+    pub allowlist_manager: Option<Pubkey>,  // 33
+    pub pauser: Option<Pubkey>,             // 33
+    pub treasurer: Option<Pubkey>,          // 33
+    pub bot_signer_manager: Option<Pubkey>, // 33
+}
+
+impl RolesPda {
+    pub const SIZE: usize = 8 + 32 + 33 + 33 + 33 + 33 + 33;
+
+    pub const PREFIX: &'static str = "roles";
+
+    pub fn get_role(&self, role: Role) -> Option<Pubkey> {
+        match role {
+            Role::ConfigLoader => self.config_loader,
+            Role::AllowlistManager => self.allowlist_manager,
+            Role::Pauser => self.pauser,
+            Role::Treasurer => self.treasurer,
+            Role::BotSignerManager => self.bot_signer_manager,
+        }
+    }
+
+    pub fn set_role(&mut self, role: Role, key: Option<Pubkey>) {
+        match role {
+            Role::ConfigLoader => self.config_loader = key,
+            Role::AllowlistManager => self.allowlist_manager = key,
+            Role::Pauser => self.pauser = key,
+            Role::Treasurer => self.treasurer = key,
+            Role::BotSignerManager => self.bot_signer_manager = key,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Role {
+    /// Adds config lines.
+    ConfigLoader,
+    /// Appends and clears merkle allowlist roots.
+    AllowlistManager,
+    /// Pauses and resumes minting.
+    Pauser,
+    /// Withdraws the candy machine funds.
+    Treasurer,
+    /// Rotates the bot signer keys.
+    BotSignerManager,
+}
+
+/// Multisig PDA account
+///
+/// Signers that approve the sensitive admin actions of a candy machine once
+/// its bullistic authority has been handed over to this PDA. Actions go
+/// through proposals, which execute once approved by the threshold of signers
+/// and the timelock has passed.
+#[account]
+#[derive(Default, Debug)]
+pub struct MultisigPda {
+    pub candy_machine: Pubkey, // 32
+    pub signers: Vec<Pubkey>,  // 4 + 32 * MAX_MULTISIG_SIGNERS
+    pub threshold: u8,         // 1
+    pub timelock_seconds: i64, // 8
+    pub proposal_count: u64,   // 8
+}
+
+impl MultisigPda {
+    pub const SIZE: usize = 8 + 32 + 4 + 32 * MAX_MULTISIG_SIGNERS + 1 + 8 + 8;
+
+    pub const PREFIX: &'static str = "multisig";
+
+    pub fn is_signer(&self, key: &Pubkey) -> bool {
+        self.signers.iter().any(|signer| signer == key)
+    }
+}
+
+/// Proposal PDA account
+///
+/// An action proposed to the multisig of a candy machine, with the signers
+/// that approved it so far.
+#[account]
+#[derive(Debug)]
+pub struct Proposal {
+    pub candy_machine: Pubkey,    // 32
+    pub index: u64,               // 8
+    pub proposer: Pubkey,         // 32
+    pub approvals: Vec<Pubkey>,   // 4 + 32 * MAX_MULTISIG_SIGNERS
+    pub approved_at: Option<i64>, // 9
+    pub action: ProposalAction,   // variable, see space_for
+}
+
+impl Proposal {
+    pub const SIZE: usize = 8 + 32 + 8 + 32 + 4 + 32 * MAX_MULTISIG_SIGNERS + 9;
+
+    pub const PREFIX: &'static str = "proposal";
+
+    pub fn space_for(action: &ProposalAction) -> usize {
+        // Serializing into a vec can't fail.
+        Proposal::SIZE + action.try_to_vec().map_or(0, |action| action.len())
+    }
+}
+
+// Proposals are sized for their action, and anchor deserializes a zeroed
+// account on init, so the first variant must be one of the smallest.
+#[allow(clippy::large_enum_variant)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum ProposalAction {
+    /// Same as update_authority. Handing the authority to a single key turns
+    /// the multisig mode off.
+    UpdateAuthority { new_authority: Pubkey },
+    /// Same as withdraw_funds, closing the candy machine to the destination.
+    WithdrawFunds { destination: Pubkey },
+    /// Same as update_candy_machine.
+    UpdateCandyMachine {
+        data: CandyMachineData,
+        treasury_wallet: Pubkey,
+        treasury_mint: Option<Pubkey>,
+    },
+    /// Change the signers, threshold or timelock of the multisig.
+    UpdateMultisig {
+        signers: Vec<Pubkey>,
+        threshold: u8,
+        timelock_seconds: i64,
+    },
+    /// Same as rotate_bot_signers.
+    RotateBotSigners { bot_signers: Vec<BotSigner> },
+}
+
+/// Referral PDA account
+///
+/// Holds the referral earnings of a referrer for a candy machine as lamports,
+/// until the referrer claims them.
+#[account]
+#[derive(Default, Debug)]
+pub struct ReferralAccount {
+    pub candy_machine: Pubkey, // 32
+    pub referrer: Pubkey,      // 32
+    pub number_referred: u64,  // 8
+    pub total_earned: u64,     // 8
+    pub total_claimed: u64,    // 8
+}
+
+impl ReferralAccount {
+    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8;
+
+    pub const PREFIX: &'static str = "referral";
+}
+
+/// Refund receipt PDA account
+///
+/// Records the amount escrowed in the freeze PDA for an NFT of an
+/// all-or-nothing sale, which is paid back if the sale fails.
+#[account]
+#[derive(Default, Debug)]
+pub struct RefundReceipt {
+    pub candy_machine: Pubkey, // 32
+    pub mint: Pubkey,          // 32
+    pub amount: u64,           // 8
+}
+
+impl RefundReceipt {
+    pub const SIZE: usize = 8 + 32 + 32 + 8;
+
+    pub const PREFIX: &'static str = "refund_receipt";
+}
+
+/// Drop account
+///
+/// Shared by the candy machines a drop is split across, e.g. one per
+/// currency, to enforce a combined supply cap and per-buyer limit.
+#[account]
+#[derive(Default, Debug)]
+pub struct DropAccount {
+    pub authority: Pubkey,      // 32
+    pub supply_cap: u64,        // 8
+    pub items_minted: u64,      // 8
+    pub limit_per_address: u16, // 2
+}
+
+impl DropAccount {
+    pub const SIZE: usize = 8 + 32 + 8 + 8 + 2;
+}
+
+/// Number of NFTs a buyer minted across the candy machines of a drop.
+#[account]
+#[derive(Default, Debug)]
+pub struct DropBuyerInfoAccount {
+    pub number_bought: u16, // 2
+}
+
+impl DropBuyerInfoAccount {
+    pub const SIZE: usize = 8 + 2;
+
+    pub const PREFIX: &'static str = "drop_buyer_info";
+}
+
+/// Records when an NFT was frozen with a rolling freeze, which starts its
+/// own lock.
+#[account]
+#[derive(Default, Debug)]
+pub struct FreezeRecord {
+    pub candy_machine: Pubkey, // 32
+    pub mint: Pubkey,          // 32
+    pub frozen_at: i64,        // 8
+}
+
+impl FreezeRecord {
+    pub const SIZE: usize = 8 + 32 + 32 + 8;
+
+    pub const PREFIX: &'static str = "freeze_record";
+}
+
+/// Owns the Bubblegum merkle tree compressed NFTs are minted into. It is the
+/// tree creator and delegate, so only the candy machine can mint into it.
+#[account]
+#[derive(Default, Debug)]
+pub struct CompressedTree {
+    pub candy_machine: Pubkey, // 32
+    pub merkle_tree: Pubkey,   // 32
+}
+
+impl CompressedTree {
+    pub const SIZE: usize = 8 + 32 + 32;
+
+    pub const PREFIX: &'static str = "compressed_tree";
+}
+
 /// Collection PDA account
 #[account]
 #[derive(Default, Debug, PartialEq, Eq)]
@@ -196,6 +722,8 @@ pub struct FreezePda {
     pub mint_start: Option<i64>, // 1 + 8
     pub freeze_time: i64,        // 8
     pub freeze_fee: u64,         // 8
+    // Latest mint frozen with a rolling freeze, whose lock expires last.
+    pub last_frozen_at: Option<i64>, // 1 + 8
 }
 
 impl FreezePda {
@@ -210,11 +738,20 @@ impl FreezePda {
         self.mint_start = mint_start;
         self.freeze_time = freeze_time;
         self.freeze_fee = FREEZE_FEE;
+        self.last_frozen_at = None;
     }
 
     pub fn thaw_eligible(&self, current_timestamp: i64, candy_machine: &CandyMachine) -> bool {
+        // All-or-nothing sales keep NFTs frozen until the reserve is met, so
+        // they can still be refunded if it never is.
+        if !candy_machine.is_reserve_met() {
+            return false;
+        }
         if self.allow_thaw || candy_machine.items_redeemed >= candy_machine.data.items_available {
             return true;
+        } else if candy_machine.has_rolling_freeze() {
+            // Each NFT's lock is checked against its own freeze record.
+            return false;
         } else if let Some(start_timestamp) = self.mint_start {
             if current_timestamp >= start_timestamp + self.freeze_time {
                 return true;
@@ -223,6 +760,24 @@ impl FreezePda {
         false
     }
 
+    /// Whether an NFT frozen at `frozen_at` can be thawed with a rolling freeze.
+    pub fn nft_thaw_eligible(
+        &self,
+        current_timestamp: i64,
+        candy_machine: &CandyMachine,
+        frozen_at: i64,
+    ) -> bool {
+        self.thaw_eligible(current_timestamp, candy_machine)
+            || (candy_machine.is_reserve_met() && current_timestamp >= frozen_at + self.freeze_time)
+    }
+
+    /// Whether the lock of every NFT frozen with a rolling freeze has expired.
+    pub fn rolling_locks_expired(&self, current_timestamp: i64) -> bool {
+        self.last_frozen_at.map_or(false, |last_frozen_at| {
+            current_timestamp >= last_frozen_at + self.freeze_time
+        })
+    }
+
     pub fn assert_from_candy(&self, candy_machine: &Pubkey) -> Result<()> {
         if &self.candy_machine != candy_machine {
             return err!(CandyError::FreezePdaMismatch);
@@ -230,3 +785,191 @@ impl FreezePda {
         Ok(())
     }
 }
+
+/// Caps how many NFTs can be minted in a rolling window of slots.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MintRateLimitSettings {
+    /// Maximum number of mints in the window.
+    pub max_mints: u32,
+    /// Length of the window, in slots.
+    pub window_slots: u64,
+}
+
+pub const MINT_RATE_LIMIT_SETTINGS_SPACE: usize = 1 + // Option
+4 + // max_mints
+8; // window_slots
+
+/// Mints programmable NFTs instead of classic NFTs.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ProgrammableNftSettings {
+    /// Token metadata authorization rule set checked on transfers, if any.
+    pub rule_set: Option<Pubkey>,
+}
+
+pub const PROGRAMMABLE_NFT_SETTINGS_SPACE: usize = 1 + // Option
+33; // rule_set
+
+/// Order in which config lines are picked by mints.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Eq, PartialEq, Debug)]
+pub enum MintOrder {
+    /// Start from an index picked from the most recent slot hash.
+    Random,
+    /// Mint config lines from first to last.
+    Sequential,
+    /// Mint config lines from last to first.
+    ReverseSequential,
+    /// Allowlist mints draw only from the first `allowlist_items` config
+    /// lines, other mints draw from the rest first. Both are in random order.
+    ReservedRangePerPhase { allowlist_items: u64 },
+}
+
+impl Default for MintOrder {
+    fn default() -> Self {
+        MintOrder::Random
+    }
+}
+
+pub const MINT_ORDER_SPACE: usize = 1 + // variant
+8; // allowlist_items
+
+/// Number of mints in a period of the mint rate limit window.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct MintRateLimitBucket {
+    pub period: u64,
+    pub count: u32,
+}
+
+/// Ring buffer of mint counts. The window is split into one period per
+/// bucket, so it rolls forward a period at a time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, Debug)]
+pub struct MintRateLimitState {
+    pub buckets: [MintRateLimitBucket; 8],
+}
+
+pub const MINT_RATE_LIMIT_STATE_SPACE: usize = 8 * 12; // buckets of period and count
+
+impl MintRateLimitState {
+    /// Count a mint made at the given slot, failing if the window already
+    /// holds the maximum number of mints.
+    pub fn record_mint(&mut self, settings: &MintRateLimitSettings, slot: u64) -> Result<()> {
+        let num_buckets = self.buckets.len() as u64;
+        let period_slots = std::cmp::max(
+            1,
+            settings.window_slots.saturating_add(num_buckets - 1) / num_buckets,
+        );
+        let period = slot / period_slots;
+
+        let mints_in_window: u64 = self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.period.saturating_add(num_buckets) > period)
+            .map(|bucket| bucket.count as u64)
+            .sum();
+        if mints_in_window >= settings.max_mints as u64 {
+            return err!(CandyError::MintRateLimitExceeded);
+        }
+
+        let bucket = &mut self.buckets[(period % num_buckets) as usize];
+        if bucket.period != period {
+            *bucket = MintRateLimitBucket { period, count: 0 };
+        }
+        bucket.count = bucket
+            .count
+            .checked_add(1)
+            .ok_or(CandyError::NumericalOverflowError)?;
+
+        Ok(())
+    }
+}
+
+/// A key accepted as the bot signer authority of a candy machine, until it
+/// expires.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BotSigner {
+    pub key: Pubkey,
+    pub expires_at: Option<i64>,
+}
+
+pub const BOT_SIGNER_SPACE: usize = 32 + 9;
+
+impl BotSigner {
+    pub fn is_valid_at(&self, key: &Pubkey, now: i64) -> bool {
+        self.key == *key && self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+}
+
+/// Candy machine as stored before the open edition settings and the fields
+/// added after them. Only read to migrate candy machines to the current
+/// layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyCandyMachine {
+    pub bullistic_authority: Pubkey,
+    pub creator_authority: Pubkey,
+    pub treasury_wallet: Pubkey,
+    pub treasury_mint: Option<Pubkey>,
+    pub items_redeemed: u64,
+    pub data: LegacyCandyMachineData,
+}
+
+/// Candy machine settings data of the legacy layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct LegacyCandyMachineData {
+    pub uuid: String,
+    pub price: u64,
+    pub premint_price: Option<u64>,
+    pub allowlist_price: Option<u64>,
+    pub symbol: String,
+    pub seller_fee_basis_points: u16,
+    pub max_supply: u64,
+    pub items_available: u64,
+    pub is_mutable: bool,
+    pub allowlist_sale_start_time: Option<i64>,
+    pub public_sale_start_time: i64,
+    pub public_sale_end_time: i64,
+    pub creators: Vec<Creator>,
+    pub omni_mint_wallets: Vec<Pubkey>,
+    pub hidden_settings: Option<HiddenSettings>,
+    pub bot_protection_enabled: bool,
+    pub limit_per_address: u16,
+    pub sequential_mint_order_enabled: bool,
+    pub merkle_allowlist_root_list: Vec<[u8; 32]>,
+    pub spl_token_allowlist_settings: Option<SplTokenAllowlistSettings>,
+}
+
+impl From<LegacyCandyMachine> for CandyMachine {
+    /// Fields added since the legacy layout keep their default values.
+    fn from(legacy: LegacyCandyMachine) -> Self {
+        let data = legacy.data;
+        CandyMachine {
+            bullistic_authority: legacy.bullistic_authority,
+            creator_authority: legacy.creator_authority,
+            treasury_wallet: legacy.treasury_wallet,
+            treasury_mint: legacy.treasury_mint,
+            items_redeemed: legacy.items_redeemed,
+            data: CandyMachineData {
+                uuid: data.uuid,
+                price: data.price,
+                premint_price: data.premint_price,
+                allowlist_price: data.allowlist_price,
+                symbol: data.symbol,
+                seller_fee_basis_points: data.seller_fee_basis_points,
+                max_supply: data.max_supply,
+                items_available: data.items_available,
+                is_mutable: data.is_mutable,
+                allowlist_sale_start_time: data.allowlist_sale_start_time,
+                public_sale_start_time: data.public_sale_start_time,
+                public_sale_end_time: data.public_sale_end_time,
+                creators: data.creators,
+                omni_mint_wallets: data.omni_mint_wallets,
+                hidden_settings: data.hidden_settings,
+                bot_protection_enabled: data.bot_protection_enabled,
+                limit_per_address: data.limit_per_address,
+                sequential_mint_order_enabled: data.sequential_mint_order_enabled,
+                merkle_allowlist_root_list: data.merkle_allowlist_root_list,
+                spl_token_allowlist_settings: data.spl_token_allowlist_settings,
+                ..CandyMachineData::default()
+            },
+            ..CandyMachine::default()
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use std::convert::TryFrom;
 use std::str::from_utf8_unchecked;
 
 use std::result::Result as StandardResult;
 
 use anchor_lang::prelude::*;
+use arrayref::array_ref;
+use mpl_token_metadata::state::MAX_CREATOR_LIMIT;
 use solana_program::{
     account_info::AccountInfo,
     program::{invoke, invoke_signed},
@@ -10,8 +13,13 @@ use solana_program::{
     program_pack::{IsInitialized, Pack},
     pubkey::{Pubkey, PUBKEY_BYTES},
     system_instruction,
+    sysvar::instructions::get_instruction_relative,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::{
+    transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+    BaseState, BaseStateWithExtensions, StateWithExtensions,
 };
-use spl_associated_token_account::get_associated_token_address;
 
 #[cfg(feature = "use-test-anti-bot-authority")]
 use crate::constants::ANTI_BOT_DEV_AUTHORITY;
@@ -19,7 +27,18 @@ use crate::constants::ANTI_BOT_DEV_AUTHORITY;
 #[cfg(not(feature = "use-test-anti-bot-authority"))]
 use crate::constants::ANTI_BOT_MAINNET_AUTHORITY;
 
-use crate::{BuyerMerkleAllowlistProofData, CandyError, CandyMachine, CandyMachineData, MintPhase};
+use crate::constants::{
+    MAX_BOT_SIGNERS, MAX_BOT_TAX_ALLOWED_PROGRAM_IDS, MAX_MULTISIG_SIGNERS, MAX_MULTISIG_TIMELOCK,
+    PRICE_FEED_ACCOUNT_TYPE, PRICE_FEED_CONF_OFFSET, PRICE_FEED_EXPO_OFFSET, PRICE_FEED_MAGIC,
+    PRICE_FEED_MIN_SIZE, PRICE_FEED_PRICE_OFFSET, PRICE_FEED_STATUS_OFFSET,
+    PRICE_FEED_STATUS_TRADING, PRICE_FEED_TIMESTAMP_OFFSET, USD_PRICE_DECIMALS,
+};
+use crate::{
+    BotSigner, BotTaxSettings, BuyerMerkleAllowlistProofData, CandyError, CandyMachine,
+    CandyMachineData, CompressedTree, ConfigLine, CreatorOverride, CreatorOverridesPda,
+    DropBuyerInfoAccount, FreezeRecord, MintOrder, MintPhase, MintVoucher, MultisigPda, Proposal,
+    ReferralAccount, RefundReceipt, Role, RolesPda, UsdPriceSettings, VOUCHER_NONCE_PREFIX,
+};
 
 pub fn assert_initialized<T: Pack + IsInitialized>(account_info: &AccountInfo) -> Result<T> {
     let account: T = T::unpack_unchecked(&account_info.data.borrow())?;
@@ -34,18 +53,16 @@ pub fn cmp_pubkeys(a: &Pubkey, b: &Pubkey) -> bool {
     sol_memcmp(a.as_ref(), b.as_ref(), PUBKEY_BYTES) == 0
 }
 
-pub fn is_omni_minter<'info>(
-    buyer: &Signer<'info>,
-    candy_machine: &Account<'info, CandyMachine>,
-) -> bool {
-    candy_machine.data.omni_mint_wallets.contains(&buyer.key())
+pub fn is_omni_minter<'info>(buyer: &Pubkey, candy_machine: &Account<'info, CandyMachine>) -> bool {
+    candy_machine.data.omni_mint_wallets.contains(buyer)
 }
 
 pub fn validate_mint_phase<'info>(
-    buyer: &Signer<'info>,
+    buyer: &Pubkey,
     mint_phase: &MintPhase,
     candy_machine: &Account<'info, CandyMachine>,
     buyer_merkle_allowlist_proof_data: &Option<BuyerMerkleAllowlistProofData>,
+    has_mint_voucher: bool,
 ) -> StandardResult<(), CandyError> {
     if is_omni_minter(buyer, candy_machine) && mint_phase != &MintPhase::Expired {
         return Ok(());
@@ -63,7 +80,9 @@ pub fn validate_mint_phase<'info>(
         }
         MintPhase::Allowlist => {
             let allowlist_settings_present = buyer_merkle_allowlist_proof_data.is_some()
-                || candy_machine.data.spl_token_allowlist_settings.is_some();
+                || candy_machine.data.spl_token_allowlist_settings.is_some()
+                || candy_machine.data.burns_for_allowlist()
+                || has_mint_voucher;
 
             if !allowlist_settings_present {
                 Err(CandyError::CandyMachineAllowlistSaleNotLive)
@@ -100,13 +119,104 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<()> {
         Ok(())
     }
 }
+/// Payment and allowlist mints may live under either the legacy token program
+/// or Token-2022.
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    cmp_pubkeys(program_id, &spl_token::id()) || cmp_pubkeys(program_id, &spl_token_2022::id())
+}
+
+pub fn assert_is_token_program(token_program: &AccountInfo) -> Result<()> {
+    if !is_token_program(token_program.key) {
+        return err!(CandyError::IncorrectTokenProgram);
+    }
+
+    Ok(())
+}
+
+fn unpack_token_state<S: BaseState>(account_info: &AccountInfo) -> Result<S> {
+    if !is_token_program(account_info.owner) {
+        return err!(CandyError::IncorrectOwner);
+    }
+
+    let data = account_info.data.borrow();
+    let state =
+        StateWithExtensions::<S>::unpack(&data).map_err(|_| error!(CandyError::Uninitialized))?;
+
+    Ok(state.base)
+}
+
+/// Unpacks a mint owned by either token program.
+pub fn unpack_token_mint(mint_info: &AccountInfo) -> Result<spl_token_2022::state::Mint> {
+    unpack_token_state::<spl_token_2022::state::Mint>(mint_info)
+}
+
+/// Unpacks a token account owned by either token program.
+pub fn unpack_token_account(token_account: &AccountInfo) -> Result<spl_token_2022::state::Account> {
+    unpack_token_state::<spl_token_2022::state::Account>(token_account)
+}
+
+/// Returns the amount the buyer has to send so that `amount` arrives at the
+/// destination after any Token-2022 transfer fee is withheld.
+pub fn get_transfer_amount_with_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    if !cmp_pubkeys(mint_info.owner, &spl_token_2022::id()) {
+        return Ok(amount);
+    }
+
+    let data = mint_info.data.borrow();
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)
+        .map_err(|_| error!(CandyError::Uninitialized))?;
+    let transfer_fee_config = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config,
+        Err(_) => return Ok(amount),
+    };
+
+    let transfer_fee = transfer_fee_config.get_epoch_fee(Clock::get()?.epoch);
+    let basis_points = u64::from(u16::from(transfer_fee.transfer_fee_basis_points));
+    let maximum_fee = u64::from(transfer_fee.maximum_fee);
+    if basis_points == 0 || maximum_fee == 0 {
+        return Ok(amount);
+    }
+
+    let max_basis_points = u64::from(MAX_FEE_BASIS_POINTS);
+    let fee = if basis_points >= max_basis_points {
+        maximum_fee
+    } else {
+        // Smallest gross amount whose fee still leaves `amount` for the
+        // destination, i.e. ceil(amount * bps / (10_000 - bps)).
+        let denominator = max_basis_points - basis_points;
+        let fee = (amount as u128)
+            .checked_mul(basis_points as u128)
+            .and_then(|numerator| numerator.checked_add(denominator as u128 - 1))
+            .map(|numerator| numerator / denominator as u128)
+            .ok_or(CandyError::NumericalOverflowError)?;
+        u64::try_from(fee)
+            .map_err(|_| CandyError::NumericalOverflowError)?
+            .min(maximum_fee)
+    };
+
+    let gross_amount = amount
+        .checked_add(fee)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    let withheld_fee = transfer_fee
+        .calculate_fee(gross_amount)
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if gross_amount.saturating_sub(withheld_fee) < amount {
+        return err!(CandyError::NumericalOverflowError);
+    }
+
+    Ok(gross_amount)
+}
+
 /// TokenTransferParams
 pub struct TokenTransferParams<'a: 'b, 'b> {
     /// CHECK: account checked in CPI
     pub source: AccountInfo<'a>,
     /// CHECK: account checked in CPI
+    pub mint: AccountInfo<'a>,
+    /// CHECK: account checked in CPI
     pub destination: AccountInfo<'a>,
     pub amount: u64,
+    pub decimals: u8,
     /// CHECK: account checked in CPI
     pub authority: AccountInfo<'a>,
     /// authority_signer_seeds
@@ -119,10 +229,12 @@ pub struct TokenTransferParams<'a: 'b, 'b> {
 pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> Result<()> {
     let TokenTransferParams {
         source,
+        mint,
         destination,
         authority,
         token_program,
         amount,
+        decimals,
         authority_signer_seeds,
     } = params;
 
@@ -131,16 +243,20 @@ pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> Result<()> {
         signer_seeds.push(authority_signer_seeds)
     }
 
+    // transfer_checked works for both token programs and is required by
+    // Token-2022 mints with a transfer fee.
     let result = invoke_signed(
-        &spl_token::instruction::transfer(
+        &spl_token_2022::instruction::transfer_checked(
             token_program.key,
             source.key,
+            mint.key,
             destination.key,
             authority.key,
             &[],
             amount,
+            decimals,
         )?,
-        &[source, destination, authority, token_program],
+        &[source, mint, destination, authority, token_program],
         &signer_seeds,
     );
 
@@ -151,9 +267,12 @@ pub fn assert_is_ata(
     ata: &AccountInfo,
     wallet: &Pubkey,
     mint: &Pubkey,
-) -> core::result::Result<spl_token::state::Account, ProgramError> {
+) -> core::result::Result<spl_token_2022::state::Account, ProgramError> {
     let ata_account = assert_is_token_account(ata, wallet, mint)?;
-    assert_keys_equal(&get_associated_token_address(wallet, mint), ata.key)?;
+    assert_keys_equal(
+        &get_associated_token_address_with_program_id(wallet, mint, ata.owner),
+        ata.key,
+    )?;
     Ok(ata_account)
 }
 
@@ -161,9 +280,8 @@ pub fn assert_is_token_account(
     token_account: &AccountInfo,
     wallet: &Pubkey,
     mint: &Pubkey,
-) -> core::result::Result<spl_token::state::Account, ProgramError> {
-    assert_owned_by(token_account, &spl_token::id())?;
-    let ata_account: spl_token::state::Account = assert_initialized(token_account)?;
+) -> core::result::Result<spl_token_2022::state::Account, ProgramError> {
+    let ata_account = unpack_token_account(token_account)?;
     assert_keys_equal(&ata_account.owner, wallet)?;
     assert_keys_equal(&ata_account.mint, mint)?;
     Ok(ata_account)
@@ -191,6 +309,8 @@ pub struct TokenBurnParams<'a: 'b, 'b> {
     pub source: AccountInfo<'a>,
     /// amount
     pub amount: u64,
+    /// decimals
+    pub decimals: u8,
     /// authority
     /// CHECK: account checked in CPI
     pub authority: AccountInfo<'a>,
@@ -208,6 +328,7 @@ pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> Result<()> {
         authority,
         token_program,
         amount,
+        decimals,
         authority_signer_seeds,
     } = params;
     let mut seeds: Vec<&[&[u8]]> = vec![];
@@ -215,13 +336,14 @@ pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> Result<()> {
         seeds.push(seed);
     }
     let result = invoke_signed(
-        &spl_token::instruction::burn(
+        &spl_token_2022::instruction::burn_checked(
             token_program.key,
             source.key,
             mint.key,
             authority.key,
             &[],
             amount,
+            decimals,
         )?,
         &[source, mint, authority, token_program],
         seeds.as_slice(),
@@ -297,24 +419,79 @@ pub fn punish_bots<'a>(
     Ok(())
 }
 
+/// Bot taxes go to the configured destination if it was passed in the
+/// remaining accounts, otherwise to the candy machine account.
+pub fn get_bot_tax_destination<'a>(
+    bot_tax_settings: &BotTaxSettings,
+    candy_machine: AccountInfo<'a>,
+    remaining_accounts: &[AccountInfo<'a>],
+) -> AccountInfo<'a> {
+    bot_tax_settings
+        .destination
+        .and_then(|destination| {
+            remaining_accounts
+                .iter()
+                .find(|account| cmp_pubkeys(account.key, &destination))
+                .cloned()
+        })
+        .unwrap_or(candy_machine)
+}
+
 // On non-mainnet environments we check against a less secure anti-bot authority
 // because we include this keypair into our repos for testing convenience.
 #[cfg(feature = "use-test-anti-bot-authority")]
-pub fn assert_valid_bot_signer_authority(bot_signer_authority: &Pubkey) -> Result<()> {
-    if cmp_pubkeys(bot_signer_authority, &ANTI_BOT_DEV_AUTHORITY) {
-        return Ok(());
-    }
-
-    Err(CandyError::InvalidBotSignerAuthority.into())
+fn default_bot_signer_authority() -> Pubkey {
+    ANTI_BOT_DEV_AUTHORITY
 }
 
 #[cfg(not(feature = "use-test-anti-bot-authority"))]
-pub fn assert_valid_bot_signer_authority(bot_signer_authority: &Pubkey) -> Result<()> {
-    if cmp_pubkeys(bot_signer_authority, &ANTI_BOT_MAINNET_AUTHORITY) {
-        return Ok(());
+fn default_bot_signer_authority() -> Pubkey {
+    ANTI_BOT_MAINNET_AUTHORITY
+}
+
+/// Candy machines with their own bot signers accept any of them which hasn't
+/// expired, the others accept the default bot signer authority.
+pub fn assert_valid_bot_signer_authority(
+    candy_machine: &CandyMachine,
+    bot_signer_authority: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    let is_valid = if candy_machine.bot_signers.is_empty() {
+        cmp_pubkeys(bot_signer_authority, &default_bot_signer_authority())
+    } else {
+        candy_machine
+            .bot_signers
+            .iter()
+            .any(|bot_signer| bot_signer.is_valid_at(bot_signer_authority, now))
+    };
+
+    if is_valid {
+        Ok(())
+    } else {
+        Err(CandyError::InvalidBotSignerAuthority.into())
     }
+}
 
-    Err(CandyError::InvalidBotSignerAuthority.into())
+pub fn validate_bot_signers(bot_signers: &[BotSigner]) -> Result<()> {
+    if bot_signers.len() > MAX_BOT_SIGNERS {
+        msg!(
+            "Only {} bot signers are allowed, got {}.",
+            MAX_BOT_SIGNERS,
+            bot_signers.len()
+        );
+        return err!(CandyError::InvalidBotSigners);
+    }
+    for (i, bot_signer) in bot_signers.iter().enumerate() {
+        if bot_signers[..i]
+            .iter()
+            .any(|other| cmp_pubkeys(&other.key, &bot_signer.key))
+        {
+            msg!("Duplicate bot signer {}.", bot_signer.key);
+            return err!(CandyError::InvalidBotSigners);
+        }
+    }
+
+    Ok(())
 }
 
 pub fn make_ata<'a>(
@@ -359,7 +536,7 @@ pub fn make_ata<'a>(
 }
 
 pub fn write_anchor_account_discriminator<T: AnchorDeserialize + AccountSerialize>(
-    account: &UncheckedAccount,
+    account: &AccountInfo,
 ) -> Result<()> {
     let mut data_ref: &mut [u8] = &mut account.try_borrow_mut_data()?;
     let anchor_account: T = AnchorDeserialize::deserialize(&mut &*data_ref)?;
@@ -409,6 +586,500 @@ pub fn validate_candy_machine_allowlist_state(data: &CandyMachineData) -> Result
     Ok(())
 }
 
+pub fn validate_candy_machine_open_edition_state(data: &CandyMachineData) -> Result<()> {
+    if data.open_edition_settings.is_some() && data.hidden_settings.is_some() {
+        return Err(CandyError::InvalidOpenEditionSettings.into());
+    }
+
+    Ok(())
+}
+
+pub fn validate_candy_machine_bot_tax_settings(data: &CandyMachineData) -> Result<()> {
+    if let Some(bot_tax_settings) = &data.bot_tax_settings {
+        if bot_tax_settings.allowed_program_ids.len() > MAX_BOT_TAX_ALLOWED_PROGRAM_IDS {
+            return err!(CandyError::TooManyBotTaxAllowedPrograms);
+        }
+    }
+
+    Ok(())
+}
+
+/// Referral earnings are held as lamports, so referrals are only available for
+/// candy machines paid in SOL.
+pub fn validate_candy_machine_referral_settings(
+    data: &CandyMachineData,
+    treasury_mint: Option<Pubkey>,
+) -> Result<()> {
+    if data.referral_fee_basis_points > 10000
+        || (data.referral_fee_basis_points > 0 && treasury_mint.is_some())
+    {
+        return err!(CandyError::InvalidReferralSettings);
+    }
+
+    Ok(())
+}
+
+pub fn validate_candy_machine_creator_overrides_state(data: &CandyMachineData) -> Result<()> {
+    if data.creator_overrides_enabled
+        && (data.hidden_settings.is_some() || data.open_edition_settings.is_some())
+    {
+        return err!(CandyError::InvalidCreatorOverridesSettings);
+    }
+
+    Ok(())
+}
+
+pub fn validate_creator_override(creator_override: &CreatorOverride) -> Result<()> {
+    let total_shares = creator_override
+        .creators
+        .iter()
+        .map(|creator| creator.share as u16)
+        .sum::<u16>();
+    if creator_override.creators.is_empty()
+        || creator_override.creators.len() > MAX_CREATOR_LIMIT
+        || total_shares != 100
+        || creator_override.seller_fee_basis_points > 10000
+    {
+        return err!(CandyError::InvalidCreatorOverride);
+    }
+
+    Ok(())
+}
+
+/// Read the creator override of a config line from the creator overrides PDA,
+/// if it has one.
+pub fn get_creator_override(
+    creator_overrides_info: &AccountInfo,
+    index: usize,
+) -> Result<Option<CreatorOverride>> {
+    if !cmp_pubkeys(creator_overrides_info.owner, &crate::id()) {
+        return err!(CandyError::MissingCreatorOverridesAccount);
+    }
+
+    let data = creator_overrides_info.data.borrow();
+    let position = CreatorOverridesPda::space_for(index);
+    if data.len() < position + CreatorOverridesPda::LINE_SIZE || data[position] == 0 {
+        return Ok(None);
+    }
+
+    let creator_override = CreatorOverride::deserialize(
+        &mut &data[position + 1..position + CreatorOverridesPda::LINE_SIZE],
+    )?;
+    Ok(Some(creator_override))
+}
+
+/// USD prices are converted to lamports, so USD pricing is only available for
+/// candy machines paid in SOL.
+pub fn validate_candy_machine_usd_price_settings(
+    data: &CandyMachineData,
+    treasury_mint: Option<Pubkey>,
+) -> Result<()> {
+    if let Some(usd_price_settings) = &data.usd_price_settings {
+        if treasury_mint.is_some() || usd_price_settings.max_confidence_basis_points > 10000 {
+            return err!(CandyError::InvalidUsdPriceSettings);
+        }
+    }
+
+    Ok(())
+}
+
+/// The reserve of an all-or-nothing sale has to be reachable.
+/// All-or-nothing sales refund buyers from the freeze escrow, so freeze has to
+/// be set before the settings are.
+pub fn validate_candy_machine_min_items_to_succeed(
+    data: &CandyMachineData,
+    freeze_enabled: bool,
+) -> Result<()> {
+    if let Some(min_items_to_succeed) = data.min_items_to_succeed {
+        if min_items_to_succeed == 0 || min_items_to_succeed > data.items_available {
+            return err!(CandyError::InvalidMinItemsToSucceed);
+        }
+        if !freeze_enabled {
+            return err!(CandyError::MinItemsToSucceedRequiresFreeze);
+        }
+    }
+
+    Ok(())
+}
+
+/// Programmable NFTs can't be printed from a master edition, revealed with
+/// update_metadata_accounts_v2 or burned for a refund.
+pub fn validate_candy_machine_programmable_nft_settings(data: &CandyMachineData) -> Result<()> {
+    if data.programmable_nft_settings.is_some()
+        && (data.open_edition_settings.is_some()
+            || data.on_chain_reveal_enabled
+            || data.min_items_to_succeed.is_some())
+    {
+        return err!(CandyError::InvalidProgrammableNftSettings);
+    }
+
+    Ok(())
+}
+
+pub fn validate_candy_machine_mint_order(data: &CandyMachineData) -> Result<()> {
+    if let MintOrder::ReservedRangePerPhase { allowlist_items } = data.mint_order {
+        if allowlist_items == 0 || allowlist_items > data.items_available {
+            return err!(CandyError::InvalidMintOrder);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn validate_candy_machine_mint_rate_limit_settings(data: &CandyMachineData) -> Result<()> {
+    if let Some(mint_rate_limit_settings) = &data.mint_rate_limit_settings {
+        if mint_rate_limit_settings.max_mints == 0 || mint_rate_limit_settings.window_slots == 0 {
+            return err!(CandyError::InvalidMintRateLimitSettings);
+        }
+    }
+
+    Ok(())
+}
+
+/// Aggregate price of a Pyth-style price feed account.
+pub struct PriceFeedPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+pub fn load_price_feed(data: &[u8]) -> Result<PriceFeedPrice> {
+    if data.len() < PRICE_FEED_MIN_SIZE
+        || u32::from_le_bytes(*array_ref![data, 0, 4]) != PRICE_FEED_MAGIC
+        || u32::from_le_bytes(*array_ref![data, 8, 4]) != PRICE_FEED_ACCOUNT_TYPE
+        || u32::from_le_bytes(*array_ref![data, PRICE_FEED_STATUS_OFFSET, 4])
+            != PRICE_FEED_STATUS_TRADING
+    {
+        return err!(CandyError::InvalidPriceFeed);
+    }
+
+    let price_feed_price = PriceFeedPrice {
+        price: i64::from_le_bytes(*array_ref![data, PRICE_FEED_PRICE_OFFSET, 8]),
+        conf: u64::from_le_bytes(*array_ref![data, PRICE_FEED_CONF_OFFSET, 8]),
+        expo: i32::from_le_bytes(*array_ref![data, PRICE_FEED_EXPO_OFFSET, 4]),
+        publish_time: i64::from_le_bytes(*array_ref![data, PRICE_FEED_TIMESTAMP_OFFSET, 8]),
+    };
+    if price_feed_price.price <= 0 {
+        return err!(CandyError::InvalidPriceFeed);
+    }
+
+    Ok(price_feed_price)
+}
+
+/// Convert a USD price to lamports at the SOL/USD price of the price feed.
+pub fn convert_usd_price_to_lamports(
+    usd_price: u64,
+    price_feed_price: &PriceFeedPrice,
+) -> Result<u64> {
+    let lamports_decimals = 9;
+    // lamports = usd_price * 10^(9 - 6) / (price * 10^expo)
+    let mut numerator = (usd_price as u128)
+        .checked_mul(10u128.pow(lamports_decimals - USD_PRICE_DECIMALS))
+        .ok_or(CandyError::NumericalOverflowError)?;
+    let mut denominator = price_feed_price.price as u128;
+    let expo_multiplier = 10u128
+        .checked_pow(price_feed_price.expo.unsigned_abs())
+        .ok_or(CandyError::NumericalOverflowError)?;
+    if price_feed_price.expo < 0 {
+        numerator = numerator
+            .checked_mul(expo_multiplier)
+            .ok_or(CandyError::NumericalOverflowError)?;
+    } else {
+        denominator = denominator
+            .checked_mul(expo_multiplier)
+            .ok_or(CandyError::NumericalOverflowError)?;
+    }
+
+    u64::try_from(numerator / denominator).map_err(|_| CandyError::NumericalOverflowError.into())
+}
+
+/// Read the price feed and convert the USD price to lamports, failing if the
+/// price feed is stale or not confident enough.
+pub fn get_usd_price_in_lamports(
+    usd_price_settings: &UsdPriceSettings,
+    price_feed_info: &AccountInfo,
+    usd_price: u64,
+    now: i64,
+) -> Result<u64> {
+    let price_feed_price = load_price_feed(&price_feed_info.data.borrow())?;
+
+    let price_age = now.saturating_sub(price_feed_price.publish_time);
+    if price_age > usd_price_settings.max_price_age_seconds as i64 {
+        return err!(CandyError::StalePriceFeed);
+    }
+
+    let max_conf = (price_feed_price.price as u128)
+        .checked_mul(usd_price_settings.max_confidence_basis_points as u128)
+        .ok_or(CandyError::NumericalOverflowError)?
+        / 10000;
+    if price_feed_price.conf as u128 > max_conf {
+        return err!(CandyError::PriceFeedConfidenceTooWide);
+    }
+
+    convert_usd_price_to_lamports(usd_price, &price_feed_price)
+}
+
+pub fn validate_candy_machine_reveal_state(data: &CandyMachineData) -> Result<()> {
+    if data.on_chain_reveal_enabled && data.hidden_settings.is_none() {
+        return err!(CandyError::InvalidOnChainRevealSettings);
+    }
+
+    Ok(())
+}
+
+/// Extend the reveal hash chain with the next config line, in mint order.
+pub fn hash_reveal_config_line(
+    running_hash: &[u8; 32],
+    config_line: &ConfigLine,
+) -> Result<[u8; 32]> {
+    Ok(solana_program::keccak::hashv(&[running_hash, &config_line.try_to_vec()?]).0)
+}
+
+/// The hidden settings hash only holds 32 characters, so the reveal commitment
+/// is the hex encoding of the first 16 bytes of the final hash chain value.
+pub fn get_reveal_hash_commitment(running_hash: &[u8; 32]) -> [u8; 32] {
+    const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+    let mut commitment = [0u8; 32];
+    for (i, byte) in running_hash[..16].iter().enumerate() {
+        commitment[2 * i] = HEX_CHARS[(byte >> 4) as usize];
+        commitment[2 * i + 1] = HEX_CHARS[(byte & 0x0f) as usize];
+    }
+
+    commitment
+}
+
+/// Read the mint voucher from the Ed25519 program instruction right before
+/// the mint, if the candy machine accepts vouchers and one was provided. The
+/// Ed25519 program has already verified the signature by the time the mint
+/// runs, so only the signing key and message are checked here.
+pub fn get_mint_voucher(
+    data: &CandyMachineData,
+    instruction_sysvar_account_info: &AccountInfo,
+) -> Result<Option<MintVoucher>> {
+    let voucher_signer = match data.voucher_signer {
+        Some(voucher_signer) => voucher_signer,
+        None => return Ok(None),
+    };
+
+    let ix = match get_instruction_relative(-1, instruction_sysvar_account_info) {
+        Ok(ix) if cmp_pubkeys(&ix.program_id, &solana_program::ed25519_program::id()) => ix,
+        _ => return Ok(None),
+    };
+
+    // Ed25519 instruction data: a signature count and a padding byte, then
+    // the signature offsets, each field a u16.
+    let data = &ix.data;
+    if data.len() < 16 || data[0] != 1 {
+        return err!(CandyError::InvalidMintVoucher);
+    }
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let signature_instruction_index = read_u16(4);
+    let public_key_offset = read_u16(6) as usize;
+    let public_key_instruction_index = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_instruction_index = read_u16(14);
+
+    // Everything must be read from the Ed25519 instruction itself, otherwise
+    // the verified message could differ from the one read here.
+    if signature_instruction_index != u16::MAX
+        || public_key_instruction_index != u16::MAX
+        || message_instruction_index != u16::MAX
+    {
+        return err!(CandyError::InvalidMintVoucher);
+    }
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBKEY_BYTES)
+        .ok_or(CandyError::InvalidMintVoucher)?;
+    if public_key != voucher_signer.as_ref() {
+        return err!(CandyError::InvalidMintVoucher);
+    }
+
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(CandyError::InvalidMintVoucher)?;
+    let voucher =
+        MintVoucher::try_from_slice(message).map_err(|_| CandyError::InvalidMintVoucher)?;
+
+    Ok(Some(voucher))
+}
+
+pub fn assert_valid_mint_voucher(
+    voucher: &MintVoucher,
+    candy_machine: &Pubkey,
+    buyer: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    if !cmp_pubkeys(&voucher.candy_machine, candy_machine) || !cmp_pubkeys(&voucher.buyer, buyer) {
+        return err!(CandyError::MintVoucherMismatch);
+    }
+
+    if now > voucher.expiry {
+        return err!(CandyError::MintVoucherExpired);
+    }
+
+    Ok(())
+}
+
+pub fn find_voucher_nonce_pda(candy_machine: &Pubkey, buyer: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOUCHER_NONCE_PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+            buyer.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_referral_account_pda(candy_machine: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ReferralAccount::PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+            referrer.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_drop_buyer_info_pda(drop: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            DropBuyerInfoAccount::PREFIX.as_bytes(),
+            drop.as_ref(),
+            buyer.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_roles_pda(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RolesPda::PREFIX.as_bytes(), candy_machine.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Admin instructions with a role can be signed by the bullistic authority,
+/// or by the key holding the role in the roles PDA of the candy machine.
+pub fn assert_authority_or_role(
+    candy_machine: &Account<CandyMachine>,
+    roles_pda: &Option<Account<RolesPda>>,
+    signer: &Pubkey,
+    role: Role,
+) -> Result<()> {
+    if cmp_pubkeys(signer, &candy_machine.bullistic_authority) {
+        return Ok(());
+    }
+
+    let roles_pda = roles_pda.as_ref().ok_or(CandyError::MissingRole)?;
+    if !cmp_pubkeys(&roles_pda.candy_machine, &candy_machine.key()) {
+        return err!(CandyError::InvalidRolesPda);
+    }
+    match roles_pda.get_role(role) {
+        Some(role_key) if cmp_pubkeys(&role_key, signer) => Ok(()),
+        _ => err!(CandyError::MissingRole),
+    }
+}
+
+pub fn find_multisig_pda(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MultisigPda::PREFIX.as_bytes(), candy_machine.as_ref()],
+        &crate::id(),
+    )
+}
+
+pub fn find_proposal_pda(candy_machine: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Proposal::PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn validate_multisig_settings(
+    signers: &[Pubkey],
+    threshold: u8,
+    timelock_seconds: i64,
+) -> Result<()> {
+    if signers.is_empty() || signers.len() > MAX_MULTISIG_SIGNERS {
+        msg!(
+            "Multisig needs between 1 and {} signers, got {}.",
+            MAX_MULTISIG_SIGNERS,
+            signers.len()
+        );
+        return err!(CandyError::InvalidMultisigSettings);
+    }
+    if threshold == 0 || threshold as usize > signers.len() {
+        msg!(
+            "Multisig threshold {} must be between 1 and the number of signers.",
+            threshold
+        );
+        return err!(CandyError::InvalidMultisigSettings);
+    }
+    if !(0..=MAX_MULTISIG_TIMELOCK).contains(&timelock_seconds) {
+        msg!(
+            "Multisig timelock must be between 0 and {} seconds.",
+            MAX_MULTISIG_TIMELOCK
+        );
+        return err!(CandyError::InvalidMultisigSettings);
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        if signers[..i].contains(signer) {
+            msg!("Duplicate multisig signer {}.", signer);
+            return err!(CandyError::InvalidMultisigSettings);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn find_creator_overrides_pda(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CreatorOverridesPda::PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_refund_receipt_pda(candy_machine: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RefundReceipt::PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_freeze_record_pda(candy_machine: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            FreezeRecord::PREFIX.as_bytes(),
+            candy_machine.as_ref(),
+            mint.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_compressed_tree_pda(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CompressedTree::PREFIX.as_bytes(), candy_machine.as_ref()],
+        &crate::id(),
+    )
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
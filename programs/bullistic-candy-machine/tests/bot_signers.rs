@@ -0,0 +1,252 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{find_roles_pda, BotSigner, CandyError, Role};
+use solana_program::clock::Clock;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{
+    new_funded_keypair, prepare_nft, update_blockhash, update_blockhash_to_slot,
+};
+use crate::utils::helpers::{
+    assert_tx_failed_with_error_code, find_candy_creator, get_bot_signer_keypair, sol,
+};
+use crate::utils::{
+    candy_machine_program_test, initialize_roles, mint_nft_ix, rotate_bot_signers, set_role,
+    CandyConfigBuilder, CandyManager, CandyManagerBuilder, SolanaProgramTestResult, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn create_bot_protected_candy_machine(context: &mut ProgramTestContext) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_bot_protection_enabled(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_bot_protection_enabled(true)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    candy_manager
+}
+
+/// Mint co-signed by the given bot signer instead of the default one.
+async fn mint_with_bot_signer(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    bot_signer: &Keypair,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        true,
+        None,
+        DEFAULT_PRICE,
+    );
+    let default_bot_signer = get_bot_signer_keypair().pubkey();
+    for account in ix[1].accounts.iter_mut() {
+        if account.pubkey == default_bot_signer {
+            account.pubkey = bot_signer.pubkey();
+        }
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint, bot_signer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(())
+}
+
+async fn get_items_redeemed(context: &mut ProgramTestContext, candy_manager: &CandyManager) -> u64 {
+    candy_manager.get_candy(context).await.items_redeemed
+}
+
+#[tokio::test]
+async fn rotated_bot_signer_replaces_default() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_bot_protected_candy_machine(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let bot_signer = Keypair::new();
+
+    rotate_bot_signers(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        None,
+        vec![BotSigner {
+            key: bot_signer.pubkey(),
+            expires_at: None,
+        }],
+    )
+    .await
+    .unwrap();
+
+    // The default bot signer is bot taxed, so nothing is minted.
+    mint_with_bot_signer(context, &candy_manager, &get_bot_signer_keypair())
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 0);
+
+    mint_with_bot_signer(context, &candy_manager, &bot_signer)
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 1);
+
+    // Without bot signers, the default one is accepted again.
+    rotate_bot_signers(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        None,
+        vec![],
+    )
+    .await
+    .unwrap();
+    mint_with_bot_signer(context, &candy_manager, &get_bot_signer_keypair())
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 2);
+}
+
+#[tokio::test]
+async fn expired_bot_signer_is_rejected() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_bot_protected_candy_machine(context).await;
+    let bot_signer = Keypair::new();
+    let now = context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp;
+
+    rotate_bot_signers(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+        None,
+        vec![BotSigner {
+            key: bot_signer.pubkey(),
+            expires_at: Some(now + 60),
+        }],
+    )
+    .await
+    .unwrap();
+
+    mint_with_bot_signer(context, &candy_manager, &bot_signer)
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 1);
+
+    update_blockhash_to_slot(context, 50_000).await.unwrap();
+
+    mint_with_bot_signer(context, &candy_manager, &bot_signer)
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 1);
+}
+
+#[tokio::test]
+async fn fail_rotate_too_many_bot_signers() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_bot_protected_candy_machine(context).await;
+
+    let bot_signers = (0..4)
+        .map(|_| BotSigner {
+            key: Keypair::new().pubkey(),
+            expires_at: None,
+        })
+        .collect();
+    let result = rotate_bot_signers(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+        None,
+        bot_signers,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidBotSigners);
+}
+
+#[tokio::test]
+async fn bot_signer_manager_rotates_bot_signers() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_bot_protected_candy_machine(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (roles_pda, _) = find_roles_pda(&candy_machine);
+    let bot_signer_manager = new_funded_keypair(context, sol(1)).await;
+    let bot_signer = Keypair::new();
+    let bot_signers = vec![BotSigner {
+        key: bot_signer.pubkey(),
+        expires_at: None,
+    }];
+
+    initialize_roles(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+
+    // Without the role, the key can't rotate the bot signers.
+    let result = rotate_bot_signers(
+        context,
+        &candy_machine,
+        &bot_signer_manager,
+        Some(roles_pda),
+        bot_signers.clone(),
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::BotSignerManager,
+        Some(bot_signer_manager.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    rotate_bot_signers(
+        context,
+        &candy_machine,
+        &bot_signer_manager,
+        Some(roles_pda),
+        bot_signers,
+    )
+    .await
+    .unwrap();
+
+    mint_with_bot_signer(context, &candy_manager, &bot_signer)
+        .await
+        .unwrap();
+    assert_eq!(get_items_redeemed(context, &candy_manager).await, 1);
+}
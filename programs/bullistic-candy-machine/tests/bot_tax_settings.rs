@@ -0,0 +1,170 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{BotTaxSettings, CandyError};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{get_balance, prepare_nft, update_blockhash};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManagerBuilder,
+};
+
+pub mod core;
+pub mod utils;
+
+const MEMO_PROGRAM_ID: Pubkey =
+    solana_program::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+#[tokio::test]
+async fn allowed_program_instruction_after_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .set_bot_tax_settings(BotTaxSettings {
+            allowed_program_ids: vec![MEMO_PROGRAM_ID],
+            ..BotTaxSettings::default()
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    ix.push(Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![],
+        data: b"gm".to_vec(),
+    });
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let candy_end = candy_manager.get_candy(context).await;
+    assert_eq!(candy_end.items_redeemed, 1, "Mint should have succeeded");
+}
+
+#[tokio::test]
+async fn bot_tax_paid_to_destination() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_bot_protection_enabled(true)
+        .build(context)
+        .await;
+
+    let bot_tax_fee = 50_000_000;
+    let bot_tax_destination = Pubkey::new_unique();
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .set_bot_protection_enabled(true)
+        .set_bot_tax_settings(BotTaxSettings {
+            fee: bot_tax_fee,
+            destination: Some(bot_tax_destination),
+            ..BotTaxSettings::default()
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    // Leave the bot signer off so the mint gets taxed.
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    ix[1]
+        .accounts
+        .push(AccountMeta::new(bot_tax_destination, false));
+
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    let candy_start_balance = get_balance(context, &candy_manager.candy_machine.pubkey()).await;
+    context.banks_client.process_transaction(tx).await.unwrap();
+    let candy_end_balance = get_balance(context, &candy_manager.candy_machine.pubkey()).await;
+
+    assert_eq!(
+        get_balance(context, &bot_tax_destination).await,
+        bot_tax_fee,
+        "Bot tax should be paid to the configured destination"
+    );
+    assert_eq!(
+        candy_start_balance, candy_end_balance,
+        "Candy machine balance should not change"
+    );
+    let candy_end = candy_manager.get_candy(context).await;
+    assert_eq!(candy_end.items_redeemed, 0, "Items redeemed was not 0!");
+}
+
+#[tokio::test]
+async fn fail_too_many_bot_tax_allowed_programs() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_bot_tax_settings(BotTaxSettings {
+            allowed_program_ids: (0..6).map(|_| Pubkey::new_unique()).collect(),
+            ..BotTaxSettings::default()
+        })
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::TooManyBotTaxAllowedPrograms);
+}
@@ -0,0 +1,243 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{BurnToMintMode, BurnToMintSettings, CandyError, MintPhase};
+use mpl_token_metadata::{instruction::verify_collection, state::Collection};
+use solana_program::instruction::AccountMeta;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::core::helpers::{
+    assert_account_empty, get_balance, new_funded_keypair, prepare_nft, update_blockhash,
+};
+use crate::core::{MasterEditionManager, MetadataManager};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator, sol};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    SolanaProgramTestResult, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn create_source_collection(context: &mut ProgramTestContext) -> MasterEditionManager {
+    let collection_authority = new_funded_keypair(context, sol(1)).await;
+    let metadata = MetadataManager::new(&collection_authority);
+    metadata
+        .create_v2(
+            context,
+            "Source Collection".to_string(),
+            "SOURCE".to_string(),
+            "URI".to_string(),
+            None,
+            0,
+            true,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let master_edition = MasterEditionManager::new(&metadata);
+    master_edition.create_v3(context, Some(0)).await.unwrap();
+
+    master_edition
+}
+
+/// Creates an NFT owned by owner, verified as a member of the collection.
+async fn create_collection_nft(
+    context: &mut ProgramTestContext,
+    collection: &MasterEditionManager,
+    owner: &Keypair,
+) -> MasterEditionManager {
+    let metadata = MetadataManager::new(owner);
+    metadata
+        .create_v2(
+            context,
+            "Source NFT".to_string(),
+            "SOURCE".to_string(),
+            "URI".to_string(),
+            None,
+            0,
+            true,
+            None,
+            Some(Collection {
+                verified: false,
+                key: collection.mint.pubkey(),
+            }),
+            None,
+        )
+        .await
+        .unwrap();
+    let master_edition = MasterEditionManager::new(&metadata);
+    master_edition.create_v3(context, Some(0)).await.unwrap();
+
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[verify_collection(
+            mpl_token_metadata::id(),
+            master_edition.metadata_pubkey,
+            collection.authority.pubkey(),
+            collection.authority.pubkey(),
+            collection.mint.pubkey(),
+            collection.metadata_pubkey,
+            collection.edition_pubkey,
+            None,
+        )],
+        Some(&collection.authority.pubkey()),
+        &[&collection.authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    master_edition
+}
+
+async fn mint_burning_nft(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    collection: &MasterEditionManager,
+    burn_nft: Option<&MasterEditionManager>,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    if let Some(burn_nft) = burn_nft {
+        ix[1].accounts.extend([
+            AccountMeta::new(burn_nft.metadata_pubkey, false),
+            AccountMeta::new(burn_nft.mint.pubkey(), false),
+            AccountMeta::new(burn_nft.token_account, false),
+            AccountMeta::new(burn_nft.edition_pubkey, false),
+            AccountMeta::new(collection.metadata_pubkey, false),
+        ]);
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tokio::test]
+async fn burn_nft_to_mint_without_payment() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let collection = create_source_collection(context).await;
+    let burn_nft = create_collection_nft(context, &collection, &candy_manager.minter).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_burn_to_mint_settings(BurnToMintSettings {
+            mode: BurnToMintMode::ReplacesPayment,
+            collection_mint: collection.mint.pubkey(),
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let result = mint_burning_nft(context, &candy_manager, &collection, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingBurnToMintAccounts);
+
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    mint_burning_nft(context, &candy_manager, &collection, Some(&burn_nft))
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_start, treasury_end,
+        "Burning an NFT should replace the mint price"
+    );
+    let candy_end = candy_manager.get_candy(context).await;
+    assert_eq!(candy_end.items_redeemed, 1, "Mint should have succeeded");
+    assert_account_empty(context, &burn_nft.metadata_pubkey).await;
+    assert_account_empty(context, &burn_nft.token_account).await;
+}
+
+#[tokio::test]
+async fn burn_nft_instead_of_allowlist_proof() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let collection = create_source_collection(context).await;
+    let burn_nft = create_collection_nft(context, &collection, &candy_manager.minter).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .set_burn_to_mint_settings(BurnToMintSettings {
+            mode: BurnToMintMode::ReplacesAllowlist,
+            collection_mint: collection.mint.pubkey(),
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    mint_burning_nft(context, &candy_manager, &collection, Some(&burn_nft))
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        DEFAULT_PRICE,
+        "Mint price should still be paid"
+    );
+    assert_account_empty(context, &burn_nft.metadata_pubkey).await;
+
+    // Without an NFT left to burn the buyer is not on the allowlist.
+    let result = mint_burning_nft(context, &candy_manager, &collection, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingBurnToMintAccounts);
+}
+
+#[tokio::test]
+async fn fail_burn_nft_from_other_collection() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let collection = create_source_collection(context).await;
+    let other_collection = create_source_collection(context).await;
+    let burn_nft = create_collection_nft(context, &other_collection, &candy_manager.minter).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_burn_to_mint_settings(BurnToMintSettings {
+            mode: BurnToMintMode::ReplacesPayment,
+            collection_mint: collection.mint.pubkey(),
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let result = mint_burning_nft(context, &candy_manager, &collection, Some(&burn_nft)).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidBurnToMintNft);
+}
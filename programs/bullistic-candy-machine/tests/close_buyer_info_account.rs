@@ -0,0 +1,133 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use bullistic_candy_machine::{CandyError, MintPhase};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{assert_account_empty, get_balance, new_funded_keypair};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_buyer_info_account_pda, sol};
+use crate::utils::{
+    candy_machine_program_test, close_buyer_info_account, CandyConfigBuilder, CandyManager,
+    CandyManagerBuilder, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+/// Creates a candy machine with a buy limit, so mints create buyer info
+/// accounts, and mints one NFT.
+async fn create_candy_machine_with_buyer_info(context: &mut ProgramTestContext) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_limit_per_address(2)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager
+        .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+        .await
+        .unwrap();
+
+    candy_manager
+}
+
+async fn end_sale(context: &mut ProgramTestContext, candy_manager: &mut CandyManager) {
+    let candy_data = CandyConfigBuilder::new(candy_manager)
+        .set_limit_per_address(2)
+        .enable_mint_phase(MintPhase::Expired)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn buyer_closes_buyer_info_account_after_sale_end() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = create_candy_machine_with_buyer_info(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let buyer = candy_manager.minter.pubkey();
+
+    let buyer_info_account = candy_manager.get_buyer_info_account(context).await;
+    assert_eq!(buyer_info_account.buyer, buyer);
+    assert_eq!(buyer_info_account.payer, buyer);
+
+    let result = close_buyer_info_account(
+        context,
+        &candy_machine,
+        &buyer,
+        &buyer,
+        &candy_manager.minter,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::PublicSaleNotEnded);
+
+    end_sale(context, &mut candy_manager).await;
+
+    let pre_balance = get_balance(context, &buyer).await;
+    close_buyer_info_account(
+        context,
+        &candy_machine,
+        &buyer,
+        &buyer,
+        &candy_manager.minter,
+    )
+    .await
+    .unwrap();
+
+    assert!(get_balance(context, &buyer).await > pre_balance);
+    assert_account_empty(
+        context,
+        &find_buyer_info_account_pda(&candy_machine, &buyer).0,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn authority_closes_buyer_info_account_with_rent_to_payer() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = create_candy_machine_with_buyer_info(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let buyer = candy_manager.minter.pubkey();
+    let outsider = new_funded_keypair(context, sol(1)).await;
+
+    end_sale(context, &mut candy_manager).await;
+
+    let result = close_buyer_info_account(context, &candy_machine, &buyer, &buyer, &outsider).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidBuyerInfoAccountCloser);
+
+    let result = close_buyer_info_account(
+        context,
+        &candy_machine,
+        &buyer,
+        &Keypair::new().pubkey(),
+        &candy_manager.bullistic_authority,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidRentReceiver);
+
+    let pre_balance = get_balance(context, &buyer).await;
+    close_buyer_info_account(
+        context,
+        &candy_machine,
+        &buyer,
+        &buyer,
+        &candy_manager.bullistic_authority,
+    )
+    .await
+    .unwrap();
+
+    assert!(get_balance(context, &buyer).await > pre_balance);
+    assert_account_empty(
+        context,
+        &find_buyer_info_account_pda(&candy_machine, &buyer).0,
+    )
+    .await;
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use bullistic_candy_machine::{CandyError, MintPhase};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{get_account, get_balance};
+use crate::utils::helpers::{
+    assert_tx_failed_with_error_code, get_config_line_name, get_config_line_uri,
+    ParsedConfigLinesResult,
+};
+use crate::utils::{
+    candy_machine_program_test, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+const ITEMS_AVAILABLE: u64 = 5;
+
+async fn create_public_candy_machine(context: &mut ProgramTestContext) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    candy_manager
+}
+
+#[tokio::test]
+async fn close_unsold_inventory_after_sale_end() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = create_public_candy_machine(context).await;
+
+    for _ in 0..2 {
+        candy_manager
+            .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+            .await
+            .unwrap();
+    }
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Expired)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let authority = candy_manager.bullistic_authority.pubkey();
+    let pre_data_len = get_account(context, &candy_machine).await.data.len();
+    let pre_balance = get_balance(context, &authority).await;
+
+    candy_manager.close_unsold_inventory(context).await.unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.data.items_available, 2);
+    assert_eq!(candy_machine_state.items_redeemed, 2);
+    assert!(get_account(context, &candy_machine).await.data.len() < pre_data_len);
+    assert!(get_balance(context, &authority).await > pre_balance);
+
+    // The minted config lines are kept, so the minted items can still be
+    // verified.
+    let ParsedConfigLinesResult {
+        config_line_count_number,
+        config_lines,
+    } = candy_manager.parse_config_lines(context).await;
+    assert_eq!(config_line_count_number, 2);
+    for config_line in config_lines.iter().take(2) {
+        assert!((0..ITEMS_AVAILABLE as u32).any(|i| {
+            config_line.name == get_config_line_name(i) && config_line.uri == get_config_line_uri(i)
+        }));
+    }
+
+    let result = candy_manager.close_unsold_inventory(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::NoUnsoldInventory);
+}
+
+#[tokio::test]
+async fn fail_close_unsold_inventory_before_sale_end() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = create_public_candy_machine(context).await;
+
+    candy_manager
+        .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+        .await
+        .unwrap();
+
+    let result = candy_manager.close_unsold_inventory(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::PublicSaleNotEnded);
+}
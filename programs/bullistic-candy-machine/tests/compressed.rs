@@ -0,0 +1,219 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use std::mem::size_of;
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_lang::{AccountDeserialize, Id};
+use bullistic_candy_machine::{CandyError, MintPhase};
+use bytemuck::pod_read_unaligned;
+use mpl_bubblegum::{
+    hash_creators, hash_metadata,
+    state::{
+        leaf_schema::LeafSchema,
+        metaplex_adapter::{Creator, MetadataArgs, TokenProgramVersion, TokenStandard},
+        TreeConfig,
+    },
+    utils::get_asset_id,
+};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_account_compression::{
+    state::CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1, ConcurrentMerkleTree, Noop,
+};
+
+use crate::core::helpers::{get_account, prepare_nft, update_blockhash};
+use crate::utils::helpers::{
+    assert_tx_failed_with_error_code, find_candy_creator, get_config_line_name, get_config_line_uri,
+};
+use crate::utils::{
+    candy_machine_program_test, compressed_candy_machine_program_test, find_tree_authority,
+    mint_nft_ix, set_compressed_tree, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+const MAX_DEPTH: usize = 14;
+const MAX_BUFFER_SIZE: usize = 64;
+
+/// Mints without the metadata, mint and master edition accounts, passing the
+/// accounts of the merkle tree if one is given.
+async fn mint_without_nft_accounts(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    merkle_tree: Option<Pubkey>,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (candy_machine_creator, creator_bump) = find_candy_creator(&candy_machine);
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_machine,
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    // Missing optional accounts are passed as the candy machine program.
+    let nft_accounts = [
+        nft_info.metadata_pubkey,
+        nft_info.mint.pubkey(),
+        nft_info.edition_pubkey,
+    ];
+    for account in ix[1].accounts.iter_mut() {
+        if nft_accounts.contains(&account.pubkey) {
+            *account = AccountMeta::new_readonly(bullistic_candy_machine::id(), false);
+        }
+    }
+
+    if let Some(merkle_tree) = merkle_tree {
+        ix[1].accounts.extend([
+            AccountMeta::new_readonly(
+                bullistic_candy_machine::find_compressed_tree_pda(&candy_machine).0,
+                false,
+            ),
+            AccountMeta::new(find_tree_authority(&merkle_tree), false),
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new_readonly(Noop::id(), false),
+            AccountMeta::new_readonly(spl_account_compression::id(), false),
+            AccountMeta::new_readonly(mpl_bubblegum::id(), false),
+        ]);
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tokio::test]
+async fn mint_compressed_nft() {
+    let mut context = compressed_candy_machine_program_test()
+        .start_with_context()
+        .await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(1)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let merkle_tree = Keypair::new();
+    set_compressed_tree(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+        &merkle_tree,
+        MAX_DEPTH as u32,
+        MAX_BUFFER_SIZE as u32,
+    )
+    .await
+    .unwrap();
+
+    mint_without_nft_accounts(context, &candy_manager, Some(merkle_tree.pubkey()))
+        .await
+        .unwrap();
+
+    let candy = candy_manager.get_candy(context).await;
+    assert_eq!(candy.items_redeemed, 1);
+
+    let tree_config_account =
+        get_account(context, &find_tree_authority(&merkle_tree.pubkey())).await;
+    let tree_config = TreeConfig::try_deserialize(&mut tree_config_account.data.as_ref()).unwrap();
+    assert_eq!(tree_config.num_minted, 1);
+
+    // The creator authority didn't sign, so no creator is verified.
+    let creators: Vec<Creator> = candy
+        .data
+        .creators
+        .iter()
+        .map(|creator| Creator {
+            address: creator.address,
+            verified: false,
+            share: creator.share,
+        })
+        .collect();
+    let metadata = MetadataArgs {
+        name: get_config_line_name(0),
+        symbol: candy.data.symbol.clone(),
+        uri: get_config_line_uri(0),
+        seller_fee_basis_points: candy.data.seller_fee_basis_points,
+        primary_sale_happened: true,
+        is_mutable: candy.data.is_mutable,
+        edition_nonce: None,
+        token_standard: Some(TokenStandard::NonFungible),
+        collection: None,
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        creators: creators.clone(),
+    };
+    let minter = candy_manager.minter.pubkey();
+    let leaf = LeafSchema::new_v0(
+        get_asset_id(&merkle_tree.pubkey(), 0),
+        minter,
+        minter,
+        0,
+        hash_metadata(&metadata).unwrap(),
+        hash_creators(&creators).unwrap(),
+    );
+
+    let tree_account = get_account(context, &merkle_tree.pubkey()).await;
+    let tree = pod_read_unaligned::<ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>>(
+        &tree_account.data[CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1
+            ..CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1
+                + size_of::<ConcurrentMerkleTree<MAX_DEPTH, MAX_BUFFER_SIZE>>()],
+    );
+    assert_eq!(tree.rightmost_proof.index, 1);
+    assert_eq!(
+        tree.rightmost_proof.leaf,
+        leaf.to_node(),
+        "The tree should hold the minted NFT as its first leaf"
+    );
+}
+
+#[tokio::test]
+async fn fail_mint_without_nft_accounts() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(1)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    // Only compressed mints can leave out the NFT accounts.
+    let result = mint_without_nft_accounts(context, &candy_manager, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingNftAccounts);
+
+    candy_manager.mint_nft(context, None, None).await.unwrap();
+}
@@ -10,8 +10,12 @@ use solana_sdk::{
     transaction::Transaction,
     transport,
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 use spl_token::state::Mint;
 pub use spl_token::ID as TOKEN_PROGRAM_ID;
+use spl_token_2022::extension::{
+    transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType, StateWithExtensions,
+};
 
 use crate::core::{master_edition_manager::MasterEditionManager, metadata_manager};
 use crate::utils::SolanaProgramTestResult;
@@ -85,9 +89,11 @@ pub async fn get_balance(context: &mut ProgramTestContext, pubkey: &Pubkey) -> u
 }
 
 pub async fn get_token_balance(context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
-    get_token_account(context, token_account)
-        .await
+    // Unpacking with extensions works for both SPL token and Token-2022 accounts.
+    let account = get_account(context, token_account).await;
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
         .unwrap()
+        .base
         .amount
 }
 
@@ -354,6 +360,131 @@ pub async fn transfer(
         .map_err(|e| e.into())
 }
 
+/// Creates a Token-2022 mint, optionally with the transfer fee extension
+/// (basis points, maximum fee).
+#[allow(dead_code)]
+pub async fn create_token_2022_mint(
+    context: &mut ProgramTestContext,
+    authority: &Pubkey,
+    decimals: u8,
+    transfer_fee: Option<(u16, u64)>,
+) -> transport::Result<Keypair> {
+    update_blockhash(context).await?;
+    let mint = Keypair::new();
+    let rent = context.banks_client.get_rent().await.unwrap();
+
+    let extensions = match transfer_fee {
+        Some(_) => vec![ExtensionType::TransferFeeConfig],
+        None => vec![],
+    };
+    let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&extensions);
+
+    let mut instructions = vec![system_instruction::create_account(
+        &context.payer.pubkey(),
+        &mint.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &spl_token_2022::id(),
+    )];
+    if let Some((transfer_fee_basis_points, maximum_fee)) = transfer_fee {
+        instructions.push(
+            initialize_transfer_fee_config(
+                &spl_token_2022::id(),
+                &mint.pubkey(),
+                Some(authority),
+                Some(authority),
+                transfer_fee_basis_points,
+                maximum_fee,
+            )
+            .unwrap(),
+        );
+    }
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint(
+            &spl_token_2022::id(),
+            &mint.pubkey(),
+            authority,
+            None,
+            decimals,
+        )
+        .unwrap(),
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&context.payer.pubkey()),
+        &[&context.payer, &mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await.unwrap();
+    Ok(mint)
+}
+
+#[allow(dead_code)]
+pub async fn create_token_2022_associated_token_account(
+    context: &mut ProgramTestContext,
+    wallet: &Pubkey,
+    token_mint: &Pubkey,
+) -> transport::Result<Pubkey> {
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &context.payer.pubkey(),
+                wallet,
+                token_mint,
+                &spl_token_2022::id(),
+            ),
+        ],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(get_associated_token_address_with_program_id(
+        wallet,
+        token_mint,
+        &spl_token_2022::id(),
+    ))
+}
+
+/// Creates Token-2022 associated token accounts for each wallet and mints the
+/// allocated amounts into them.
+#[allow(dead_code)]
+pub async fn mint_token_2022_to_wallets(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    authority: &Keypair,
+    allocations: Vec<(Pubkey, u64)>,
+) -> SolanaProgramTestResult<Vec<Pubkey>> {
+    let mut atas = Vec::with_capacity(allocations.len());
+
+    for (wallet, amount) in allocations {
+        let ata = create_token_2022_associated_token_account(context, &wallet, mint).await?;
+        update_blockhash(context).await?;
+        let tx = Transaction::new_signed_with_payer(
+            &[spl_token_2022::instruction::mint_to(
+                &spl_token_2022::id(),
+                mint,
+                &ata,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap()],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await?;
+        atas.push(ata);
+    }
+
+    Ok(atas)
+}
+
 pub async fn prepare_nft(minter: &Keypair) -> MasterEditionManager {
     let nft = metadata_manager::MetadataManager::new(minter);
     MasterEditionManager::new(&nft)
@@ -1,17 +1,80 @@
 #![cfg(feature = "test-bpf")]
 #![allow(dead_code)]
 
+use anchor_lang::error::ErrorCode;
+use solana_program::pubkey::Pubkey;
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
 
-use bullistic_candy_machine::MintPhase;
+use bullistic_candy_machine::{CandyError, MintPhase};
 use utils::CandyConfigBuilder;
 
-use crate::utils::{candy_machine_program_test, CandyManagerBuilder};
+use crate::core::helpers::{clone_keypair, prepare_nft, update_blockhash};
+use crate::core::MasterEditionManager;
+use crate::utils::{
+    candy_machine_program_test,
+    helpers::{assert_tx_failed_with_error_code, find_candy_creator},
+    mint_nft_ix, CandyManager, CandyManagerBuilder, SolanaProgramTestError,
+    SolanaProgramTestResult,
+};
 
 mod core;
 mod utils;
 
+/// Mints with `signer` passed, and signing, as the creator authority.
+async fn mint_signed_as_creator_authority(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    signer: &Keypair,
+) -> SolanaProgramTestResult<MasterEditionManager> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &signer.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    for account in ix[1].accounts.iter_mut() {
+        if account.pubkey == signer.pubkey() {
+            account.is_signer = true;
+        }
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint, signer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(TransportError::from)?;
+    Ok(nft_info)
+}
+
 #[tokio::test]
 async fn mint_update_authority_should_be_creator_authority() {
     let mut context = candy_machine_program_test().start_with_context().await;
@@ -55,3 +118,106 @@ async fn mint_update_authority_should_be_creator_authority() {
         "NFT metadata creator should be the creator_authority."
     );
 }
+
+#[tokio::test]
+async fn creator_authority_signer_is_verified_during_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    let creator_authority = clone_keypair(&candy_manager.creator_authority);
+    let nft_info = mint_signed_as_creator_authority(context, &candy_manager, &creator_authority)
+        .await
+        .unwrap();
+
+    let nft_metadata = nft_info.get_metadata(context).await;
+    let nft_creators = nft_metadata.data.creators.unwrap();
+    assert_eq!(
+        nft_creators.first().unwrap().address,
+        creator_authority.pubkey(),
+        "NFT metadata creator should be the creator_authority."
+    );
+    assert!(
+        nft_creators.first().unwrap().verified,
+        "creator_authority should be verified when it signs the mint."
+    );
+}
+
+#[tokio::test]
+async fn fail_signing_creator_authority_not_a_creator() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_creator(Pubkey::new_unique())
+        .build();
+
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    let creator_authority = clone_keypair(&candy_manager.creator_authority);
+    let result =
+        mint_signed_as_creator_authority(context, &candy_manager, &creator_authority).await;
+    assert_tx_failed_with_error_code(result, CandyError::CreatorAuthorityNotACreator);
+}
+
+#[tokio::test]
+async fn fail_creator_authority_signed_by_another_wallet() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    // Without the creator authority's signature, another wallet can't stand
+    // in for it to get verified as the creator.
+    let impostor = Keypair::new();
+    let result = mint_signed_as_creator_authority(context, &candy_manager, &impostor).await;
+    match result.unwrap_err() {
+        SolanaProgramTestError::TransportError(TransportError::TransactionError(
+            TransactionError::InstructionError(_, InstructionError::Custom(error_code)),
+        )) => assert_eq!(error_code, ErrorCode::ConstraintHasOne as u32),
+        error => panic!("Unexpected error: {:?}", error),
+    }
+
+    // Unsigned, the creator authority still mints but isn't verified.
+    let nft = candy_manager
+        .mint_and_assert_successful(context, None, false, None)
+        .await
+        .unwrap();
+    let nft_creators = nft.get_metadata(context).await.data.creators.unwrap();
+    assert_eq!(
+        nft_creators.first().unwrap().address,
+        candy_manager.creator_authority.pubkey()
+    );
+    assert!(
+        !nft_creators.first().unwrap().verified,
+        "creator_authority shouldn't be verified without signing the mint."
+    );
+}
@@ -0,0 +1,172 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{
+    find_creator_overrides_pda, CandyError, Creator, CreatorOverride, HiddenSettings, MintPhase,
+};
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{prepare_nft, update_blockhash};
+use crate::core::{MasterEditionManager, MetadataManager};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, initialize_creator_overrides, mint_nft_ix, set_creator_overrides,
+    CandyConfigBuilder, CandyManager, CandyManagerBuilder, SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn mint_with_creator_overrides(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    pass_creator_overrides: bool,
+) -> SolanaProgramTestResult<MasterEditionManager> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    if pass_creator_overrides {
+        let (creator_overrides_pda, _) =
+            find_creator_overrides_pda(&candy_manager.candy_machine.pubkey());
+        ix[1]
+            .accounts
+            .push(AccountMeta::new_readonly(creator_overrides_pda, false));
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(nft_info)
+}
+
+fn make_creator_override(artist: Pubkey, share: u8) -> CreatorOverride {
+    CreatorOverride {
+        seller_fee_basis_points: 750,
+        creators: vec![Creator {
+            address: artist,
+            verified: false,
+            share,
+        }],
+    }
+}
+
+#[tokio::test]
+async fn mint_with_config_line_creator_overrides() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let items_available = 2;
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(items_available)
+        .enable_mint_phase(MintPhase::Public)
+        .set_creator_overrides_enabled(true)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    initialize_creator_overrides(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+    let artist = Pubkey::new_unique();
+    set_creator_overrides(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        0,
+        vec![Some(make_creator_override(artist, 100)); items_available as usize],
+    )
+    .await
+    .unwrap();
+
+    // The creators can't be skipped by leaving out the overrides account.
+    let result = mint_with_creator_overrides(context, &candy_manager, false).await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingCreatorOverridesAccount);
+
+    let nft = mint_with_creator_overrides(context, &candy_manager, true)
+        .await
+        .unwrap();
+    let metadata = MetadataManager::get_data_from_account(context, &nft.metadata_pubkey).await;
+    let creators = metadata.data.creators.unwrap();
+    assert_eq!(creators.len(), 1);
+    assert_eq!(
+        creators[0].address, artist,
+        "NFT should use the line creators"
+    );
+    assert_eq!(creators[0].share, 100);
+    assert_eq!(metadata.data.seller_fee_basis_points, 750);
+}
+
+#[tokio::test]
+async fn fail_invalid_creator_override_shares() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_creator_overrides_enabled(true)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    initialize_creator_overrides(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+    let result = set_creator_overrides(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        0,
+        vec![Some(make_creator_override(Pubkey::new_unique(), 90))],
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidCreatorOverride);
+}
+
+#[tokio::test]
+async fn fail_creator_overrides_with_hidden_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_hidden_settings(HiddenSettings {
+            name: "Hidden".to_string(),
+            uri: "https://example.com/hidden.json".to_string(),
+            hash: [0; 32],
+        })
+        .set_creator_overrides_enabled(true)
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidCreatorOverridesSettings);
+}
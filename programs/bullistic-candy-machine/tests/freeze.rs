@@ -9,13 +9,15 @@ use bullistic_candy_machine::constants::{
     FREEZE_FEATURE_INDEX, FREEZE_FEE, FREEZE_LOCK_FEATURE_INDEX, MAX_FREEZE_TIME,
 };
 use bullistic_candy_machine::{
-    is_feature_active, FreezePda, MintPhase, SplTokenAllowlistMode::BurnEveryTime,
+    find_freeze_record_pda, is_feature_active, CandyError, FreezePda, MintPhase,
+    SplTokenAllowlistMode::BurnEveryTime,
 };
 
 use crate::core::helpers::{
     get_balance, get_token_balance, new_funded_keypair, update_blockhash_to_slot,
 };
-use crate::utils::helpers::test_start;
+use crate::core::MasterEditionManager;
+use crate::utils::helpers::{assert_tx_failed_with_error_code, test_start};
 use crate::utils::FreezeConfig;
 use crate::{
     core::helpers::{assert_account_empty, clone_keypair},
@@ -67,6 +69,7 @@ async fn freeze_flow_with_spl_token() {
         frozen_count: 0,
         allow_thaw: false,
         mint_start: None,
+        last_frozen_at: None,
     };
 
     candy_manager
@@ -176,6 +179,7 @@ async fn freeze_update() {
         frozen_count: 0,
         allow_thaw: false,
         mint_start: None,
+        last_frozen_at: None,
     };
     candy_manager
         .assert_freeze_set(context, &expected_freeze_pda)
@@ -292,6 +296,7 @@ async fn thaw_after_freeze_time() {
         frozen_count: 0,
         allow_thaw: false,
         mint_start: None,
+        last_frozen_at: None,
     };
     candy_manager
         .assert_freeze_set(context, &expected_freeze_pda)
@@ -369,6 +374,7 @@ async fn unlock_funds() {
         frozen_count: 0,
         allow_thaw: false,
         mint_start: None,
+        last_frozen_at: None,
     };
     candy_manager
         .assert_freeze_set(context, &expected_freeze_pda)
@@ -425,6 +431,7 @@ async fn mint_out_unfreeze() {
         frozen_count: 0,
         allow_thaw: false,
         mint_start: None,
+        last_frozen_at: None,
     };
 
     candy_manager
@@ -470,3 +477,129 @@ async fn mint_out_unfreeze() {
     let post_balance = get_balance(context, &candy_manager.bullistic_authority.pubkey()).await;
     assert!(post_balance - pre_balance >= sol(2));
 }
+
+#[tokio::test]
+async fn thaw_nfts_in_batch() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new(true, MAX_FREEZE_TIME))
+        .build(context)
+        .await;
+
+    let random_key = new_funded_keypair(context, sol(1)).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let mut nfts = vec![];
+    for _ in 0..3 {
+        let nft = candy_manager
+            .mint_and_assert_successful(context, Some(sol(1)), true, None)
+            .await
+            .unwrap();
+        candy_manager.assert_frozen(context, &nft).await;
+        nfts.push(nft);
+    }
+    let nft_refs: Vec<&MasterEditionManager> = nfts.iter().collect();
+
+    // Thawing an NFT on its own first shouldn't get in the way of the batch.
+    candy_manager
+        .thaw_nft(context, nft_refs[0], &random_key)
+        .await
+        .unwrap();
+
+    let pre_balance = get_balance(context, &random_key.pubkey()).await;
+    candy_manager
+        .thaw_nfts(context, &nft_refs, &random_key)
+        .await
+        .unwrap();
+    let post_balance = get_balance(context, &random_key.pubkey()).await;
+    // Two freeze fees are refunded, less the transaction fee.
+    assert!(post_balance > pre_balance);
+
+    for nft in &nfts {
+        candy_manager.assert_thawed(context, nft, false).await;
+    }
+    let freeze_pda = candy_manager.get_freeze_pda(context).await;
+    assert_eq!(freeze_pda.frozen_count, 0);
+    candy_manager.unlock_funds(context).await.unwrap();
+}
+
+#[tokio::test]
+async fn rolling_freeze_per_nft() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let freeze_time = 30; //30 seconds
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new_rolling(freeze_time))
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::default(&candy_manager);
+    candy_manager
+        .create(context, candy_data.clone())
+        .await
+        .unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let nft1 = candy_manager
+        .mint_and_assert_successful(context, Some(sol(1)), true, None)
+        .await
+        .unwrap();
+    candy_manager.assert_frozen(context, &nft1).await;
+
+    update_blockhash_to_slot(context, 50_000).await.unwrap();
+
+    // Unlike a regular freeze, mints after freeze_time are still frozen.
+    let nft2 = candy_manager
+        .mint_and_assert_successful(context, Some(sol(1)), true, None)
+        .await
+        .unwrap();
+    candy_manager.assert_frozen(context, &nft2).await;
+
+    // nft1's own lock expired while nft2's just started.
+    candy_manager
+        .thaw_nft(context, &nft1, &nft1.authority)
+        .await
+        .unwrap();
+    candy_manager.assert_thawed(context, &nft1, false).await;
+    let (nft1_freeze_record, _) =
+        find_freeze_record_pda(&candy_manager.candy_machine.pubkey(), &nft1.mint.pubkey());
+    assert_account_empty(context, &nft1_freeze_record).await;
+
+    let result = candy_manager
+        .thaw_nft(context, &nft2, &nft2.authority)
+        .await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidThawNft);
+
+    // Funds stay locked until the last NFT's lock expired.
+    let result = candy_manager.unlock_funds(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::NoUnlockWithNFTsStillFrozen);
+
+    update_blockhash_to_slot(context, 50_000).await.unwrap();
+
+    let pre_balance = get_balance(context, &candy_manager.bullistic_authority.pubkey()).await;
+    candy_manager.unlock_funds(context).await.unwrap();
+    let post_balance = get_balance(context, &candy_manager.bullistic_authority.pubkey()).await;
+    assert!(post_balance - pre_balance > sol(1));
+
+    // The freeze PDA stays open with nft2's freeze fee until it's thawed.
+    let freeze_pda = candy_manager.get_freeze_pda(context).await;
+    assert_eq!(freeze_pda.frozen_count, 1);
+    candy_manager
+        .thaw_nft(context, &nft2, &nft2.authority)
+        .await
+        .unwrap();
+    candy_manager.assert_thawed(context, &nft2, false).await;
+
+    candy_manager.unlock_funds(context).await.unwrap();
+    assert_account_empty(context, &candy_manager.freeze_info.pda).await;
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use bullistic_candy_machine::constants::{CONFIG_ARRAY_START, LEGACY_CONFIG_ARRAY_START};
+use bullistic_candy_machine::{
+    CandyError, CandyMachine, LegacyCandyMachine, LegacyCandyMachineData, MintPhase,
+};
+use solana_program_test::*;
+use solana_sdk::account::{AccountSharedData, WritableAccount};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::core::helpers::{get_account, new_funded_keypair};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, sol};
+use crate::utils::{
+    candy_machine_program_test, migrate_candy_machine, CandyConfigBuilder, CandyManager,
+    CandyManagerBuilder, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+fn to_legacy_candy_machine(candy_machine: CandyMachine) -> LegacyCandyMachine {
+    let data = candy_machine.data;
+    LegacyCandyMachine {
+        bullistic_authority: candy_machine.bullistic_authority,
+        creator_authority: candy_machine.creator_authority,
+        treasury_wallet: candy_machine.treasury_wallet,
+        treasury_mint: candy_machine.treasury_mint,
+        items_redeemed: candy_machine.items_redeemed,
+        data: LegacyCandyMachineData {
+            uuid: data.uuid,
+            price: data.price,
+            premint_price: data.premint_price,
+            allowlist_price: data.allowlist_price,
+            symbol: data.symbol,
+            seller_fee_basis_points: data.seller_fee_basis_points,
+            max_supply: data.max_supply,
+            items_available: data.items_available,
+            is_mutable: data.is_mutable,
+            allowlist_sale_start_time: data.allowlist_sale_start_time,
+            public_sale_start_time: data.public_sale_start_time,
+            public_sale_end_time: data.public_sale_end_time,
+            creators: data.creators,
+            omni_mint_wallets: data.omni_mint_wallets,
+            hidden_settings: data.hidden_settings,
+            bot_protection_enabled: data.bot_protection_enabled,
+            limit_per_address: data.limit_per_address,
+            sequential_mint_order_enabled: data.sequential_mint_order_enabled,
+            merkle_allowlist_root_list: data.merkle_allowlist_root_list,
+            spl_token_allowlist_settings: data.spl_token_allowlist_settings,
+        },
+    }
+}
+
+/// Rewrites the candy machine account as it was stored before the layout
+/// change, with its config lines at the legacy config array start and only
+/// the rent of the smaller account.
+async fn downgrade_to_legacy_layout(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+) -> Vec<u8> {
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let account = get_account(context, &candy_machine).await;
+    let candy_machine_state = candy_manager.get_candy(context).await;
+
+    let legacy_space = account.data.len() - (CONFIG_ARRAY_START - LEGACY_CONFIG_ARRAY_START);
+    let mut legacy_data = vec![0; legacy_space];
+    legacy_data[..8].copy_from_slice(&CandyMachine::discriminator());
+    let legacy_candy_machine = to_legacy_candy_machine(candy_machine_state)
+        .try_to_vec()
+        .unwrap();
+    legacy_data[8..8 + legacy_candy_machine.len()].copy_from_slice(&legacy_candy_machine);
+    legacy_data[LEGACY_CONFIG_ARRAY_START..].copy_from_slice(&account.data[CONFIG_ARRAY_START..]);
+
+    let rent = context.banks_client.get_rent().await.unwrap();
+    context.set_account(
+        &candy_machine,
+        &AccountSharedData::create(
+            rent.minimum_balance(legacy_space),
+            legacy_data,
+            bullistic_candy_machine::id(),
+            false,
+            0,
+        ),
+    );
+
+    account.data
+}
+
+#[tokio::test]
+async fn migrate_legacy_candy_machine() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(5)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager
+        .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+        .await
+        .unwrap();
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let current_data = downgrade_to_legacy_layout(context, &candy_manager).await;
+
+    let outsider = new_funded_keypair(context, sol(1)).await;
+    migrate_candy_machine(context, &candy_machine, &outsider)
+        .await
+        .unwrap_err();
+
+    migrate_candy_machine(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+
+    let account = get_account(context, &candy_machine).await;
+    assert_eq!(account.data, current_data);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert!(rent.is_exempt(account.lamports, account.data.len()));
+
+    let result =
+        migrate_candy_machine(context, &candy_machine, &candy_manager.bullistic_authority).await;
+    assert_tx_failed_with_error_code(result, CandyError::CandyMachineAlreadyMigrated);
+
+    candy_manager
+        .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fail_migrate_current_candy_machine() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let result = migrate_candy_machine(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::CandyMachineAlreadyMigrated);
+}
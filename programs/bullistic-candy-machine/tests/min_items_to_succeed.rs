@@ -0,0 +1,263 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{find_refund_receipt_pda, CandyError, MintPhase};
+use solana_program::instruction::AccountMeta;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{assert_account_empty, get_balance, prepare_nft, update_blockhash};
+use crate::core::MasterEditionManager;
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, refund_nft, CandyConfigBuilder, CandyManager,
+    CandyManagerBuilder, FreezeConfig, SolanaProgramTestResult, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+const ITEMS_AVAILABLE: u64 = 5;
+const FREEZE_TIME: i64 = 60 * 60;
+
+async fn create_all_or_nothing_candy_machine(
+    context: &mut ProgramTestContext,
+    min_items_to_succeed: u64,
+) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new(true, FREEZE_TIME))
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .set_min_items_to_succeed(min_items_to_succeed)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+
+    candy_manager
+}
+
+async fn mint_with_refund_receipt(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+) -> SolanaProgramTestResult<MasterEditionManager> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        DEFAULT_PRICE,
+    );
+    let (refund_receipt, _) = find_refund_receipt_pda(
+        &candy_manager.candy_machine.pubkey(),
+        &nft_info.mint.pubkey(),
+    );
+    ix[1].accounts.push(AccountMeta::new(refund_receipt, false));
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(nft_info)
+}
+
+/// Ends the public sale, keeping the rest of the candy machine settings.
+async fn end_public_sale(
+    context: &mut ProgramTestContext,
+    candy_manager: &mut CandyManager,
+    min_items_to_succeed: u64,
+) {
+    let candy_data = CandyConfigBuilder::new(candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Expired)
+        .set_min_items_to_succeed(min_items_to_succeed)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn refund_failed_all_or_nothing_sale() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let min_items_to_succeed = 3;
+    let mut candy_manager =
+        create_all_or_nothing_candy_machine(context, min_items_to_succeed).await;
+
+    let first_nft = mint_with_refund_receipt(context, &candy_manager)
+        .await
+        .unwrap();
+    let second_nft = mint_with_refund_receipt(context, &candy_manager)
+        .await
+        .unwrap();
+    candy_manager.assert_frozen(context, &first_nft).await;
+
+    // The reserve isn't met yet, so NFTs stay frozen and can't be refunded.
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let result = refund_nft(
+        context,
+        &candy_machine,
+        &candy_manager.freeze_info,
+        &candy_manager.token_info,
+        &first_nft,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::RefundNotAvailable);
+    let result = candy_manager
+        .thaw_nft(context, &first_nft, &first_nft.owner)
+        .await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidThawNft);
+    let result = candy_manager.remove_freeze(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::ReserveNotMet);
+
+    end_public_sale(context, &mut candy_manager, min_items_to_succeed).await;
+
+    for nft in [&first_nft, &second_nft] {
+        let owner_start = get_balance(context, &nft.owner.pubkey()).await;
+        refund_nft(
+            context,
+            &candy_machine,
+            &candy_manager.freeze_info,
+            &candy_manager.token_info,
+            nft,
+        )
+        .await
+        .unwrap();
+        let owner_end = get_balance(context, &nft.owner.pubkey()).await;
+
+        // The refund also returns the freeze fee and the receipt rent, which
+        // cover the transaction fee.
+        assert!(owner_end - owner_start > DEFAULT_PRICE);
+        let (refund_receipt, _) = find_refund_receipt_pda(&candy_machine, &nft.mint.pubkey());
+        assert_account_empty(context, &refund_receipt).await;
+        assert_account_empty(context, &nft.token_account).await;
+    }
+
+    let freeze_pda = candy_manager.get_freeze_pda(context).await;
+    assert_eq!(freeze_pda.frozen_count, 0);
+    candy_manager.unlock_funds(context).await.unwrap();
+}
+
+#[tokio::test]
+async fn fail_refund_when_reserve_met() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let min_items_to_succeed = 2;
+    let mut candy_manager =
+        create_all_or_nothing_candy_machine(context, min_items_to_succeed).await;
+
+    let nft = mint_with_refund_receipt(context, &candy_manager)
+        .await
+        .unwrap();
+    mint_with_refund_receipt(context, &candy_manager)
+        .await
+        .unwrap();
+
+    end_public_sale(context, &mut candy_manager, min_items_to_succeed).await;
+
+    let result = refund_nft(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.freeze_info,
+        &candy_manager.token_info,
+        &nft,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::RefundNotAvailable);
+}
+
+#[tokio::test]
+async fn fail_min_items_to_succeed_above_items_available() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .set_min_items_to_succeed(ITEMS_AVAILABLE + 1)
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidMinItemsToSucceed);
+}
+
+#[tokio::test]
+async fn fail_min_items_to_succeed_without_freeze() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new(true, FREEZE_TIME))
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .set_min_items_to_succeed(2)
+        .build();
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::MinItemsToSucceedRequiresFreeze);
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let all_or_nothing_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(ITEMS_AVAILABLE)
+        .enable_mint_phase(MintPhase::Public)
+        .set_min_items_to_succeed(2)
+        .build();
+    let result = candy_manager
+        .update(context, None, all_or_nothing_data.clone())
+        .await;
+    assert_tx_failed_with_error_code(result, CandyError::MinItemsToSucceedRequiresFreeze);
+
+    candy_manager.set_freeze(context).await.unwrap();
+    candy_manager
+        .update(context, None, all_or_nothing_data)
+        .await
+        .unwrap();
+
+    // Freeze can't be removed before the sale starts, which would let it
+    // run without an escrow to refund from.
+    let result = candy_manager.remove_freeze(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::MinItemsToSucceedRequiresFreeze);
+}
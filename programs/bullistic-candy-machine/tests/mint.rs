@@ -3,7 +3,7 @@
 
 use anchor_client::solana_sdk::transaction::Transaction;
 use bullistic_candy_machine::{CandyError, SplTokenAllowlistMode};
-use solana_program::pubkey::Pubkey;
+use solana_program::{pubkey::Pubkey, system_instruction};
 use solana_program_test::*;
 use solana_sdk::account::{AccountSharedData, WritableAccount};
 use solana_sdk::{signature::Keypair, signer::Signer};
@@ -11,8 +11,8 @@ use solana_sdk::{signature::Keypair, signer::Signer};
 use crate::core::helpers::{prepare_nft, update_blockhash};
 use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
 use crate::utils::{
-    candy_machine_program_test, mint_nft, mint_nft_ix, CandyConfigBuilder, CandyManagerBuilder,
-    SplTokenAllowlistConfig, SplTokenAllowlistInfo,
+    candy_machine_program_test, mint_nft, mint_nft_ix, set_collection_during_mint_ix,
+    CandyConfigBuilder, CandyManagerBuilder, SplTokenAllowlistConfig, SplTokenAllowlistInfo,
 };
 
 pub mod core;
@@ -145,3 +145,161 @@ async fn metadata_check_before_bot_tax() {
         .map_err(|e| e.into());
     assert_tx_failed_with_error_code(tx_result, CandyError::MetadataAccountMustBeEmpty);
 }
+
+#[tokio::test]
+async fn missing_collection_accounts_bot_tax() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .build();
+    candy_manager
+        .create(context, candy_data.clone())
+        .await
+        .unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    // Leave the collection accounts off the mint instruction.
+    candy_manager.collection_info.set = false;
+
+    candy_manager
+        .mint_and_assert_bot_tax(context, None, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn fail_extra_instruction_after_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .build();
+    candy_manager
+        .create(context, candy_data.clone())
+        .await
+        .unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    ix.push(system_instruction::transfer(
+        &candy_manager.minter.pubkey(),
+        &candy_manager.treasury_wallet,
+        1,
+    ));
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    let tx_result = context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into());
+    assert_tx_failed_with_error_code(tx_result, CandyError::SuspiciousTransaction);
+}
+
+#[tokio::test]
+async fn mint_with_deprecated_set_collection_during_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .build();
+    candy_manager
+        .create(context, candy_data.clone())
+        .await
+        .unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    // Older clients leave the collection accounts off the mint instruction
+    // and verify the collection in the instruction after it.
+    let mut collection_info = candy_manager.collection_info.clone();
+    collection_info.set = false;
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        collection_info,
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    ix.push(set_collection_during_mint_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &nft_info.metadata_pubkey,
+        &candy_manager.minter.pubkey(),
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.collection_info,
+    ));
+
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let metadata = nft_info.get_metadata(context).await;
+    let collection = metadata.collection.unwrap();
+    assert_eq!(collection.key, candy_manager.collection_info.mint.pubkey());
+    assert!(collection.verified, "Collection wasn't verified!");
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use bullistic_candy_machine::{
+    CandyError, MintPhase, MintRateLimitSettings, SplTokenAllowlistMode::BurnEveryTime,
+};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+
+use crate::core::helpers::update_blockhash_to_slot;
+use crate::utils::helpers::assert_tx_failed_with_error_code;
+use crate::utils::{
+    candy_machine_program_test, CandyConfigBuilder, CandyManagerBuilder, SplTokenAllowlistConfig,
+};
+
+pub mod core;
+pub mod utils;
+
+#[tokio::test]
+async fn mint_rate_limit() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let max_mints = 2;
+    let window_slots = 1000;
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(5)
+        .enable_mint_phase(MintPhase::Public)
+        .set_mint_rate_limit_settings(MintRateLimitSettings {
+            max_mints,
+            window_slots,
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    for _ in 0..max_mints {
+        candy_manager
+            .mint_and_assert_successful(context, None, true, None)
+            .await
+            .unwrap();
+    }
+    candy_manager
+        .mint_and_assert_failure(context, None, CandyError::MintRateLimitExceeded)
+        .await;
+
+    // Once the window has rolled past the earlier mints, minting is allowed again.
+    update_blockhash_to_slot(context, 2 * window_slots)
+        .await
+        .unwrap();
+    candy_manager
+        .mint_and_assert_successful(context, None, true, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn bot_taxed_mint_not_rate_limited() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let spl_token_allowlist_settings = SplTokenAllowlistConfig::new(BurnEveryTime);
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_spl_token_allowlist_config(spl_token_allowlist_settings.clone())
+        .build(context)
+        .await;
+
+    let allowlist_price = 1;
+    let max_mints = 2;
+    let mint_rate_limit_settings = MintRateLimitSettings {
+        max_mints,
+        window_slots: 1000,
+    };
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(5)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .set_spl_token_allowlist_settings(SplTokenAllowlistConfig::to_candy_format(
+            spl_token_allowlist_settings.clone(),
+            &candy_manager.spl_token_allowlist_info.mint,
+        ))
+        .set_allowlist_price(allowlist_price)
+        .set_mint_rate_limit_settings(mint_rate_limit_settings.clone())
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    // The first mint burns the minter's only allowlist token, so the next
+    // allowlist mint is bot taxed.
+    candy_manager
+        .mint_and_assert_successful(context, Some(allowlist_price), true, None)
+        .await
+        .unwrap();
+    candy_manager
+        .mint_and_assert_bot_tax(context, None, None)
+        .await
+        .unwrap();
+
+    // Keeping the same window keeps the recorded mints.
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(5)
+        .enable_mint_phase(MintPhase::Public)
+        .set_spl_token_allowlist_settings(SplTokenAllowlistConfig::to_candy_format(
+            spl_token_allowlist_settings,
+            &candy_manager.spl_token_allowlist_info.mint,
+        ))
+        .set_allowlist_price(allowlist_price)
+        .set_mint_rate_limit_settings(mint_rate_limit_settings)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+
+    // The taxed attempt didn't count towards max_mints.
+    candy_manager
+        .mint_and_assert_successful(context, None, true, None)
+        .await
+        .unwrap();
+    candy_manager
+        .mint_and_assert_failure(context, None, CandyError::MintRateLimitExceeded)
+        .await;
+}
+
+#[tokio::test]
+async fn fail_invalid_mint_rate_limit_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_rate_limit_settings(MintRateLimitSettings {
+            max_mints: 0,
+            window_slots: 1000,
+        })
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidMintRateLimitSettings);
+}
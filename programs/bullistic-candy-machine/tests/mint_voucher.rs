@@ -0,0 +1,173 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_lang::AccountDeserialize;
+use bullistic_candy_machine::{
+    find_voucher_nonce_pda, CandyError, MintPhase, MintVoucher, VoucherNonce,
+};
+use solana_program::instruction::AccountMeta;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{get_account, get_balance, prepare_nft, update_blockhash};
+use crate::utils::helpers::{
+    assert_tx_failed_with_error_code, find_candy_creator, get_current_unix_timestamp,
+    make_mint_voucher_ix, sol,
+};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn mint_with_voucher(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    voucher_signer: &Keypair,
+    voucher: &MintVoucher,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let (voucher_nonce, _) = find_voucher_nonce_pda(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.minter.pubkey(),
+        voucher.nonce,
+    );
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        voucher.price,
+    );
+    ix[1].accounts.push(AccountMeta::new(voucher_nonce, false));
+    // The voucher must be verified right before the mint instruction.
+    ix.insert(1, make_mint_voucher_ix(voucher_signer, voucher));
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+fn make_voucher(candy_manager: &CandyManager, max_amount: u16, price: u64) -> MintVoucher {
+    MintVoucher {
+        candy_machine: candy_manager.candy_machine.pubkey(),
+        buyer: candy_manager.minter.pubkey(),
+        max_amount,
+        price,
+        expiry: get_current_unix_timestamp() + 60 * 60,
+        nonce: 0,
+    }
+}
+
+#[tokio::test]
+async fn mint_with_voucher_during_allowlist_phase() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let voucher_signer = Keypair::new();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .set_voucher_signer(voucher_signer.pubkey())
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let price = sol(1) / 2;
+    let voucher = make_voucher(&candy_manager, 1, price);
+
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    mint_with_voucher(context, &candy_manager, &voucher_signer, &voucher)
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        price,
+        "Treasury should receive the voucher price"
+    );
+    let candy_end = candy_manager.get_candy(context).await;
+    assert_eq!(candy_end.items_redeemed, 1, "Mint should have succeeded");
+
+    let (voucher_nonce, _) = find_voucher_nonce_pda(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.minter.pubkey(),
+        voucher.nonce,
+    );
+    let voucher_nonce_account = get_account(context, &voucher_nonce).await;
+    let voucher_nonce =
+        VoucherNonce::try_deserialize(&mut voucher_nonce_account.data.as_ref()).unwrap();
+    assert_eq!(voucher_nonce.number_minted, 1);
+
+    // The voucher only allows a single mint.
+    let result = mint_with_voucher(context, &candy_manager, &voucher_signer, &voucher).await;
+    assert_tx_failed_with_error_code(result, CandyError::VoucherMintsAlreadyUsed);
+}
+
+#[tokio::test]
+async fn fail_mint_with_voucher_from_wrong_signer() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .set_voucher_signer(Keypair::new().pubkey())
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let voucher = make_voucher(&candy_manager, 1, sol(1));
+    let result = mint_with_voucher(context, &candy_manager, &Keypair::new(), &voucher).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidMintVoucher);
+}
+
+#[tokio::test]
+async fn fail_mint_with_expired_voucher() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let voucher_signer = Keypair::new();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .set_voucher_signer(voucher_signer.pubkey())
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let voucher = MintVoucher {
+        expiry: get_current_unix_timestamp() - 60 * 60,
+        ..make_voucher(&candy_manager, 1, sol(1))
+    };
+    let result = mint_with_voucher(context, &candy_manager, &voucher_signer, &voucher).await;
+    assert_tx_failed_with_error_code(result, CandyError::MintVoucherExpired);
+}
@@ -0,0 +1,466 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_lang::AccountDeserialize;
+use bullistic_candy_machine::{
+    find_multisig_pda, find_proposal_pda, BotSigner, CandyError, MintPhase, MultisigPda,
+    ProposalAction,
+};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{
+    assert_account_empty, get_account, get_balance, new_funded_keypair, update_blockhash_to_slot,
+};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, sol};
+use crate::utils::{
+    approve_proposal, candy_machine_program_test, create_proposal, execute_proposal,
+    rotate_bot_signers, set_multisig, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn create_multisig_candy_machine(
+    context: &mut ProgramTestContext,
+    signers: &[&Keypair],
+    threshold: u8,
+    timelock_seconds: i64,
+) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    set_multisig(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+        signers.iter().map(|signer| signer.pubkey()).collect(),
+        threshold,
+        timelock_seconds,
+    )
+    .await
+    .unwrap();
+
+    candy_manager
+}
+
+#[tokio::test]
+async fn multisig_updates_price_after_threshold() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let first_signer = new_funded_keypair(context, sol(1)).await;
+    let second_signer = new_funded_keypair(context, sol(1)).await;
+    let outsider = new_funded_keypair(context, sol(1)).await;
+    let mut candy_manager =
+        create_multisig_candy_machine(context, &[&first_signer, &second_signer], 2, 0).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_price(2 * DEFAULT_PRICE)
+        .build();
+
+    // The authority was handed over to the multisig.
+    candy_manager
+        .update(context, None, candy_data.clone())
+        .await
+        .unwrap_err();
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        0,
+        ProposalAction::UpdateCandyMachine {
+            data: candy_data,
+            treasury_wallet: candy_manager.treasury_wallet,
+            treasury_mint: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let result = execute_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        &first_signer.pubkey(),
+        0,
+        None,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::ProposalNotApproved);
+
+    let result = approve_proposal(context, &candy_machine, &outsider, 0).await;
+    assert_tx_failed_with_error_code(result, CandyError::NotAMultisigSigner);
+
+    let result = approve_proposal(context, &candy_machine, &first_signer, 0).await;
+    assert_tx_failed_with_error_code(result, CandyError::ProposalAlreadyApproved);
+
+    approve_proposal(context, &candy_machine, &second_signer, 0)
+        .await
+        .unwrap();
+    execute_proposal(
+        context,
+        &candy_machine,
+        &outsider,
+        &first_signer.pubkey(),
+        0,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.data.price, 2 * DEFAULT_PRICE);
+    assert_account_empty(context, &find_proposal_pda(&candy_machine, 0).0).await;
+}
+
+#[tokio::test]
+async fn multisig_timelock_delays_execution() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let signer = new_funded_keypair(context, sol(1)).await;
+    let new_authority = Keypair::new();
+    let candy_manager = create_multisig_candy_machine(context, &[&signer], 1, 60).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        0,
+        ProposalAction::UpdateAuthority {
+            new_authority: new_authority.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+
+    let result =
+        execute_proposal(context, &candy_machine, &signer, &signer.pubkey(), 0, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::ProposalTimelockActive);
+
+    update_blockhash_to_slot(context, 50_000).await.unwrap();
+
+    execute_proposal(context, &candy_machine, &signer, &signer.pubkey(), 0, None)
+        .await
+        .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(
+        candy_machine_state.bullistic_authority,
+        new_authority.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn multisig_withdraws_to_destination() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let signer = new_funded_keypair(context, sol(1)).await;
+    let destination = Keypair::new().pubkey();
+    let candy_manager = create_multisig_candy_machine(context, &[&signer], 1, 0).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        0,
+        ProposalAction::WithdrawFunds { destination },
+    )
+    .await
+    .unwrap();
+
+    let result =
+        execute_proposal(context, &candy_machine, &signer, &signer.pubkey(), 0, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidWithdrawDestination);
+
+    let candy_machine_balance = get_balance(context, &candy_machine).await;
+    execute_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        &signer.pubkey(),
+        0,
+        Some(destination),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_balance(context, &destination).await,
+        candy_machine_balance
+    );
+    assert_account_empty(context, &candy_machine).await;
+}
+
+#[tokio::test]
+async fn fail_create_proposal_by_non_signer() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let signer = new_funded_keypair(context, sol(1)).await;
+    let outsider = new_funded_keypair(context, sol(1)).await;
+    let candy_manager = create_multisig_candy_machine(context, &[&signer], 1, 0).await;
+
+    let result = create_proposal(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &outsider,
+        0,
+        ProposalAction::UpdateAuthority {
+            new_authority: outsider.pubkey(),
+        },
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::NotAMultisigSigner);
+}
+
+#[tokio::test]
+async fn multisig_restarts_timelock_after_signer_change() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let first_signer = new_funded_keypair(context, sol(1)).await;
+    let second_signer = new_funded_keypair(context, sol(1)).await;
+    let third_signer = new_funded_keypair(context, sol(1)).await;
+    let new_signer = new_funded_keypair(context, sol(1)).await;
+    let new_authority = Keypair::new();
+    let candy_manager = create_multisig_candy_machine(
+        context,
+        &[&first_signer, &second_signer, &third_signer],
+        2,
+        60,
+    )
+    .await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        0,
+        ProposalAction::UpdateAuthority {
+            new_authority: new_authority.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+    approve_proposal(context, &candy_machine, &second_signer, 0)
+        .await
+        .unwrap();
+
+    // Replace the second signer, which leaves the first proposal with only
+    // one valid approval.
+    create_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        1,
+        ProposalAction::UpdateMultisig {
+            signers: vec![
+                first_signer.pubkey(),
+                third_signer.pubkey(),
+                new_signer.pubkey(),
+            ],
+            threshold: 2,
+            timelock_seconds: 60,
+        },
+    )
+    .await
+    .unwrap();
+    approve_proposal(context, &candy_machine, &third_signer, 1)
+        .await
+        .unwrap();
+    update_blockhash_to_slot(context, 50_000).await.unwrap();
+    execute_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        &first_signer.pubkey(),
+        1,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let result = execute_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        &first_signer.pubkey(),
+        0,
+        None,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::ProposalNotApproved);
+
+    // Reaching the threshold again restarts the timelock.
+    approve_proposal(context, &candy_machine, &new_signer, 0)
+        .await
+        .unwrap();
+    let result = execute_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        &first_signer.pubkey(),
+        0,
+        None,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::ProposalTimelockActive);
+
+    update_blockhash_to_slot(context, 100_000).await.unwrap();
+    execute_proposal(
+        context,
+        &candy_machine,
+        &first_signer,
+        &first_signer.pubkey(),
+        0,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(
+        candy_machine_state.bullistic_authority,
+        new_authority.pubkey()
+    );
+}
+
+#[tokio::test]
+async fn multisig_rotates_bot_signers() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let signer = new_funded_keypair(context, sol(1)).await;
+    let candy_manager = create_multisig_candy_machine(context, &[&signer], 1, 0).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let bot_signers = vec![BotSigner {
+        key: Keypair::new().pubkey(),
+        expires_at: None,
+    }];
+
+    // The authority was handed over to the multisig.
+    let result = rotate_bot_signers(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        None,
+        bot_signers.clone(),
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        0,
+        ProposalAction::RotateBotSigners {
+            bot_signers: bot_signers.clone(),
+        },
+    )
+    .await
+    .unwrap();
+    execute_proposal(context, &candy_machine, &signer, &signer.pubkey(), 0, None)
+        .await
+        .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.bot_signers, bot_signers);
+}
+
+#[tokio::test]
+async fn multisig_can_be_set_again_after_handing_back_authority() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let signer = new_funded_keypair(context, sol(1)).await;
+    let new_signer = new_funded_keypair(context, sol(1)).await;
+    let candy_manager = create_multisig_candy_machine(context, &[&signer], 1, 0).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let bullistic_authority = &candy_manager.bullistic_authority;
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        0,
+        ProposalAction::UpdateAuthority {
+            new_authority: bullistic_authority.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+    execute_proposal(context, &candy_machine, &signer, &signer.pubkey(), 0, None)
+        .await
+        .unwrap();
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(
+        candy_machine_state.bullistic_authority,
+        bullistic_authority.pubkey()
+    );
+
+    // The multisig PDA is still there and gets reused.
+    set_multisig(
+        context,
+        &candy_machine,
+        bullistic_authority,
+        vec![new_signer.pubkey()],
+        1,
+        0,
+    )
+    .await
+    .unwrap();
+
+    let (multisig_pda, _) = find_multisig_pda(&candy_machine);
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.bullistic_authority, multisig_pda);
+
+    let account = get_account(context, &multisig_pda).await;
+    let multisig = MultisigPda::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(multisig.signers, vec![new_signer.pubkey()]);
+    assert_eq!(multisig.proposal_count, 1);
+
+    let result = create_proposal(
+        context,
+        &candy_machine,
+        &signer,
+        1,
+        ProposalAction::UpdateAuthority {
+            new_authority: signer.pubkey(),
+        },
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::NotAMultisigSigner);
+
+    create_proposal(
+        context,
+        &candy_machine,
+        &new_signer,
+        1,
+        ProposalAction::UpdateAuthority {
+            new_authority: new_signer.pubkey(),
+        },
+    )
+    .await
+    .unwrap();
+    execute_proposal(
+        context,
+        &candy_machine,
+        &new_signer,
+        &new_signer.pubkey(),
+        1,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.bullistic_authority, new_signer.pubkey());
+}
@@ -0,0 +1,274 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{CandyError, HiddenSettings, MintPhase, OpenEditionSettings};
+use mpl_token_metadata::pda::{find_master_edition_account, find_metadata_account};
+use mpl_token_metadata::state::{Edition, EDITION, EDITION_MARKER_BIT_SIZE, PREFIX};
+use solana_program::borsh::try_from_slice_unchecked;
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::core::helpers::{
+    create_associated_token_account, get_account, prepare_nft, update_blockhash,
+};
+use crate::core::{MasterEditionManager, MetadataManager};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+/// Creates a master edition and moves its token to the candy machine creator
+/// PDA, which signs for every edition printed from it.
+async fn create_master_edition_for_candy_machine(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+) -> MasterEditionManager {
+    let metadata_info = MetadataManager::new(&candy_manager.bullistic_authority);
+    metadata_info
+        .create_v2(
+            context,
+            "Open Edition".to_string(),
+            "OPEN".to_string(),
+            "URI".to_string(),
+            None,
+            500,
+            true,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let master_edition_info = MasterEditionManager::new(&metadata_info);
+    master_edition_info.create_v3(context, None).await.unwrap();
+
+    let master_edition_mint = metadata_info.mint.pubkey();
+    let (candy_machine_creator, _) = find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let destination =
+        create_associated_token_account(context, &candy_machine_creator, &master_edition_mint)
+            .await
+            .unwrap();
+    update_blockhash(context).await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::transfer(
+            &spl_token::id(),
+            &metadata_info.get_ata(),
+            &destination,
+            &candy_manager.bullistic_authority.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap()],
+        Some(&candy_manager.bullistic_authority.pubkey()),
+        &[&candy_manager.bullistic_authority],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    master_edition_info
+}
+
+async fn create_open_edition_candy_machine(
+    context: &mut ProgramTestContext,
+) -> (CandyManager, MasterEditionManager) {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let master_edition_info =
+        create_master_edition_for_candy_machine(context, &candy_manager).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_open_edition_settings(OpenEditionSettings {
+            master_edition_mint: master_edition_info.mint.pubkey(),
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    (candy_manager, master_edition_info)
+}
+
+/// Mints the next edition, passing the master edition accounts the open
+/// edition mint needs.
+async fn mint_edition(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    master_edition_mint: &Pubkey,
+) -> SolanaProgramTestResult<MasterEditionManager> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    let edition = candy_manager.get_candy(context).await.items_redeemed + 1;
+    let edition_marker_number = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    let (edition_marker, _) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            master_edition_mint.as_ref(),
+            EDITION.as_bytes(),
+            edition_marker_number.as_bytes(),
+        ],
+        &mpl_token_metadata::id(),
+    );
+    ix[1].accounts.extend([
+        AccountMeta::new(find_metadata_account(master_edition_mint).0, false),
+        AccountMeta::new(find_master_edition_account(master_edition_mint).0, false),
+        AccountMeta::new_readonly(
+            get_associated_token_address(&candy_machine_creator, master_edition_mint),
+            false,
+        ),
+        AccountMeta::new(edition_marker, false),
+    ]);
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(nft_info)
+}
+
+#[tokio::test]
+async fn fail_open_edition_with_hidden_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_hidden_settings(HiddenSettings {
+            name: "Hidden".to_string(),
+            uri: "https://example.com".to_string(),
+            hash: [0; 32],
+        })
+        .set_open_edition_settings(OpenEditionSettings {
+            master_edition_mint: Pubkey::new_unique(),
+        })
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidOpenEditionSettings);
+}
+
+#[tokio::test]
+async fn fail_add_config_lines_to_open_edition() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_open_edition_settings(OpenEditionSettings {
+            master_edition_mint: Pubkey::new_unique(),
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let result = candy_manager.fill_config_lines(context).await;
+    assert_tx_failed_with_error_code(result, CandyError::OpenEditionConfigsDoNotHaveConfigLines);
+}
+
+#[tokio::test]
+async fn mint_open_edition() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let (candy_manager, master_edition_info) = create_open_edition_candy_machine(context).await;
+    let master_edition_mint = master_edition_info.mint.pubkey();
+
+    for expected_edition in 1..=2 {
+        let nft_info = mint_edition(context, &candy_manager, &master_edition_mint)
+            .await
+            .unwrap();
+
+        let edition_account = get_account(context, &nft_info.edition_pubkey).await;
+        let edition: Edition = try_from_slice_unchecked(&edition_account.data).unwrap();
+        assert_eq!(edition.edition, expected_edition);
+        assert_eq!(edition.parent, master_edition_info.edition_pubkey);
+        assert_eq!(
+            candy_manager.get_candy(context).await.items_redeemed,
+            expected_edition
+        );
+    }
+}
+
+#[tokio::test]
+async fn switch_on_open_edition_settings_before_first_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let master_edition_info =
+        create_master_edition_for_candy_machine(context, &candy_manager).await;
+    let open_edition_settings = OpenEditionSettings {
+        master_edition_mint: master_edition_info.mint.pubkey(),
+    };
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_open_edition_settings(open_edition_settings.clone())
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+
+    mint_edition(
+        context,
+        &candy_manager,
+        &open_edition_settings.master_edition_mint,
+    )
+    .await
+    .unwrap();
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    let result = candy_manager.update(context, None, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::CannotSwitchOpenEditionSettings);
+}
+
+#[tokio::test]
+async fn fail_switch_off_open_edition_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let (mut candy_manager, _) = create_open_edition_candy_machine(context).await;
+
+    // The candy machine has no space for config lines, even before any mint.
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    let result = candy_manager.update(context, None, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::CannotSwitchOpenEditionSettings);
+}
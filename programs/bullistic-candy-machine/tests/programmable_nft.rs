@@ -0,0 +1,240 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{
+    BurnToMintMode, BurnToMintSettings, CandyError, MintPhase, OpenEditionSettings,
+    ProgrammableNftSettings,
+};
+use mpl_token_metadata::{
+    pda::find_token_record_account,
+    state::{TokenDelegateRole, TokenMetadataAccount, TokenRecord, TokenStandard, TokenState},
+};
+use solana_program::{instruction::AccountMeta, program_option::COption, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{compute_budget::ComputeBudgetInstruction, signature::Keypair, signer::Signer};
+use spl_token::state::AccountState;
+
+use crate::core::helpers::{get_account, get_token_account, prepare_nft, update_blockhash};
+use crate::core::MasterEditionManager;
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    FreezeConfig, SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+/// Mints a programmable NFT, passing its token record after the other
+/// remaining accounts.
+async fn mint_programmable_nft(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+) -> SolanaProgramTestResult<MasterEditionManager> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+    // Creating, minting and locking a programmable NFT takes a few more
+    // token metadata instructions than a regular mint.
+    ix[0] = ComputeBudgetInstruction::set_compute_unit_limit(1_400_000);
+    let (token_record, _) =
+        find_token_record_account(&nft_info.mint.pubkey(), &nft_info.token_account);
+    ix[1].accounts.push(AccountMeta::new(token_record, false));
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(nft_info)
+}
+
+async fn get_token_record(
+    context: &mut ProgramTestContext,
+    nft_info: &MasterEditionManager,
+) -> TokenRecord {
+    let (token_record, _) =
+        find_token_record_account(&nft_info.mint.pubkey(), &nft_info.token_account);
+    let account = get_account(context, &token_record).await;
+    TokenRecord::safe_deserialize(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn mint_programmable_nft_with_collection() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_collection(true)
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .enable_mint_phase(MintPhase::Public)
+        .set_programmable_nft_settings(ProgrammableNftSettings { rule_set: None })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_collection(context).await.unwrap();
+
+    let nft_info = mint_programmable_nft(context, &candy_manager)
+        .await
+        .unwrap();
+
+    let candy = candy_manager.get_candy(context).await;
+    assert_eq!(candy.items_redeemed, 1);
+
+    let metadata = nft_info.get_metadata(context).await;
+    assert_eq!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    );
+    assert_eq!(metadata.update_authority, candy.creator_authority);
+    // The legacy collection authority record verifies programmable NFTs too.
+    let collection = metadata.collection.unwrap();
+    assert_eq!(collection.key, candy_manager.collection_info.mint.pubkey());
+    assert!(collection.verified, "Collection wasn't verified!");
+
+    let token_record = get_token_record(context, &nft_info).await;
+    assert_eq!(token_record.state, TokenState::Unlocked);
+    assert_eq!(token_record.delegate, None);
+    assert_eq!(token_record.delegate_role, None);
+
+    // Programmable NFT token accounts are always frozen by token metadata.
+    let token_account = get_token_account(context, &nft_info.token_account)
+        .await
+        .unwrap();
+    assert_eq!(token_account.owner, candy_manager.minter.pubkey());
+    assert_eq!(token_account.amount, 1);
+    assert_eq!(token_account.state, AccountState::Frozen);
+}
+
+#[tokio::test]
+async fn mint_frozen_programmable_nft() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new(true, 60 * 60))
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .enable_mint_phase(MintPhase::Public)
+        .set_programmable_nft_settings(ProgrammableNftSettings { rule_set: None })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let nft_info = mint_programmable_nft(context, &candy_manager)
+        .await
+        .unwrap();
+
+    // The freeze PDA locks the NFT as its utility delegate.
+    let freeze_pda = candy_manager.freeze_info.pda;
+    let token_record = get_token_record(context, &nft_info).await;
+    assert_eq!(token_record.state, TokenState::Locked);
+    assert_eq!(token_record.delegate, Some(freeze_pda));
+    assert_eq!(token_record.delegate_role, Some(TokenDelegateRole::Utility));
+
+    let token_account = get_token_account(context, &nft_info.token_account)
+        .await
+        .unwrap();
+    assert_eq!(token_account.delegate, COption::Some(freeze_pda));
+    assert_eq!(token_account.delegated_amount, 1);
+    assert_eq!(token_account.state, AccountState::Frozen);
+
+    let freeze = candy_manager.get_freeze_pda(context).await;
+    assert_eq!(freeze.frozen_count, 1);
+}
+
+#[tokio::test]
+async fn mint_programmable_nft_outside_burn_to_mint_phase() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    // Burning only replaces the allowlist, so public mints pass no burn
+    // accounts and the token record directly follows the other accounts.
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(2)
+        .enable_mint_phase(MintPhase::Public)
+        .set_burn_to_mint_settings(BurnToMintSettings {
+            mode: BurnToMintMode::ReplacesAllowlist,
+            collection_mint: Pubkey::new_unique(),
+        })
+        .set_programmable_nft_settings(ProgrammableNftSettings { rule_set: None })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let nft_info = mint_programmable_nft(context, &candy_manager)
+        .await
+        .unwrap();
+
+    let metadata = nft_info.get_metadata(context).await;
+    assert_eq!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible)
+    );
+    let token_record = get_token_record(context, &nft_info).await;
+    assert_eq!(token_record.state, TokenState::Unlocked);
+}
+
+#[tokio::test]
+async fn fail_programmable_nft_with_open_edition() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_open_edition_settings(OpenEditionSettings {
+            master_edition_mint: Pubkey::new_unique(),
+        })
+        .set_programmable_nft_settings(ProgrammableNftSettings { rule_set: None })
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidProgrammableNftSettings);
+}
+
+#[tokio::test]
+async fn fail_programmable_nft_with_on_chain_reveal() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_on_chain_reveal_enabled(true)
+        .set_programmable_nft_settings(ProgrammableNftSettings {
+            rule_set: Some(Pubkey::new_unique()),
+        })
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidProgrammableNftSettings);
+}
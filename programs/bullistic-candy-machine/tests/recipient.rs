@@ -0,0 +1,163 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{CandyError, MintPhase};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::core::helpers::{get_balance, get_token_account, prepare_nft, update_blockhash};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_for_recipient_ix, CandyConfigBuilder, CandyManager,
+    CandyManagerBuilder, SolanaProgramTestResult, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn mint_to_recipient(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    recipient: &Keypair,
+    recipient_signs: bool,
+) -> SolanaProgramTestResult<Pubkey> {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let mut ix = mint_nft_for_recipient_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        Some(recipient.pubkey()),
+        None,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    let mut signers = vec![&candy_manager.minter, &nft_info.mint];
+    if recipient_signs {
+        for account in ix[1].accounts.iter_mut() {
+            if account.pubkey == recipient.pubkey() {
+                account.is_signer = true;
+            }
+        }
+        signers.push(recipient);
+    }
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &signers,
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(get_associated_token_address(
+        &recipient.pubkey(),
+        &nft_info.mint.pubkey(),
+    ))
+}
+
+#[tokio::test]
+async fn mint_to_separate_recipient() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .set_limit_per_address(1)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let recipient = Keypair::new();
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    let recipient_token_account = mint_to_recipient(context, &candy_manager, &recipient, true)
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        DEFAULT_PRICE,
+        "Buyer should pay for the recipient's mint"
+    );
+    let token_account = get_token_account(context, &recipient_token_account)
+        .await
+        .unwrap();
+    assert_eq!(token_account.owner, recipient.pubkey());
+    assert_eq!(token_account.amount, 1, "Recipient should receive the NFT");
+
+    // The buy limit applies to the recipient, not to the payer.
+    let result = mint_to_recipient(context, &candy_manager, &recipient, true).await;
+    assert_tx_failed_with_error_code(result, CandyError::BuyLimitPerAddressExceeded);
+
+    candy_manager.mint_nft(context, None, None).await.unwrap();
+    let candy_end = candy_manager.get_candy(context).await;
+    assert_eq!(candy_end.items_redeemed, 2);
+}
+
+#[tokio::test]
+async fn fail_use_limit_of_non_signing_recipient() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .set_limit_per_address(1)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    // Otherwise anyone could use up the recipient's limit per address.
+    let recipient = Keypair::new();
+    let result = mint_to_recipient(context, &candy_manager, &recipient, false).await;
+    assert_tx_failed_with_error_code(result, CandyError::RecipientSignatureRequired);
+
+    mint_to_recipient(context, &candy_manager, &recipient, true)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn mint_to_non_signing_recipient_without_limit() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let recipient = Keypair::new();
+    let recipient_token_account = mint_to_recipient(context, &candy_manager, &recipient, false)
+        .await
+        .unwrap();
+    let token_account = get_token_account(context, &recipient_token_account)
+        .await
+        .unwrap();
+    assert_eq!(token_account.owner, recipient.pubkey());
+}
@@ -0,0 +1,201 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_lang::AccountDeserialize;
+use bullistic_candy_machine::constants::FREEZE_FEE;
+use bullistic_candy_machine::{find_referral_account_pda, CandyError, MintPhase, ReferralAccount};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use crate::core::helpers::{
+    get_account, get_balance, new_funded_keypair, prepare_nft, update_blockhash,
+};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator, sol};
+use crate::utils::{
+    candy_machine_program_test, claim_referral_earnings, mint_nft_for_recipient_ix,
+    CandyConfigBuilder, CandyManager, CandyManagerBuilder, FreezeConfig, SolanaProgramTestResult,
+    DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+const REFERRAL_FEE_BASIS_POINTS: u16 = 1000;
+
+async fn mint_with_referrer(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    referrer: &Pubkey,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+    let mint_price = candy_manager.get_mint_price(context).await;
+
+    let ix = mint_nft_for_recipient_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        None,
+        Some(*referrer),
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        mint_price,
+    );
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+async fn get_referral_account(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    referrer: &Pubkey,
+) -> ReferralAccount {
+    let (referral_account, _) = find_referral_account_pda(candy_machine, referrer);
+    let account = get_account(context, &referral_account).await;
+    ReferralAccount::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn mint_with_referrer_and_claim_earnings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+    let referrer = new_funded_keypair(context, sol(1)).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .enable_mint_phase(MintPhase::Public)
+        .set_referral_fee_basis_points(REFERRAL_FEE_BASIS_POINTS)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let referral_fee = DEFAULT_PRICE * REFERRAL_FEE_BASIS_POINTS as u64 / 10000;
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    mint_with_referrer(context, &candy_manager, &referrer.pubkey())
+        .await
+        .unwrap();
+    mint_with_referrer(context, &candy_manager, &referrer.pubkey())
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        2 * (DEFAULT_PRICE - referral_fee),
+        "Treasury should receive the mint price minus the referral share"
+    );
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let referral_account = get_referral_account(context, &candy_machine, &referrer.pubkey()).await;
+    assert_eq!(referral_account.referrer, referrer.pubkey());
+    assert_eq!(referral_account.number_referred, 2);
+    assert_eq!(referral_account.total_earned, 2 * referral_fee);
+    assert_eq!(referral_account.total_claimed, 0);
+
+    let referrer_start = get_balance(context, &referrer.pubkey()).await;
+    claim_referral_earnings(context, &candy_machine, &referrer)
+        .await
+        .unwrap();
+    let referrer_end = get_balance(context, &referrer.pubkey()).await;
+
+    // The referrer pays the transaction fee for the claim.
+    assert!(referrer_end > referrer_start);
+    let referral_account = get_referral_account(context, &candy_machine, &referrer.pubkey()).await;
+    assert_eq!(referral_account.total_claimed, 2 * referral_fee);
+
+    let result = claim_referral_earnings(context, &candy_machine, &referrer).await;
+    assert_tx_failed_with_error_code(result, CandyError::NoReferralEarnings);
+}
+
+#[tokio::test]
+async fn referral_not_paid_while_frozen() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_freeze(FreezeConfig::new(true, 60 * 60))
+        .build(context)
+        .await;
+    let referrer = new_funded_keypair(context, sol(1)).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .enable_mint_phase(MintPhase::Public)
+        .set_referral_fee_basis_points(REFERRAL_FEE_BASIS_POINTS)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let freeze_start = get_balance(context, &candy_manager.freeze_info.pda).await;
+    mint_with_referrer(context, &candy_manager, &referrer.pubkey())
+        .await
+        .unwrap();
+    let freeze_end = get_balance(context, &candy_manager.freeze_info.pda).await;
+
+    // The full price is escrowed, so a refund returns all of it.
+    assert_eq!(freeze_end - freeze_start, DEFAULT_PRICE + FREEZE_FEE);
+    let referral_account = get_referral_account(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &referrer.pubkey(),
+    )
+    .await;
+    assert_eq!(referral_account.number_referred, 0);
+    assert_eq!(referral_account.total_earned, 0);
+}
+
+#[tokio::test]
+async fn fail_self_referral() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .set_referral_fee_basis_points(REFERRAL_FEE_BASIS_POINTS)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let result = mint_with_referrer(context, &candy_manager, &candy_manager.minter.pubkey()).await;
+    assert_tx_failed_with_error_code(result, CandyError::SelfReferral);
+}
+
+#[tokio::test]
+async fn fail_referral_when_not_enabled() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let result = mint_with_referrer(context, &candy_manager, &Keypair::new().pubkey()).await;
+    assert_tx_failed_with_error_code(result, CandyError::ReferralsNotEnabled);
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use bullistic_candy_machine::{
+    get_reveal_hash_commitment, hash_reveal_config_line, CandyError, ConfigLine, HiddenSettings,
+};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::MetadataManager;
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator};
+use crate::utils::{
+    add_reveal_config_lines, candy_machine_program_test, initialize_reveal, reveal_nft,
+    CandyConfigBuilder, CandyManagerBuilder,
+};
+
+pub mod core;
+pub mod utils;
+
+fn reveal_config_lines(items_available: u64) -> Vec<ConfigLine> {
+    (1..=items_available)
+        .map(|i| ConfigLine {
+            name: format!("Revealed #{}", i),
+            uri: format!("https://example.com/{}.json", i),
+        })
+        .collect()
+}
+
+fn reveal_hidden_settings(config_lines: &[ConfigLine]) -> HiddenSettings {
+    let running_hash = config_lines.iter().fold([0; 32], |hash, config_line| {
+        hash_reveal_config_line(&hash, config_line).unwrap()
+    });
+
+    HiddenSettings {
+        name: "Hidden".to_string(),
+        uri: "https://example.com/hidden.json".to_string(),
+        hash: get_reveal_hash_commitment(&running_hash),
+    }
+}
+
+#[tokio::test]
+async fn fail_on_chain_reveal_without_hidden_settings() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_on_chain_reveal_enabled(true)
+        .build();
+
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidOnChainRevealSettings);
+}
+
+#[tokio::test]
+async fn reveal_minted_nft_on_chain() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let items_available = 2;
+    let config_lines = reveal_config_lines(items_available);
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(items_available)
+        .set_hidden_settings(reveal_hidden_settings(&config_lines))
+        .set_on_chain_reveal_enabled(true)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let nft = candy_manager.mint_nft(context, None, None).await.unwrap();
+    let metadata = MetadataManager::get_data_from_account(context, &nft.metadata_pubkey).await;
+    assert_eq!(
+        metadata.update_authority,
+        find_candy_creator(&candy_manager.candy_machine.pubkey()).0,
+        "Unrevealed NFT should keep the candy machine creator as update authority"
+    );
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    initialize_reveal(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+    add_reveal_config_lines(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        0,
+        reveal_config_lines(items_available),
+    )
+    .await
+    .unwrap();
+    reveal_nft(
+        context,
+        &candy_machine,
+        &candy_manager.minter,
+        &nft.metadata_pubkey,
+    )
+    .await
+    .unwrap();
+
+    let metadata = MetadataManager::get_data_from_account(context, &nft.metadata_pubkey).await;
+    assert_eq!(
+        metadata.data.uri.trim_matches(char::from(0)),
+        config_lines[0].uri,
+        "NFT uri should be revealed"
+    );
+    assert_eq!(
+        metadata.update_authority,
+        candy_manager.creator_authority.pubkey(),
+        "Revealed NFT update authority should be the creator authority"
+    );
+}
+
+#[tokio::test]
+async fn fail_reveal_lines_hash_mismatch() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let items_available = 2;
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(items_available)
+        .set_hidden_settings(reveal_hidden_settings(&reveal_config_lines(
+            items_available,
+        )))
+        .set_on_chain_reveal_enabled(true)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    initialize_reveal(context, &candy_machine, &candy_manager.bullistic_authority)
+        .await
+        .unwrap();
+
+    let mut config_lines = reveal_config_lines(items_available);
+    config_lines.reverse();
+    let result = add_reveal_config_lines(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        0,
+        config_lines,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::RevealHashMismatch);
+}
@@ -0,0 +1,254 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use bullistic_candy_machine::{find_roles_pda, CandyError, MintPhase, Role};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{get_balance, new_funded_keypair};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, make_config_lines, sol};
+use crate::utils::{
+    add_config_lines, append_merkle_allowlist_roots, candy_machine_program_test,
+    get_empty_merkle_tree_node, initialize_roles, set_paused, set_role, withdraw_funds,
+    CandyConfigBuilder, CandyManager, CandyManagerBuilder, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn create_candy_machine_with_roles(context: &mut ProgramTestContext) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(10)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    initialize_roles(
+        context,
+        &candy_manager.candy_machine.pubkey(),
+        &candy_manager.bullistic_authority,
+    )
+    .await
+    .unwrap();
+
+    candy_manager
+}
+
+#[tokio::test]
+async fn config_loader_only_loads_config_lines() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_candy_machine_with_roles(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (roles_pda, _) = find_roles_pda(&candy_machine);
+    let config_loader = new_funded_keypair(context, sol(1)).await;
+
+    // Without the role, the key can't load config lines.
+    let result = add_config_lines(
+        context,
+        &candy_machine,
+        &config_loader,
+        Some(roles_pda),
+        0,
+        make_config_lines(0, 10),
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::ConfigLoader,
+        Some(config_loader.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    add_config_lines(
+        context,
+        &candy_machine,
+        &config_loader,
+        Some(roles_pda),
+        0,
+        make_config_lines(0, 10),
+    )
+    .await
+    .unwrap();
+
+    let result = append_merkle_allowlist_roots(
+        context,
+        &candy_machine,
+        &config_loader,
+        Some(roles_pda),
+        vec![get_empty_merkle_tree_node()],
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+
+    let result = set_paused(
+        context,
+        &candy_machine,
+        &config_loader,
+        Some(roles_pda),
+        true,
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+}
+
+#[tokio::test]
+async fn allowlist_manager_appends_roots() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_candy_machine_with_roles(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (roles_pda, _) = find_roles_pda(&candy_machine);
+    let allowlist_manager = new_funded_keypair(context, sol(1)).await;
+
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::AllowlistManager,
+        Some(allowlist_manager.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    append_merkle_allowlist_roots(
+        context,
+        &candy_machine,
+        &allowlist_manager,
+        Some(roles_pda),
+        vec![get_empty_merkle_tree_node()],
+    )
+    .await
+    .unwrap();
+
+    let candy_machine_state = candy_manager.get_candy(context).await;
+    assert_eq!(candy_machine_state.data.merkle_allowlist_root_list.len(), 1);
+
+    // Unassigning the role revokes the key.
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::AllowlistManager,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let result = append_merkle_allowlist_roots(
+        context,
+        &candy_machine,
+        &allowlist_manager,
+        Some(roles_pda),
+        vec![get_empty_merkle_tree_node()],
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+}
+
+#[tokio::test]
+async fn pauser_pauses_minting() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = create_candy_machine_with_roles(context).await;
+    candy_manager.fill_config_lines(context).await.unwrap();
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (roles_pda, _) = find_roles_pda(&candy_machine);
+    let pauser = new_funded_keypair(context, sol(1)).await;
+
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::Pauser,
+        Some(pauser.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    set_paused(context, &candy_machine, &pauser, Some(roles_pda), true)
+        .await
+        .unwrap();
+    candy_manager
+        .mint_and_assert_failure(context, None, CandyError::CandyMachinePaused)
+        .await;
+
+    set_paused(context, &candy_machine, &pauser, Some(roles_pda), false)
+        .await
+        .unwrap();
+    candy_manager
+        .mint_and_assert_successful(context, Some(DEFAULT_PRICE), false, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn treasurer_withdraws_to_authority_wallet() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let candy_manager = create_candy_machine_with_roles(context).await;
+    let candy_machine = candy_manager.candy_machine.pubkey();
+    let (roles_pda, _) = find_roles_pda(&candy_machine);
+    let authority = candy_manager.bullistic_authority.pubkey();
+    let treasurer = new_funded_keypair(context, sol(1)).await;
+
+    // Without the role, the key can't withdraw.
+    let result = withdraw_funds(
+        context,
+        &candy_machine,
+        &treasurer,
+        &authority,
+        Some(roles_pda),
+    )
+    .await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingRole);
+
+    set_role(
+        context,
+        &candy_machine,
+        &candy_manager.bullistic_authority,
+        Role::Treasurer,
+        Some(treasurer.pubkey()),
+    )
+    .await
+    .unwrap();
+
+    // The treasurer can't send the funds to its own wallet.
+    let result = withdraw_funds(
+        context,
+        &candy_machine,
+        &treasurer,
+        &treasurer.pubkey(),
+        Some(roles_pda),
+    )
+    .await;
+    assert!(result.is_err());
+
+    let candy_machine_balance = get_balance(context, &candy_machine).await;
+    let authority_balance = get_balance(context, &authority).await;
+    let treasurer_balance = get_balance(context, &treasurer.pubkey()).await;
+
+    withdraw_funds(
+        context,
+        &candy_machine,
+        &treasurer,
+        &authority,
+        Some(roles_pda),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(get_balance(context, &candy_machine).await, 0);
+    assert_eq!(
+        get_balance(context, &authority).await,
+        authority_balance + candy_machine_balance
+    );
+    assert!(get_balance(context, &treasurer.pubkey()).await < treasurer_balance);
+}
@@ -8,11 +8,11 @@ use crate::{
     core::helpers::{airdrop, clone_keypair, strip_empty_bytes_from_string},
     utils::{
         candy_machine_program_test,
-        helpers::{get_config_line_name, sol},
-        CandyConfigBuilder, CandyManagerBuilder,
+        helpers::{assert_tx_failed_with_error_code, get_config_line_name, sol},
+        CandyConfigBuilder, CandyManager, CandyManagerBuilder, SplTokenAllowlistConfig,
     },
 };
-use bullistic_candy_machine::MintPhase;
+use bullistic_candy_machine::{CandyError, MintOrder, MintPhase, SplTokenAllowlistMode::NeverBurn};
 
 mod core;
 mod utils;
@@ -65,3 +65,126 @@ async fn creator_authority_can_pre_mint_in_sequential_order() {
         );
     }
 }
+
+/// Mints an NFT and returns the name of the config line it was minted from.
+async fn mint_config_line_name(
+    context: &mut ProgramTestContext,
+    candy_manager: &mut CandyManager,
+) -> String {
+    let nft = candy_manager
+        .mint_and_assert_successful(context, None, false, None)
+        .await
+        .unwrap();
+
+    let metadata = nft.get_metadata(context).await;
+    strip_empty_bytes_from_string(metadata.data.name)
+}
+
+#[tokio::test]
+async fn sequential_mint_order_applies_in_public_phase() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let mut candy_manager = CandyManagerBuilder::new().build(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_order(MintOrder::Sequential)
+        .set_items_available(10)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    for i in 0..10 {
+        let name = mint_config_line_name(context, &mut candy_manager).await;
+        assert_eq!(name, get_config_line_name(i));
+    }
+}
+
+#[tokio::test]
+async fn reverse_sequential_mint_order_mints_from_last_line() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let mut candy_manager = CandyManagerBuilder::new().build(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_order(MintOrder::ReverseSequential)
+        .set_items_available(10)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    for i in 0..10 {
+        let name = mint_config_line_name(context, &mut candy_manager).await;
+        assert_eq!(name, get_config_line_name(9 - i));
+    }
+}
+
+#[tokio::test]
+async fn reserved_range_mint_order_keeps_allowlist_lines_apart() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_spl_token_allowlist_config(SplTokenAllowlistConfig::new(NeverBurn))
+        .build(context)
+        .await;
+
+    let mint_order = MintOrder::ReservedRangePerPhase { allowlist_items: 4 };
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_order(mint_order.clone())
+        .set_items_available(10)
+        .enable_mint_phase(MintPhase::Allowlist)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let allowlist_names: Vec<String> = (0..4).map(get_config_line_name).collect();
+    let mut minted_names = vec![];
+    for _ in 0..4 {
+        let name = mint_config_line_name(context, &mut candy_manager).await;
+        assert!(allowlist_names.contains(&name));
+        assert!(!minted_names.contains(&name));
+        minted_names.push(name);
+    }
+
+    candy_manager
+        .mint_and_assert_failure(context, None, CandyError::MintOrderRangeSoldOut)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_order(mint_order)
+        .set_items_available(10)
+        .enable_mint_phase(MintPhase::Public)
+        .build();
+    candy_manager
+        .update(context, None, candy_data)
+        .await
+        .unwrap();
+
+    for _ in 0..6 {
+        let name = mint_config_line_name(context, &mut candy_manager).await;
+        assert!(!allowlist_names.contains(&name));
+        assert!(!minted_names.contains(&name));
+        minted_names.push(name);
+    }
+}
+
+#[tokio::test]
+async fn fail_reserved_range_larger_than_items_available() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+
+    let mut candy_manager = CandyManagerBuilder::new().build(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_mint_order(MintOrder::ReservedRangePerPhase {
+            allowlist_items: 11,
+        })
+        .set_items_available(10)
+        .build();
+    let result = candy_manager.create(context, candy_data).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidMintOrder);
+}
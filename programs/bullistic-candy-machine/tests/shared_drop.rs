@@ -0,0 +1,195 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use anchor_lang::AccountDeserialize;
+use bullistic_candy_machine::{find_drop_buyer_info_pda, CandyError, DropAccount, MintPhase};
+use solana_program::instruction::AccountMeta;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{
+    clone_keypair, get_account, new_funded_keypair, prepare_nft, update_blockhash,
+};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator, sol};
+use crate::utils::{
+    candy_machine_program_test, initialize_drop, mint_nft_ix, CandyConfigBuilder, CandyManager,
+    CandyManagerBuilder, SolanaProgramTestResult, DEFAULT_PRICE,
+};
+
+pub mod core;
+pub mod utils;
+
+async fn create_drop_candy_machine(
+    context: &mut ProgramTestContext,
+    bullistic_authority: &Keypair,
+    minter: &Keypair,
+    drop: Option<Pubkey>,
+) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_bullistic_authority(clone_keypair(bullistic_authority))
+        .set_minter(clone_keypair(minter))
+        .build(context)
+        .await;
+
+    let mut candy_data =
+        CandyConfigBuilder::new(&candy_manager).enable_mint_phase(MintPhase::Public);
+    if let Some(drop) = drop {
+        candy_data = candy_data.set_drop(drop);
+    }
+    candy_manager
+        .create(context, candy_data.build())
+        .await
+        .unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    candy_manager
+}
+
+async fn mint_with_drop(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    drop: &Pubkey,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        DEFAULT_PRICE,
+    );
+    let (drop_buyer_info, _) = find_drop_buyer_info_pda(drop, &candy_manager.minter.pubkey());
+    ix[1].accounts.extend([
+        AccountMeta::new(*drop, false),
+        AccountMeta::new(drop_buyer_info, false),
+    ]);
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+    Ok(())
+}
+
+async fn get_drop(context: &mut ProgramTestContext, drop: &Pubkey) -> DropAccount {
+    let account = get_account(context, drop).await;
+    DropAccount::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn drop_limit_per_address_across_candy_machines() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let bullistic_authority = Keypair::new();
+    let minter = Keypair::new();
+    let drop = Keypair::new();
+
+    let first_candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    let second_candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    initialize_drop(context, &drop, &bullistic_authority, 0, 1)
+        .await
+        .unwrap();
+
+    mint_with_drop(context, &first_candy_manager, &drop.pubkey())
+        .await
+        .unwrap();
+
+    // The buyer is under the limit of the second candy machine, but not of
+    // the drop.
+    let result = mint_with_drop(context, &second_candy_manager, &drop.pubkey()).await;
+    assert_tx_failed_with_error_code(result, CandyError::DropBuyLimitPerAddressExceeded);
+
+    assert_eq!(get_drop(context, &drop.pubkey()).await.items_minted, 1);
+}
+
+#[tokio::test]
+async fn drop_supply_cap_across_candy_machines() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let bullistic_authority = Keypair::new();
+    let minter = Keypair::new();
+    let drop = Keypair::new();
+
+    let first_candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    let second_candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    initialize_drop(context, &drop, &bullistic_authority, 2, 0)
+        .await
+        .unwrap();
+
+    mint_with_drop(context, &first_candy_manager, &drop.pubkey())
+        .await
+        .unwrap();
+    mint_with_drop(context, &second_candy_manager, &drop.pubkey())
+        .await
+        .unwrap();
+
+    let result = mint_with_drop(context, &first_candy_manager, &drop.pubkey()).await;
+    assert_tx_failed_with_error_code(result, CandyError::DropSoldOut);
+}
+
+#[tokio::test]
+async fn fail_mint_without_drop_accounts() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let bullistic_authority = Keypair::new();
+    let minter = Keypair::new();
+    let drop = Keypair::new();
+
+    let mut candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    initialize_drop(context, &drop, &bullistic_authority, 0, 0)
+        .await
+        .unwrap();
+
+    let result = candy_manager.mint_nft(context, None, None).await;
+    assert_tx_failed_with_error_code(result, CandyError::MissingDropAccount);
+}
+
+#[tokio::test]
+async fn fail_mint_with_drop_of_other_authority() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let bullistic_authority = Keypair::new();
+    let minter = Keypair::new();
+    let drop = Keypair::new();
+    let other_authority = new_funded_keypair(context, sol(1)).await;
+
+    let candy_manager =
+        create_drop_candy_machine(context, &bullistic_authority, &minter, Some(drop.pubkey()))
+            .await;
+    initialize_drop(context, &drop, &other_authority, 0, 0)
+        .await
+        .unwrap();
+
+    let result = mint_with_drop(context, &candy_manager, &drop.pubkey()).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidDropAccount);
+}
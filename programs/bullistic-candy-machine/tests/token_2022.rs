@@ -0,0 +1,117 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+
+use crate::core::helpers::{assert_account_empty, get_token_balance};
+use crate::utils::{
+    candy_machine_program_test, CandyConfigBuilder, CandyManagerBuilder, FreezeConfig,
+    Token2022Config,
+};
+
+pub mod core;
+pub mod utils;
+
+#[tokio::test]
+async fn mint_with_token_2022_treasury_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_token_2022(Token2022Config::new(None))
+        .build(context)
+        .await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager).set_price(1).build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    candy_manager
+        .mint_and_assert_successful(context, Some(1), true, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn mint_with_token_2022_transfer_fee_treasury_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    // 10% transfer fee, capped at 100 tokens.
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_token_2022(Token2022Config::new(Some((1_000, 100))))
+        .build(context)
+        .await;
+
+    let price = 5;
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_price(price)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    let treasury_start = get_token_balance(context, &candy_manager.token_info.auth_account).await;
+    let minter_start = get_token_balance(context, &candy_manager.token_info.minter_account).await;
+
+    candy_manager.mint_nft(context, None, None).await.unwrap();
+
+    let treasury_end = get_token_balance(context, &candy_manager.token_info.auth_account).await;
+    let minter_end = get_token_balance(context, &candy_manager.token_info.minter_account).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        price,
+        "Treasury should receive the full price after the transfer fee"
+    );
+    assert_eq!(
+        minter_start - minter_end,
+        price + 1,
+        "Minter should pay the price plus the transfer fee"
+    );
+}
+
+#[tokio::test]
+async fn freeze_and_unlock_funds_with_token_2022_treasury_mint() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let mut candy_manager = CandyManagerBuilder::new()
+        .set_token_2022(Token2022Config::new(None))
+        .set_freeze(FreezeConfig::new(true, 60 * 60))
+        .build(context)
+        .await;
+
+    let price = 5;
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_price(price)
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+    candy_manager.set_freeze(context).await.unwrap();
+
+    let new_nft = candy_manager
+        .mint_and_assert_successful(context, Some(price), true, None)
+        .await
+        .unwrap();
+    candy_manager.assert_frozen(context, &new_nft).await;
+
+    // The price is escrowed in the freeze PDA's Token-2022 account.
+    let freeze_ata = candy_manager
+        .freeze_info
+        .find_freeze_ata(&candy_manager.token_info.mint);
+    assert_eq!(get_token_balance(context, &freeze_ata).await, price);
+
+    candy_manager.remove_freeze(context).await.unwrap();
+    candy_manager
+        .thaw_nft(context, &new_nft, &new_nft.owner)
+        .await
+        .unwrap();
+    candy_manager.assert_thawed(context, &new_nft, true).await;
+
+    let treasury_start = get_token_balance(context, &candy_manager.token_info.auth_account).await;
+    candy_manager.unlock_funds(context).await.unwrap();
+    let treasury_end = get_token_balance(context, &candy_manager.token_info.auth_account).await;
+    assert_eq!(treasury_end - treasury_start, price);
+
+    // Nothing is frozen anymore, so the freeze PDA and its escrow are closed.
+    assert_account_empty(context, &freeze_ata).await;
+    assert_account_empty(context, &candy_manager.freeze_info.pda).await;
+}
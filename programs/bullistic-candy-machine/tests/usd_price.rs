@@ -0,0 +1,199 @@
+#![cfg(feature = "test-bpf")]
+#![allow(dead_code)]
+
+use anchor_client::solana_sdk::transaction::Transaction;
+use bullistic_candy_machine::{CandyError, MintPhase, UsdPriceSettings};
+use solana_program::{clock::Clock, instruction::AccountMeta, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::signature::Keypair;
+use solana_sdk::account::{AccountSharedData, WritableAccount};
+use solana_sdk::signer::Signer;
+
+use crate::core::helpers::{get_balance, prepare_nft, update_blockhash};
+use crate::utils::helpers::{assert_tx_failed_with_error_code, find_candy_creator, sol};
+use crate::utils::{
+    candy_machine_program_test, mint_nft_ix, CandyConfigBuilder, CandyManager, CandyManagerBuilder,
+    SolanaProgramTestResult,
+};
+
+pub mod core;
+pub mod utils;
+
+// $20, in millionths of a dollar.
+const USD_PRICE: u64 = 20_000_000;
+// $25 per SOL, with the usual Pyth exponent.
+const SOL_PRICE: i64 = 2_500_000_000;
+const SOL_PRICE_EXPO: i32 = -8;
+const MAX_PRICE_AGE_SECONDS: u32 = 60;
+
+/// Write a mock Pyth price account with the given aggregate price.
+fn set_price_feed(
+    context: &mut ProgramTestContext,
+    price_feed: &Pubkey,
+    price: i64,
+    conf: u64,
+    publish_time: i64,
+) {
+    let mut data = vec![0u8; 240];
+    data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes()); // magic
+    data[4..8].copy_from_slice(&2u32.to_le_bytes()); // version
+    data[8..12].copy_from_slice(&3u32.to_le_bytes()); // price account
+    data[20..24].copy_from_slice(&SOL_PRICE_EXPO.to_le_bytes());
+    data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+    data[208..216].copy_from_slice(&price.to_le_bytes());
+    data[216..224].copy_from_slice(&conf.to_le_bytes());
+    data[224..228].copy_from_slice(&1u32.to_le_bytes()); // trading
+
+    context.set_account(
+        price_feed,
+        &AccountSharedData::create(sol(1), data, Pubkey::new_unique(), false, 1),
+    );
+}
+
+async fn get_unix_timestamp(context: &mut ProgramTestContext) -> i64 {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn create_usd_priced_candy_machine(
+    context: &mut ProgramTestContext,
+    price_feed: &Pubkey,
+) -> CandyManager {
+    let mut candy_manager = CandyManagerBuilder::default(context).await;
+
+    let candy_data = CandyConfigBuilder::new(&candy_manager)
+        .set_items_available(3)
+        .set_price(USD_PRICE)
+        .enable_mint_phase(MintPhase::Public)
+        .set_usd_price_settings(UsdPriceSettings {
+            price_feed: *price_feed,
+            max_price_age_seconds: MAX_PRICE_AGE_SECONDS,
+            max_confidence_basis_points: 100,
+        })
+        .build();
+    candy_manager.create(context, candy_data).await.unwrap();
+    candy_manager.fill_config_lines(context).await.unwrap();
+
+    candy_manager
+}
+
+async fn mint_with_price_feed(
+    context: &mut ProgramTestContext,
+    candy_manager: &CandyManager,
+    price_feed: &Pubkey,
+    max_price: u64,
+) -> SolanaProgramTestResult {
+    let nft_info = prepare_nft(&candy_manager.minter).await;
+    let (candy_machine_creator, creator_bump) =
+        find_candy_creator(&candy_manager.candy_machine.pubkey());
+
+    let mut ix = mint_nft_ix(
+        &candy_manager.candy_machine.pubkey(),
+        &candy_machine_creator,
+        creator_bump,
+        &candy_manager.treasury_wallet,
+        &candy_manager.creator_authority.pubkey(),
+        &candy_manager.minter,
+        &nft_info,
+        candy_manager.token_info.clone(),
+        candy_manager.spl_token_allowlist_info.clone(),
+        candy_manager.collection_info.clone(),
+        candy_manager.freeze_info.clone(),
+        false,
+        None,
+        max_price,
+    );
+    ix[1]
+        .accounts
+        .push(AccountMeta::new_readonly(*price_feed, false));
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        ix.as_slice(),
+        Some(&candy_manager.minter.pubkey()),
+        &[&candy_manager.minter, &nft_info.mint],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+#[tokio::test]
+async fn mint_with_usd_price() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let price_feed = Pubkey::new_unique();
+    let candy_manager = create_usd_priced_candy_machine(context, &price_feed).await;
+
+    let now = get_unix_timestamp(context).await;
+    set_price_feed(
+        context,
+        &price_feed,
+        SOL_PRICE,
+        SOL_PRICE as u64 / 1000,
+        now,
+    );
+
+    // $20 at $25 per SOL.
+    let expected_lamports = sol(1) * 4 / 5;
+
+    // The buyer's slippage cap is below the converted price.
+    let result =
+        mint_with_price_feed(context, &candy_manager, &price_feed, expected_lamports - 1).await;
+    assert_tx_failed_with_error_code(result, CandyError::InvalidMintPrice);
+
+    let treasury_start = get_balance(context, &candy_manager.treasury_wallet).await;
+    mint_with_price_feed(context, &candy_manager, &price_feed, sol(1))
+        .await
+        .unwrap();
+    let treasury_end = get_balance(context, &candy_manager.treasury_wallet).await;
+
+    assert_eq!(
+        treasury_end - treasury_start,
+        expected_lamports,
+        "Treasury should receive the USD price converted to lamports"
+    );
+}
+
+#[tokio::test]
+async fn fail_mint_with_stale_price_feed() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let price_feed = Pubkey::new_unique();
+    let candy_manager = create_usd_priced_candy_machine(context, &price_feed).await;
+
+    let now = get_unix_timestamp(context).await;
+    set_price_feed(
+        context,
+        &price_feed,
+        SOL_PRICE,
+        0,
+        now - MAX_PRICE_AGE_SECONDS as i64 - 60,
+    );
+
+    let result = mint_with_price_feed(context, &candy_manager, &price_feed, sol(1)).await;
+    assert_tx_failed_with_error_code(result, CandyError::StalePriceFeed);
+}
+
+#[tokio::test]
+async fn fail_mint_with_wide_price_feed_confidence() {
+    let mut context = candy_machine_program_test().start_with_context().await;
+    let context = &mut context;
+    let price_feed = Pubkey::new_unique();
+    let candy_manager = create_usd_priced_candy_machine(context, &price_feed).await;
+
+    let now = get_unix_timestamp(context).await;
+    // A 5% confidence interval, above the 1% limit.
+    set_price_feed(context, &price_feed, SOL_PRICE, SOL_PRICE as u64 / 20, now);
+
+    let result = mint_with_price_feed(context, &candy_manager, &price_feed, sol(1)).await;
+    assert_tx_failed_with_error_code(result, CandyError::PriceFeedConfidenceTooWide);
+}
@@ -4,7 +4,9 @@ use solana_program::native_token::LAMPORTS_PER_SOL;
 use solana_program::pubkey::Pubkey;
 
 use bullistic_candy_machine::{
-    CandyMachineData, Creator, HiddenSettings, MintPhase, SplTokenAllowlistSettings,
+    BotTaxSettings, BurnToMintSettings, CandyMachineData, Creator, HiddenSettings, MintOrder,
+    MintPhase, MintRateLimitSettings, OpenEditionSettings, ProgrammableNftSettings,
+    SplTokenAllowlistSettings, UsdPriceSettings,
 };
 use solana_sdk::signer::Signer;
 
@@ -23,8 +25,21 @@ pub struct CandyConfigBuilder {
     public_sale_end_time: i64,
     is_mutable: bool,
     hidden_settings: Option<HiddenSettings>,
+    open_edition_settings: Option<OpenEditionSettings>,
     spl_token_allowlist_settings: Option<SplTokenAllowlistSettings>,
     bot_protection_enabled: bool,
+    bot_tax_settings: Option<BotTaxSettings>,
+    on_chain_reveal_enabled: bool,
+    voucher_signer: Option<Pubkey>,
+    burn_to_mint_settings: Option<BurnToMintSettings>,
+    referral_fee_basis_points: u16,
+    creator_overrides_enabled: bool,
+    usd_price_settings: Option<UsdPriceSettings>,
+    mint_rate_limit_settings: Option<MintRateLimitSettings>,
+    min_items_to_succeed: Option<u64>,
+    programmable_nft_settings: Option<ProgrammableNftSettings>,
+    drop: Option<Pubkey>,
+    mint_order: MintOrder,
     limit_per_address: u16,
     sequential_mint_order_enabled: bool,
     items_available: u64,
@@ -55,8 +70,21 @@ impl CandyConfigBuilder {
             public_sale_end_time: default_public_sale_end_time,
             is_mutable: true,
             hidden_settings: None,
+            open_edition_settings: None,
             spl_token_allowlist_settings,
             bot_protection_enabled: false,
+            bot_tax_settings: None,
+            on_chain_reveal_enabled: false,
+            voucher_signer: None,
+            burn_to_mint_settings: None,
+            referral_fee_basis_points: 0,
+            creator_overrides_enabled: false,
+            usd_price_settings: None,
+            mint_rate_limit_settings: None,
+            min_items_to_succeed: None,
+            programmable_nft_settings: None,
+            drop: None,
+            mint_order: MintOrder::Random,
             limit_per_address: 0,
             items_available: DEFAULT_ITEMS_AVAILABLE,
             sequential_mint_order_enabled: false,
@@ -108,6 +136,95 @@ impl CandyConfigBuilder {
         self
     }
 
+    pub fn set_open_edition_settings(
+        mut self,
+        open_edition_settings: OpenEditionSettings,
+    ) -> CandyConfigBuilder {
+        self.open_edition_settings = Some(open_edition_settings);
+        self
+    }
+
+    pub fn set_bot_tax_settings(mut self, bot_tax_settings: BotTaxSettings) -> CandyConfigBuilder {
+        self.bot_tax_settings = Some(bot_tax_settings);
+        self
+    }
+
+    pub fn set_on_chain_reveal_enabled(
+        mut self,
+        on_chain_reveal_enabled: bool,
+    ) -> CandyConfigBuilder {
+        self.on_chain_reveal_enabled = on_chain_reveal_enabled;
+        self
+    }
+
+    pub fn set_voucher_signer(mut self, voucher_signer: Pubkey) -> CandyConfigBuilder {
+        self.voucher_signer = Some(voucher_signer);
+        self
+    }
+
+    pub fn set_burn_to_mint_settings(
+        mut self,
+        burn_to_mint_settings: BurnToMintSettings,
+    ) -> CandyConfigBuilder {
+        self.burn_to_mint_settings = Some(burn_to_mint_settings);
+        self
+    }
+
+    pub fn set_referral_fee_basis_points(
+        mut self,
+        referral_fee_basis_points: u16,
+    ) -> CandyConfigBuilder {
+        self.referral_fee_basis_points = referral_fee_basis_points;
+        self
+    }
+
+    pub fn set_creator_overrides_enabled(
+        mut self,
+        creator_overrides_enabled: bool,
+    ) -> CandyConfigBuilder {
+        self.creator_overrides_enabled = creator_overrides_enabled;
+        self
+    }
+
+    pub fn set_usd_price_settings(
+        mut self,
+        usd_price_settings: UsdPriceSettings,
+    ) -> CandyConfigBuilder {
+        self.usd_price_settings = Some(usd_price_settings);
+        self
+    }
+
+    pub fn set_mint_rate_limit_settings(
+        mut self,
+        mint_rate_limit_settings: MintRateLimitSettings,
+    ) -> CandyConfigBuilder {
+        self.mint_rate_limit_settings = Some(mint_rate_limit_settings);
+        self
+    }
+
+    pub fn set_min_items_to_succeed(mut self, min_items_to_succeed: u64) -> CandyConfigBuilder {
+        self.min_items_to_succeed = Some(min_items_to_succeed);
+        self
+    }
+
+    pub fn set_programmable_nft_settings(
+        mut self,
+        programmable_nft_settings: ProgrammableNftSettings,
+    ) -> CandyConfigBuilder {
+        self.programmable_nft_settings = Some(programmable_nft_settings);
+        self
+    }
+
+    pub fn set_drop(mut self, drop: Pubkey) -> CandyConfigBuilder {
+        self.drop = Some(drop);
+        self
+    }
+
+    pub fn set_mint_order(mut self, mint_order: MintOrder) -> CandyConfigBuilder {
+        self.mint_order = mint_order;
+        self
+    }
+
     pub fn set_spl_token_allowlist_settings(
         mut self,
         spl_token_allowlist_settings: SplTokenAllowlistSettings,
@@ -214,6 +331,19 @@ impl CandyConfigBuilder {
             limit_per_address: self.limit_per_address,
             merkle_allowlist_root_list: vec![],
             sequential_mint_order_enabled: self.sequential_mint_order_enabled,
+            open_edition_settings: self.open_edition_settings,
+            bot_tax_settings: self.bot_tax_settings,
+            on_chain_reveal_enabled: self.on_chain_reveal_enabled,
+            voucher_signer: self.voucher_signer,
+            burn_to_mint_settings: self.burn_to_mint_settings,
+            referral_fee_basis_points: self.referral_fee_basis_points,
+            creator_overrides_enabled: self.creator_overrides_enabled,
+            usd_price_settings: self.usd_price_settings,
+            mint_rate_limit_settings: self.mint_rate_limit_settings,
+            min_items_to_succeed: self.min_items_to_succeed,
+            programmable_nft_settings: self.programmable_nft_settings,
+            drop: self.drop,
+            mint_order: self.mint_order,
         }
     }
 }
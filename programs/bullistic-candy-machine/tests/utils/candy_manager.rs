@@ -14,7 +14,9 @@ use solana_sdk::{
     signature::{Keypair, Signer},
     transport,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
 use spl_token::state::AccountState;
 
 use bullistic_candy_machine::constants::{FREEZE_FEATURE_INDEX, FREEZE_LOCK_FEATURE_INDEX};
@@ -25,7 +27,9 @@ use bullistic_candy_machine::{
     SplTokenAllowlistMode::{BurnEveryTime, NeverBurn},
 };
 
-use crate::utils::{remove_freeze, set_freeze, thaw_nft, unlock_funds};
+use crate::utils::{
+    close_unsold_inventory, remove_freeze, set_freeze, thaw_nft, thaw_nfts, unlock_funds,
+};
 use crate::{
     core::helpers::create_associated_token_account, utils::helpers::find_buyer_info_account_pda,
 };
@@ -36,9 +40,10 @@ use crate::{
 use crate::{
     core::{
         helpers::{
-            airdrop, assert_account_empty, clone_keypair, create_mint, get_account,
+            airdrop, assert_account_empty, clone_keypair, create_mint,
+            create_token_2022_associated_token_account, create_token_2022_mint, get_account,
             get_account_if_exists, get_balance, get_token_account, get_token_balance,
-            mint_to_wallets, prepare_nft,
+            mint_to_wallets, mint_token_2022_to_wallets, prepare_nft,
         },
         MasterEditionManager, MetadataManager,
     },
@@ -64,6 +69,7 @@ pub struct CandyManagerBuilder {
     bot_protection_enabled: bool,
     collection: bool,
     token: bool,
+    token_2022: Option<Token2022Config>,
     freeze: Option<FreezeConfig>,
     spl_token_allowlist_config: Option<SplTokenAllowlistConfig>,
     sol_airdrop_size_for_minter: u64,
@@ -79,6 +85,7 @@ impl CandyManagerBuilder {
             bot_protection_enabled: false,
             collection: false,
             token: false,
+            token_2022: None,
             freeze: None,
             spl_token_allowlist_config: None,
             sol_airdrop_size_for_minter: DEFAULT_SOL_AIRDROP_SIZE,
@@ -127,6 +134,13 @@ impl CandyManagerBuilder {
         self
     }
 
+    /// Pays with a Token-2022 treasury mint instead of an SPL token mint.
+    pub fn set_token_2022(mut self, token_2022: Token2022Config) -> CandyManagerBuilder {
+        self.token = true;
+        self.token_2022 = Some(token_2022);
+        self
+    }
+
     pub fn set_freeze(mut self, freeze: FreezeConfig) -> CandyManagerBuilder {
         self.freeze = Some(freeze);
         self
@@ -153,6 +167,7 @@ impl CandyManagerBuilder {
             context,
             self.collection,
             self.token,
+            self.token_2022,
             self.freeze,
             self.spl_token_allowlist_config,
             self.candy_machine,
@@ -296,6 +311,7 @@ pub struct TokenInfo {
     pub authority: Keypair,
     pub auth_account: Pubkey,
     pub minter_account: Pubkey,
+    pub token_program: Pubkey,
 }
 
 impl TokenInfo {
@@ -306,6 +322,7 @@ impl TokenInfo {
         authority: Keypair,
         auth_account: Pubkey,
         minter_account: Pubkey,
+        token_program: Pubkey,
     ) -> Self {
         TokenInfo {
             set,
@@ -313,6 +330,7 @@ impl TokenInfo {
             authority,
             auth_account,
             minter_account,
+            token_program,
         }
     }
 
@@ -342,8 +360,51 @@ impl TokenInfo {
             authority: clone_keypair(authority),
             auth_account: atas[0],
             minter_account: atas[1],
+            token_program: spl_token::id(),
         }
     }
+
+    pub async fn init_token_2022(
+        context: &mut ProgramTestContext,
+        set: bool,
+        token_2022: Token2022Config,
+        authority: &Keypair,
+        authority_alloc: (Pubkey, u64),
+        minter: (Pubkey, u64),
+    ) -> Self {
+        let mint = create_token_2022_mint(context, &authority.pubkey(), 0, token_2022.transfer_fee)
+            .await
+            .unwrap();
+        let atas = mint_token_2022_to_wallets(
+            context,
+            &mint.pubkey(),
+            authority,
+            vec![authority_alloc, minter],
+        )
+        .await
+        .unwrap();
+
+        TokenInfo {
+            set,
+            mint: mint.pubkey(),
+            authority: clone_keypair(authority),
+            auth_account: atas[0],
+            minter_account: atas[1],
+            token_program: spl_token_2022::id(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Token2022Config {
+    // Transfer fee basis points and maximum fee, if the mint should charge one.
+    pub transfer_fee: Option<(u16, u64)>,
+}
+
+impl Token2022Config {
+    pub fn new(transfer_fee: Option<(u16, u64)>) -> Self {
+        Self { transfer_fee }
+    }
 }
 
 impl Clone for TokenInfo {
@@ -354,6 +415,7 @@ impl Clone for TokenInfo {
             authority: clone_keypair(&self.authority),
             auth_account: self.auth_account,
             minter_account: self.minter_account,
+            token_program: self.token_program,
         }
     }
 }
@@ -362,20 +424,30 @@ impl Clone for TokenInfo {
 pub struct FreezeInfo {
     pub freeze_time: i64,
     pub set: bool,
+    pub rolling: bool,
     pub ata: Pubkey,
     pub pda: Pubkey,
+    pub token_program: Pubkey,
 }
 
 impl FreezeInfo {
-    pub fn new(set: bool, candy_machine: &Pubkey, freeze_time: i64, mint: Pubkey) -> Self {
+    pub fn new(
+        set: bool,
+        candy_machine: &Pubkey,
+        freeze_time: i64,
+        mint: Pubkey,
+        token_program: Pubkey,
+    ) -> Self {
         let seeds: &[&[u8]] = &[FreezePda::PREFIX.as_bytes(), candy_machine.as_ref()];
         let pda = Pubkey::find_program_address(seeds, &bullistic_candy_machine::ID).0;
-        let freeze_ata = get_associated_token_address(&pda, &mint);
+        let freeze_ata = get_associated_token_address_with_program_id(&pda, &mint, &token_program);
         FreezeInfo {
             set,
+            rolling: false,
             pda,
             freeze_time,
             ata: freeze_ata,
+            token_program,
         }
     }
 
@@ -385,16 +457,23 @@ impl FreezeInfo {
         candy_machine: &Pubkey,
         freeze_time: i64,
         mint: Pubkey,
+        token_program: Pubkey,
     ) -> Self {
-        let freeze_info = FreezeInfo::new(set, candy_machine, freeze_time, mint);
-        create_associated_token_account(context, &freeze_info.pda, &mint)
-            .await
-            .unwrap();
+        let freeze_info = FreezeInfo::new(set, candy_machine, freeze_time, mint, token_program);
+        if cmp_pubkeys(&token_program, &spl_token::id()) {
+            create_associated_token_account(context, &freeze_info.pda, &mint)
+                .await
+                .unwrap();
+        } else {
+            create_token_2022_associated_token_account(context, &freeze_info.pda, &mint)
+                .await
+                .unwrap();
+        }
         freeze_info
     }
 
     pub fn find_freeze_ata(&self, treasury_mint: &Pubkey) -> Pubkey {
-        get_associated_token_address(&self.pda, treasury_mint)
+        get_associated_token_address_with_program_id(&self.pda, treasury_mint, &self.token_program)
     }
 }
 
@@ -402,11 +481,24 @@ impl FreezeInfo {
 pub struct FreezeConfig {
     pub set: bool,
     pub freeze_time: i64,
+    pub rolling: bool,
 }
 
 impl FreezeConfig {
     pub fn new(set: bool, freeze_time: i64) -> Self {
-        Self { set, freeze_time }
+        Self {
+            set,
+            freeze_time,
+            rolling: false,
+        }
+    }
+
+    pub fn new_rolling(freeze_time: i64) -> Self {
+        Self {
+            set: true,
+            freeze_time,
+            rolling: true,
+        }
     }
 }
 
@@ -549,6 +641,7 @@ impl CandyManager {
         context: &mut ProgramTestContext,
         collection: bool,
         token: bool,
+        token_2022: Option<Token2022Config>,
         freeze: Option<FreezeConfig>,
         spl_token_allowlist_config: Option<SplTokenAllowlistConfig>,
         candy_machine: Option<Keypair>,
@@ -597,28 +690,54 @@ impl CandyManager {
         )
         .await;
 
-        let token_info = TokenInfo::init(
-            context,
-            token,
-            &bullistic_authority,
-            (bullistic_authority.pubkey(), 10),
-            (minter.pubkey(), 1),
-        )
-        .await;
+        let token_info = match token_2022 {
+            Some(token_2022) => {
+                TokenInfo::init_token_2022(
+                    context,
+                    token,
+                    token_2022,
+                    &bullistic_authority,
+                    (bullistic_authority.pubkey(), 10),
+                    (minter.pubkey(), 10),
+                )
+                .await
+            }
+            None => {
+                TokenInfo::init(
+                    context,
+                    token,
+                    &bullistic_authority,
+                    (bullistic_authority.pubkey(), 10),
+                    (minter.pubkey(), 1),
+                )
+                .await
+            }
+        };
 
         let freeze_info = match freeze {
             Some(config) => {
-                FreezeInfo::init(
+                let mut freeze_info = FreezeInfo::init(
                     context,
                     config.set,
                     &candy_machine.pubkey(),
                     config.freeze_time,
                     token_info.mint,
+                    token_info.token_program,
                 )
-                .await
+                .await;
+                freeze_info.rolling = config.rolling;
+                freeze_info
             }
             None => {
-                FreezeInfo::init(context, false, &candy_machine.pubkey(), 0, token_info.mint).await
+                FreezeInfo::init(
+                    context,
+                    false,
+                    &candy_machine.pubkey(),
+                    0,
+                    token_info.mint,
+                    token_info.token_program,
+                )
+                .await
             }
         };
 
@@ -896,6 +1015,7 @@ impl CandyManager {
             context,
             &self.candy_machine.pubkey(),
             &self.bullistic_authority,
+            None,
             roots_to_append,
         )
         .await?;
@@ -912,6 +1032,7 @@ impl CandyManager {
             context,
             &self.candy_machine.pubkey(),
             &self.bullistic_authority,
+            None,
         )
         .await?;
         logger.end();
@@ -953,6 +1074,21 @@ impl CandyManager {
         Ok(())
     }
 
+    pub async fn close_unsold_inventory(
+        &mut self,
+        context: &mut ProgramTestContext,
+    ) -> SolanaProgramTestResult {
+        let logger = CandyTestLogger::new_start("Close unsold inventory");
+        close_unsold_inventory(
+            context,
+            &self.candy_machine.pubkey(),
+            &self.bullistic_authority,
+        )
+        .await?;
+        logger.end();
+        Ok(())
+    }
+
     pub async fn thaw_nft(
         &mut self,
         context: &mut ProgramTestContext,
@@ -972,6 +1108,25 @@ impl CandyManager {
         Ok(())
     }
 
+    pub async fn thaw_nfts(
+        &mut self,
+        context: &mut ProgramTestContext,
+        nft_infos: &[&MasterEditionManager],
+        signer: &Keypair,
+    ) -> SolanaProgramTestResult {
+        let logger = CandyTestLogger::new_start("Thaw NFTs");
+        thaw_nfts(
+            context,
+            &self.candy_machine.pubkey(),
+            signer,
+            &self.freeze_info,
+            nft_infos,
+        )
+        .await?;
+        logger.end();
+        Ok(())
+    }
+
     pub async fn unlock_funds(
         &mut self,
         context: &mut ProgramTestContext,
@@ -1051,7 +1206,7 @@ impl CandyManager {
             !freeze.thaw_eligible(current_timestamp, &candy_start)
         } {
             if self.token_info.set {
-                get_associated_token_address(&self.freeze_info.pda, &self.token_info.mint)
+                self.freeze_info.find_freeze_ata(&self.token_info.mint)
             } else {
                 self.freeze_info.pda
             }
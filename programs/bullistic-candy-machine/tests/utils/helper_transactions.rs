@@ -13,27 +13,61 @@ use solana_sdk::{
 
 use bullistic_candy_machine::{
     constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE},
-    utils::cmp_pubkeys,
-    BuyerMerkleAllowlistProofData, CandyMachine, CandyMachineData, ConfigLine,
+    utils::{
+        cmp_pubkeys, find_compressed_tree_pda, find_creator_overrides_pda, find_freeze_record_pda,
+        find_multisig_pda, find_proposal_pda, find_referral_account_pda, find_refund_receipt_pda,
+        find_roles_pda,
+    },
+    BotSigner, BuyerMerkleAllowlistProofData, CandyMachine, CandyMachineData, ConfigLine,
+    CreatorOverride, ProposalAction, Role,
     SplTokenAllowlistMode::BurnEveryTime,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_account_compression::{
+    state::{
+        merkle_tree_get_size, ConcurrentMerkleTreeHeader, CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1,
+    },
+    Noop,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
 
 use crate::{
     core::{helpers::update_blockhash, MasterEditionManager},
     utils::{
         candy_manager::{CollectionInfo, SplTokenAllowlistInfo, TokenInfo},
-        helpers::{find_buyer_info_account_pda, get_bot_signer_keypair, make_config_lines},
+        helpers::{
+            find_buyer_info_account_pda, find_candy_creator, find_reveal_pda,
+            get_bot_signer_keypair, make_config_lines,
+        },
         FreezeInfo, SolanaProgramTestResult,
     },
 };
 
 pub fn candy_machine_program_test() -> ProgramTest {
-    let mut program = ProgramTest::new("bullistic_candy_machine", bullistic_candy_machine::id(), None);
+    let mut program = ProgramTest::new(
+        "bullistic_candy_machine",
+        bullistic_candy_machine::id(),
+        None,
+    );
     program.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
     program
 }
 
+/// Same as candy_machine_program_test, with the programs compressed NFTs are
+/// minted through.
+pub fn compressed_candy_machine_program_test() -> ProgramTest {
+    let mut program = candy_machine_program_test();
+    program.add_program("mpl_bubblegum", mpl_bubblegum::id(), None);
+    program.add_program(
+        "spl_account_compression",
+        spl_account_compression::id(),
+        None,
+    );
+    program.add_program("spl_noop", Noop::id(), None);
+    program
+}
+
 pub async fn initialize_candy_machine(
     context: &mut ProgramTestContext,
     candy_account: &Keypair,
@@ -44,7 +78,7 @@ pub async fn initialize_candy_machine(
     token_info: TokenInfo,
 ) -> SolanaProgramTestResult {
     let items_available = candy_data.items_available;
-    let candy_account_size = if candy_data.hidden_settings.is_some() {
+    let candy_account_size = if !candy_data.has_config_lines() {
         CONFIG_ARRAY_START
     } else {
         CONFIG_ARRAY_START
@@ -147,11 +181,13 @@ pub async fn append_merkle_allowlist_roots(
     context: &mut ProgramTestContext,
     candy_machine: &Pubkey,
     bullistic_authority: &Keypair,
+    roles_pda: Option<Pubkey>,
     roots_to_append: Vec<[u8; 32]>,
 ) -> SolanaProgramTestResult {
     let accounts = bullistic_candy_machine::accounts::AppendMerkleAllowlistRoots {
         bullistic_authority: bullistic_authority.pubkey(),
         candy_machine: *candy_machine,
+        roles_pda,
     }
     .to_account_metas(None);
 
@@ -183,10 +219,12 @@ pub async fn clear_merkle_allowlist_roots(
     context: &mut ProgramTestContext,
     candy_machine: &Pubkey,
     bullistic_authority: &Keypair,
+    roles_pda: Option<Pubkey>,
 ) -> SolanaProgramTestResult {
     let accounts = bullistic_candy_machine::accounts::ClearMerkleAllowlistRoots {
         bullistic_authority: bullistic_authority.pubkey(),
         candy_machine: *candy_machine,
+        roles_pda,
     }
     .to_account_metas(None);
 
@@ -217,12 +255,14 @@ pub async fn add_config_lines(
     context: &mut ProgramTestContext,
     candy_machine: &Pubkey,
     bullistic_authority: &Keypair,
+    roles_pda: Option<Pubkey>,
     index: u32,
     config_lines: Vec<ConfigLine>,
 ) -> SolanaProgramTestResult {
     let accounts = bullistic_candy_machine::accounts::AddConfigLines {
         candy_machine: *candy_machine,
         bullistic_authority: bullistic_authority.pubkey(),
+        roles_pda,
     }
     .to_account_metas(None);
 
@@ -276,6 +316,7 @@ pub async fn add_all_config_lines(
             context,
             candy_machine,
             bullistic_authority,
+            None,
             index,
             config_lines,
         )
@@ -290,6 +331,7 @@ pub async fn add_all_config_lines(
             context,
             candy_machine,
             bullistic_authority,
+            None,
             index,
             config_lines,
         )
@@ -404,6 +446,7 @@ pub async fn set_freeze(
 
     let data = bullistic_candy_machine::instruction::SetFreeze {
         freeze_time: freeze_info.freeze_time,
+        rolling: freeze_info.rolling,
     }
     .data();
     let set_ix = Instruction {
@@ -461,6 +504,174 @@ pub async fn remove_freeze(
         .map_err(|e| e.into())
 }
 
+pub fn find_tree_authority(merkle_tree: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[merkle_tree.as_ref()], &mpl_bubblegum::id()).0
+}
+
+pub async fn set_compressed_tree(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    merkle_tree: &Keypair,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> SolanaProgramTestResult {
+    let mut header =
+        ConcurrentMerkleTreeHeader::try_from_slice(&[0; CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1])
+            .unwrap();
+    header.initialize(max_depth, max_buffer_size, &Pubkey::default(), 0);
+    let space = CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + merkle_tree_get_size(&header).unwrap();
+    let rent = context.banks_client.get_rent().await?;
+    let create_tree_ix = system_instruction::create_account(
+        &bullistic_authority.pubkey(),
+        &merkle_tree.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &spl_account_compression::id(),
+    );
+
+    let accounts = bullistic_candy_machine::accounts::SetCompressedTree {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        compressed_tree: find_compressed_tree_pda(candy_machine).0,
+        tree_authority: find_tree_authority(&merkle_tree.pubkey()),
+        merkle_tree: merkle_tree.pubkey(),
+        log_wrapper: Noop::id(),
+        compression_program: spl_account_compression::id(),
+        bubblegum_program: mpl_bubblegum::id(),
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::SetCompressedTree {
+        max_depth,
+        max_buffer_size,
+    }
+    .data();
+    let set_tree_ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[create_tree_ix, set_tree_ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority, merkle_tree],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn close_unsold_inventory(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::CloseUnsoldInventory {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::CloseUnsoldInventory {}.data();
+    let close_ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[close_ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn close_buyer_info_account(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    buyer: &Pubkey,
+    rent_receiver: &Pubkey,
+    authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let (buyer_info_account, _) = find_buyer_info_account_pda(candy_machine, buyer);
+    let accounts = bullistic_candy_machine::accounts::CloseBuyerInfoAccount {
+        candy_machine: *candy_machine,
+        buyer_info_account,
+        buyer: *buyer,
+        rent_receiver: *rent_receiver,
+        authority: authority.pubkey(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::CloseBuyerInfoAccount {}.data();
+    let close_ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[close_ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn migrate_candy_machine(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::MigrateCandyMachine {
+        candy_machine: *candy_machine,
+        bullistic_authority: authority.pubkey(),
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::MigrateCandyMachine {}.data();
+    let migrate_ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[migrate_ix],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
 pub async fn thaw_nft(
     context: &mut ProgramTestContext,
     candy_machine: &Pubkey,
@@ -479,6 +690,14 @@ pub async fn thaw_nft(
         token_program: spl_token::ID,
         token_metadata_program: mpl_token_metadata::ID,
         system_program: system_program::id(),
+        freeze_record: freeze_info
+            .rolling
+            .then(|| find_freeze_record_pda(candy_machine, &nft_info.mint.pubkey()).0),
+        metadata: None,
+        token_record: None,
+        instruction_sysvar_account: None,
+        authorization_rules_program: None,
+        authorization_rules: None,
     }
     .to_account_metas(None);
 
@@ -503,6 +722,56 @@ pub async fn thaw_nft(
         .map_err(|e| e.into())
 }
 
+pub async fn thaw_nfts(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    signer: &Keypair,
+    freeze_info: &FreezeInfo,
+    nft_infos: &[&MasterEditionManager],
+) -> SolanaProgramTestResult {
+    let mut accounts = bullistic_candy_machine::accounts::ThawNFTs {
+        freeze_pda: freeze_info.pda,
+        candy_machine: *candy_machine,
+        payer: signer.pubkey(),
+        token_program: spl_token::ID,
+        token_metadata_program: mpl_token_metadata::ID,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+    for nft_info in nft_infos {
+        accounts.push(AccountMeta::new_readonly(nft_info.mint.pubkey(), false));
+        accounts.push(AccountMeta::new(nft_info.token_account, false));
+        accounts.push(AccountMeta::new_readonly(nft_info.edition_pubkey, false));
+        accounts.push(AccountMeta::new_readonly(nft_info.owner.pubkey(), false));
+        if freeze_info.rolling {
+            accounts.push(AccountMeta::new(
+                find_freeze_record_pda(candy_machine, &nft_info.mint.pubkey()).0,
+                false,
+            ));
+        }
+    }
+
+    let data = bullistic_candy_machine::instruction::ThawNfts {}.data();
+    let set_ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[set_ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
 pub async fn unlock_funds(
     context: &mut ProgramTestContext,
     candy_machine: &Pubkey,
@@ -518,12 +787,13 @@ pub async fn unlock_funds(
     }
     .to_account_metas(None);
     if token_info.set {
-        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+        accounts.push(AccountMeta::new_readonly(token_info.token_program, false));
         accounts.push(AccountMeta::new(
             freeze_info.find_freeze_ata(&token_info.mint),
             false,
         ));
         accounts.push(AccountMeta::new(token_info.auth_account, false));
+        accounts.push(AccountMeta::new_readonly(token_info.mint, false));
     }
 
     let data = bullistic_candy_machine::instruction::UnlockFunds {}.data();
@@ -547,6 +817,67 @@ pub async fn unlock_funds(
         .map_err(|e| e.into())
 }
 
+pub async fn refund_nft(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    freeze_info: &FreezeInfo,
+    token_info: &TokenInfo,
+    nft_info: &MasterEditionManager,
+) -> SolanaProgramTestResult {
+    let owner = &nft_info.owner;
+    let mut accounts = bullistic_candy_machine::accounts::RefundNFT {
+        candy_machine: *candy_machine,
+        freeze_pda: freeze_info.pda,
+        refund_receipt: find_refund_receipt_pda(candy_machine, &nft_info.mint.pubkey()).0,
+        token_account: nft_info.token_account,
+        owner: owner.pubkey(),
+        mint: nft_info.mint.pubkey(),
+        metadata: nft_info.metadata_pubkey,
+        edition: nft_info.edition_pubkey,
+        collection_metadata: None,
+        token_program: spl_token::ID,
+        token_metadata_program: mpl_token_metadata::ID,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+    if token_info.set {
+        accounts.push(AccountMeta::new_readonly(token_info.token_program, false));
+        accounts.push(AccountMeta::new(
+            freeze_info.find_freeze_ata(&token_info.mint),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &owner.pubkey(),
+                &token_info.mint,
+                &token_info.token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(token_info.mint, false));
+    }
+
+    let data = bullistic_candy_machine::instruction::RefundNft {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&owner.pubkey()),
+        &[owner],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn mint_nft_ix(
     candy_machine: &Pubkey,
@@ -563,24 +894,98 @@ pub fn mint_nft_ix(
     should_set_bot_signer_authority_as_signer: bool,
     buyer_merkle_allowlist_proof_data: Option<BuyerMerkleAllowlistProofData>,
     mint_price: u64,
+) -> Vec<Instruction> {
+    mint_nft_for_recipient_ix(
+        candy_machine,
+        candy_creator_pda,
+        creator_bump,
+        treasury_wallet,
+        creator_authority,
+        buyer,
+        None,
+        None,
+        new_nft,
+        token_info,
+        spl_token_allowlist_info,
+        collection_info,
+        freeze_info,
+        should_set_bot_signer_authority_as_signer,
+        buyer_merkle_allowlist_proof_data,
+        mint_price,
+    )
+}
+
+/// The deprecated instruction that set and verified the collection after
+/// mint_nft, before mint_nft took the collection accounts.
+pub fn set_collection_during_mint_ix(
+    candy_machine: &Pubkey,
+    metadata: &Pubkey,
+    buyer: &Pubkey,
+    creator_authority: &Pubkey,
+    collection_info: &CollectionInfo,
+) -> Instruction {
+    let accounts = bullistic_candy_machine::accounts::SetCollectionDuringMint {
+        candy_machine: *candy_machine,
+        metadata: *metadata,
+        buyer: *buyer,
+        collection_pda: collection_info.pda,
+        token_metadata_program: mpl_token_metadata::id(),
+        instruction_sysvar_account: sysvar::instructions::id(),
+        collection_mint: collection_info.mint.pubkey(),
+        collection_metadata: collection_info.metadata,
+        collection_master_edition: collection_info.master_edition,
+        creator_authority: *creator_authority,
+        collection_authority_record: collection_info.authority_record,
+    }
+    .to_account_metas(None);
+    let data = bullistic_candy_machine::instruction::SetCollectionDuringMint {}.data();
+
+    Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    }
+}
+
+/// Same as mint_nft_ix, with the NFT going to the recipient instead of the
+/// buyer if one is given, and the referral share going to the referrer.
+#[allow(clippy::too_many_arguments)]
+pub fn mint_nft_for_recipient_ix(
+    candy_machine: &Pubkey,
+    candy_creator_pda: &Pubkey,
+    creator_bump: u8,
+    treasury_wallet: &Pubkey,
+    creator_authority: &Pubkey,
+    buyer: &Keypair,
+    recipient: Option<Pubkey>,
+    referrer: Option<Pubkey>,
+    new_nft: &MasterEditionManager,
+    token_info: TokenInfo,
+    spl_token_allowlist_info: SplTokenAllowlistInfo,
+    collection_info: CollectionInfo,
+    freeze_info: FreezeInfo,
+    should_set_bot_signer_authority_as_signer: bool,
+    buyer_merkle_allowlist_proof_data: Option<BuyerMerkleAllowlistProofData>,
+    mint_price: u64,
 ) -> Vec<Instruction> {
     let metadata = new_nft.metadata_pubkey;
     let master_edition = new_nft.edition_pubkey;
     let mint = new_nft.mint.pubkey();
+    let nft_owner = recipient.unwrap_or_else(|| buyer.pubkey());
 
-    let (buyer_info_account, _) = find_buyer_info_account_pda(&candy_machine, &buyer.pubkey());
+    let (buyer_info_account, _) = find_buyer_info_account_pda(&candy_machine, &nft_owner);
 
-    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &mint);
+    let buyer_token_account = get_associated_token_address(&nft_owner, &mint);
 
     let mut accounts = bullistic_candy_machine::accounts::MintNFT {
         candy_machine: *candy_machine,
         candy_machine_creator: *candy_creator_pda,
         buyer: buyer.pubkey(),
         treasury_wallet: *treasury_wallet,
-        metadata,
-        mint,
+        metadata: Some(metadata),
+        mint: Some(mint),
         creator_authority: *creator_authority,
-        master_edition,
+        master_edition: Some(master_edition),
         token_metadata_program: mpl_token_metadata::id(),
         token_program: spl_token::id(),
         system_program: system_program::id(),
@@ -591,6 +996,10 @@ pub fn mint_nft_ix(
         buyer_info_account,
         buyer_token_account,
         ata_program: AssociatedToken::id(),
+        recipient,
+        referrer,
+        referral_account: referrer
+            .map(|referrer| find_referral_account_pda(candy_machine, &referrer).0),
     }
     .to_account_metas(None);
 
@@ -609,16 +1018,19 @@ pub fn mint_nft_ix(
         ));
         if spl_token_allowlist_info.spl_token_allowlist_config.burn == BurnEveryTime {
             accounts.push(AccountMeta::new(spl_token_allowlist_info.mint, false));
+            accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
         }
     }
 
     if token_info.set {
         accounts.push(AccountMeta::new(token_info.minter_account, false));
+        accounts.push(AccountMeta::new_readonly(token_info.mint, false));
+        accounts.push(AccountMeta::new_readonly(token_info.token_program, false));
     }
 
     if freeze_info.set {
         accounts.push(AccountMeta::new(freeze_info.pda, false));
-        accounts.push(AccountMeta::new(new_nft.token_account, false));
+        accounts.push(AccountMeta::new(buyer_token_account, false));
         if token_info.set {
             accounts.push(AccountMeta::new(
                 freeze_info.find_freeze_ata(&token_info.mint),
@@ -627,11 +1039,35 @@ pub fn mint_nft_ix(
         }
     }
 
-    let (_, buyer_info_account_bump) = find_buyer_info_account_pda(&candy_machine, &buyer.pubkey());
-    let data = bullistic_candy_machine::instruction::MintNft {
-        creator_bump,
-        buyer_info_account_bump,
-        buyer_merkle_allowlist_proof_data,
+    if collection_info.set {
+        accounts.push(AccountMeta::new(collection_info.pda, false));
+        accounts.push(AccountMeta::new_readonly(
+            collection_info.mint.pubkey(),
+            false,
+        ));
+        accounts.push(AccountMeta::new(collection_info.metadata, false));
+        accounts.push(AccountMeta::new_readonly(
+            collection_info.master_edition,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            collection_info.authority_record,
+            false,
+        ));
+    }
+
+    if freeze_info.set && freeze_info.rolling {
+        accounts.push(AccountMeta::new(
+            find_freeze_record_pda(candy_machine, &mint).0,
+            false,
+        ));
+    }
+
+    let (_, buyer_info_account_bump) = find_buyer_info_account_pda(&candy_machine, &nft_owner);
+    let data = bullistic_candy_machine::instruction::MintNft {
+        creator_bump,
+        buyer_info_account_bump,
+        buyer_merkle_allowlist_proof_data,
         expected_price: mint_price,
     }
     .data();
@@ -650,29 +1086,6 @@ pub fn mint_nft_ix(
 
     instructions.push(mint_ix);
 
-    if collection_info.set {
-        let accounts = bullistic_candy_machine::accounts::SetCollectionDuringMint {
-            candy_machine: *candy_machine,
-            metadata,
-            buyer: buyer.pubkey(),
-            collection_pda: collection_info.pda,
-            token_metadata_program: mpl_token_metadata::id(),
-            instruction_sysvar_account: sysvar::instructions::id(),
-            collection_mint: collection_info.mint.pubkey(),
-            collection_metadata: collection_info.metadata,
-            collection_master_edition: collection_info.master_edition,
-            creator_authority: *creator_authority,
-            collection_authority_record: collection_info.authority_record,
-        }
-        .to_account_metas(None);
-        let data = bullistic_candy_machine::instruction::SetCollectionDuringMint {}.data();
-        let set_ix = Instruction {
-            program_id: bullistic_candy_machine::id(),
-            data,
-            accounts,
-        };
-        instructions.push(set_ix)
-    }
     instructions
 }
 
@@ -730,3 +1143,601 @@ pub async fn mint_nft(
         .await
         .map_err(|e| e.into())
 }
+
+pub async fn initialize_reveal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::InitializeReveal {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        reveal_pda: find_reveal_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::InitializeReveal {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn add_reveal_config_lines(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    index: u32,
+    config_lines: Vec<ConfigLine>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::AddRevealConfigLines {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        reveal_pda: find_reveal_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::AddRevealConfigLines {
+        index,
+        config_lines,
+    }
+    .data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn reveal_nft(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    payer: &Keypair,
+    metadata: &Pubkey,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::RevealNft {
+        candy_machine: *candy_machine,
+        candy_machine_creator: find_candy_creator(candy_machine).0,
+        reveal_pda: find_reveal_pda(candy_machine).0,
+        metadata: *metadata,
+        token_metadata_program: mpl_token_metadata::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::RevealNft {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn claim_referral_earnings(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    referrer: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::ClaimReferralEarnings {
+        referral_account: find_referral_account_pda(candy_machine, &referrer.pubkey()).0,
+        referrer: referrer.pubkey(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::ClaimReferralEarnings {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&referrer.pubkey()),
+        &[referrer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn initialize_creator_overrides(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::InitializeCreatorOverrides {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        creator_overrides_pda: find_creator_overrides_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::InitializeCreatorOverrides {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_creator_overrides(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    index: u32,
+    creator_overrides: Vec<Option<CreatorOverride>>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::SetCreatorOverrides {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        creator_overrides_pda: find_creator_overrides_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::SetCreatorOverrides {
+        index,
+        creator_overrides,
+    }
+    .data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn initialize_drop(
+    context: &mut ProgramTestContext,
+    drop: &Keypair,
+    authority: &Keypair,
+    supply_cap: u64,
+    limit_per_address: u16,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::InitializeDrop {
+        drop: drop.pubkey(),
+        authority: authority.pubkey(),
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::InitializeDrop {
+        supply_cap,
+        limit_per_address,
+    }
+    .data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority, drop],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn initialize_roles(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::InitializeRoles {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        roles_pda: find_roles_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::InitializeRoles {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_role(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    role: Role,
+    key: Option<Pubkey>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::SetRole {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        roles_pda: find_roles_pda(candy_machine).0,
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::SetRole { role, key }.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn withdraw_funds(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    signer: &Keypair,
+    authority_wallet: &Pubkey,
+    roles_pda: Option<Pubkey>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::WithdrawFunds {
+        candy_machine: *candy_machine,
+        bullistic_authority: signer.pubkey(),
+        authority_wallet: *authority_wallet,
+        roles_pda,
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::WithdrawFunds {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_paused(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    roles_pda: Option<Pubkey>,
+    paused: bool,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::SetPaused {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        roles_pda,
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::SetPaused { paused }.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn set_multisig(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+    timelock_seconds: i64,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::SetMultisig {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        multisig_pda: find_multisig_pda(candy_machine).0,
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::SetMultisig {
+        signers,
+        threshold,
+        timelock_seconds,
+    }
+    .data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn create_proposal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    proposer: &Keypair,
+    index: u64,
+    action: ProposalAction,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::CreateProposal {
+        candy_machine: *candy_machine,
+        multisig_pda: find_multisig_pda(candy_machine).0,
+        proposal: find_proposal_pda(candy_machine, index).0,
+        proposer: proposer.pubkey(),
+        system_program: system_program::id(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::CreateProposal { action }.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&proposer.pubkey()),
+        &[proposer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn approve_proposal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    signer: &Keypair,
+    index: u64,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::ApproveProposal {
+        candy_machine: *candy_machine,
+        multisig_pda: find_multisig_pda(candy_machine).0,
+        proposal: find_proposal_pda(candy_machine, index).0,
+        signer: signer.pubkey(),
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::ApproveProposal {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn execute_proposal(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    payer: &Keypair,
+    proposer: &Pubkey,
+    index: u64,
+    destination: Option<Pubkey>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::ExecuteProposal {
+        candy_machine: *candy_machine,
+        multisig_pda: find_multisig_pda(candy_machine).0,
+        proposal: find_proposal_pda(candy_machine, index).0,
+        proposer: *proposer,
+        destination,
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::ExecuteProposal {}.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn rotate_bot_signers(
+    context: &mut ProgramTestContext,
+    candy_machine: &Pubkey,
+    bullistic_authority: &Keypair,
+    roles_pda: Option<Pubkey>,
+    bot_signers: Vec<BotSigner>,
+) -> SolanaProgramTestResult {
+    let accounts = bullistic_candy_machine::accounts::RotateBotSigners {
+        candy_machine: *candy_machine,
+        bullistic_authority: bullistic_authority.pubkey(),
+        roles_pda,
+    }
+    .to_account_metas(None);
+
+    let data = bullistic_candy_machine::instruction::RotateBotSigners { bot_signers }.data();
+    let ix = Instruction {
+        program_id: bullistic_candy_machine::id(),
+        data,
+        accounts,
+    };
+
+    update_blockhash(context).await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&bullistic_authority.pubkey()),
+        &[bullistic_authority],
+        context.last_blockhash,
+    );
+
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(|e| e.into())
+}
@@ -1,15 +1,16 @@
 use anchor_lang::prelude::ERROR_CODE_OFFSET;
+use anchor_lang::AnchorSerialize;
 use arrayref::array_ref;
+use bullistic_candy_machine::constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE};
+use bullistic_candy_machine::{CandyError, CandyMachine};
 use chrono::Utc;
 use console::style;
 use enum_index::EnumIndex;
-use bullistic_candy_machine::constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE};
-use bullistic_candy_machine::{CandyError, CandyMachine};
 use mpl_token_metadata::state::{MAX_NAME_LENGTH, MAX_URI_LENGTH};
 use solana_program::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
 use solana_sdk::account::Account;
-use solana_sdk::instruction::InstructionError;
-use solana_sdk::signature::Keypair;
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
 use solana_sdk::transaction::TransactionError;
 use solana_sdk::transport::TransportError;
 use spl_associated_token_account::get_associated_token_address;
@@ -20,7 +21,7 @@ use crate::utils::{FreezeInfo, SolanaProgramTestError, SolanaProgramTestResult,
 use bullistic_candy_machine::{
     constants::{BULLISTIC_CANDY_MACHINE_ERROR_OFFSET, PREFIX as CANDY_PREFIX},
     state::BUYER_INFO_ACCOUNT_PREFIX,
-    ConfigLine,
+    ConfigLine, MintVoucher, RevealPda,
 };
 
 pub fn get_config_line_name(index: u32) -> String {
@@ -54,6 +55,13 @@ pub fn find_collection_pda(candy_machine_key: &Pubkey) -> (Pubkey, u8) {
     )
 }
 
+pub fn find_reveal_pda(candy_machine_key: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[RevealPda::PREFIX.as_bytes(), candy_machine_key.as_ref()],
+        &bullistic_candy_machine::id(),
+    )
+}
+
 pub fn find_buyer_info_account_pda(candy_machine: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
     let seeds = &[
         BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
@@ -63,6 +71,40 @@ pub fn find_buyer_info_account_pda(candy_machine: &Pubkey, buyer: &Pubkey) -> (P
     Pubkey::find_program_address(seeds, &bullistic_candy_machine::id())
 }
 
+/// Ed25519 program instruction signing the voucher, to be placed right before
+/// the mint instruction.
+pub fn make_mint_voucher_ix(voucher_signer: &Keypair, voucher: &MintVoucher) -> Instruction {
+    let message = voucher.try_to_vec().unwrap();
+    let signature = voucher_signer.sign_message(&message);
+
+    // Signature count and padding, then the offsets, public key, signature
+    // and message. u16::MAX instruction indexes refer to this instruction.
+    let public_key_offset: u16 = 16;
+    let signature_offset: u16 = public_key_offset + 32;
+    let message_offset: u16 = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(voucher_signer.pubkey().as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(&message);
+
+    Instruction {
+        program_id: solana_sdk::ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
 pub fn find_freeze_ata(freeze_info: &FreezeInfo, token_info: &TokenInfo) -> Pubkey {
     get_associated_token_address(&freeze_info.pda, &token_info.mint)
 }
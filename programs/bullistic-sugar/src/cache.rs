@@ -102,6 +102,30 @@ impl CacheItems {
     pub fn new() -> Self {
         CacheItems(IndexMap::new())
     }
+
+    /// Config lines of every item in mint order, for the on-chain reveal.
+    pub fn to_reveal_config_lines(&self) -> Result<Vec<ConfigLine>> {
+        let mut items = self
+            .iter()
+            .filter(|(key, _)| *key != "-1") // skip collection index
+            .map(|(key, item)| Ok((key.parse::<usize>()?, item)))
+            .collect::<Result<Vec<(usize, &CacheItem)>>>()?;
+        items.sort_by_key(|(index, _)| *index);
+
+        items
+            .into_iter()
+            .enumerate()
+            .map(|(position, (index, item))| {
+                if position != index {
+                    return Err(anyhow!("Cache is missing item {}", position));
+                }
+                Ok(ConfigLine {
+                    name: item.name.clone(),
+                    uri: item.metadata_link.clone(),
+                })
+            })
+            .collect()
+    }
 }
 impl Default for CacheItems {
     fn default() -> Self {
@@ -1,14 +1,21 @@
-use anchor_client::{solana_sdk::pubkey::Pubkey, Client, ClientError};
+use anchor_client::{
+    solana_sdk::{pubkey::Pubkey, signature::Signature},
+    Client, ClientError, Program,
+};
 use anyhow::{anyhow, Result};
 pub use bullistic_candy_machine::ID as CANDY_MACHINE_ID;
 use bullistic_candy_machine::{
-    CandyMachine, CandyMachineData, SplTokenAllowlistMode, SplTokenAllowlistSettings,
+    accounts as nft_accounts, constants::USD_PRICE_DECIMALS, instruction as nft_instruction,
+    BotSigner, CandyMachine, CandyMachineData, SplTokenAllowlistMode, SplTokenAllowlistSettings,
 };
 use spl_token::id as token_program_id;
 
 use crate::{
     common::FloatConversionError,
-    config::{data::SugarConfig, price_as_lamports, ConfigData},
+    config::{
+        data::{BotSigner as ConfigBotSigner, SugarConfig},
+        price_as_lamports, ConfigData,
+    },
     setup::setup_client,
     utils::{check_spl_token, f64_to_u64_safe},
 };
@@ -59,6 +66,29 @@ pub fn parse_config_price(
                 }
             }
         }
+    } else if config.usd_price_settings.is_some() {
+        let config_price_base_units = price_to_parse * 10f64.powf(USD_PRICE_DECIMALS as f64);
+
+        match f64_to_u64_safe(config_price_base_units) {
+            Ok(price) => price,
+            Err(e) => {
+                match e {
+                    FloatConversionError::Fractional => {
+                        return Err(anyhow!(
+                            "Can't convert price to u64: USD prices may have at most {} decimals. Price: {}.",
+                            USD_PRICE_DECIMALS,
+                            price_to_parse
+                        ))
+                    },
+                    FloatConversionError::Overflow => {
+                        return Err(anyhow!(
+                            "Can't convert price to u64 because of overflow: price is too large. Price: {}.",
+                            price_to_parse
+                        ))
+                    },
+                }
+            }
+        }
     } else {
         price_as_lamports(price_to_parse)
     };
@@ -91,6 +121,32 @@ pub fn get_candy_machine_data(
     Ok(candy_machine.data)
 }
 
+pub fn get_bot_signers(config_bot_signers: &[ConfigBotSigner]) -> Result<Vec<BotSigner>> {
+    config_bot_signers
+        .iter()
+        .map(|bot_signer| bot_signer.to_candy_format())
+        .collect()
+}
+
+/// Replace the keys accepted as the bot signer authority of the candy machine.
+pub fn rotate_bot_signers(
+    program: &Program,
+    candy_machine: &Pubkey,
+    bot_signers: Vec<BotSigner>,
+) -> Result<Signature> {
+    let signature = program
+        .request()
+        .accounts(nft_accounts::RotateBotSigners {
+            candy_machine: *candy_machine,
+            bullistic_authority: program.payer(),
+            roles_pda: None,
+        })
+        .args(nft_instruction::RotateBotSigners { bot_signers })
+        .send()?;
+
+    Ok(signature)
+}
+
 pub fn print_candy_machine_state(state: CandyMachine) {
     println!("Authority {:?}", state.bullistic_authority);
     println!("Wallet {:?}", state.treasury_wallet);
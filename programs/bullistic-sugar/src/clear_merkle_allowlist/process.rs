@@ -9,6 +9,7 @@ use crate::{
     cache::load_cache,
     candy_machine::get_candy_machine_state,
     common::{setup_client, sugar_setup},
+    pdas::get_roles_pda_for_signer,
 };
 
 #[derive(Debug)]
@@ -47,8 +48,9 @@ pub async fn clear_merkle_allowlist(args: ClearMerkleAllowlistArgs) -> Result<()
         candy_machine_state.data.merkle_allowlist_root_list.len();
 
     let accounts = bullistic_candy_machine::accounts::ClearMerkleAllowlistRoots {
-        bullistic_authority: candy_machine_state.bullistic_authority,
+        bullistic_authority: program.payer(),
         candy_machine: candy_pubkey,
+        roles_pda: get_roles_pda_for_signer(&candy_machine_state, &candy_pubkey, &program.payer()),
     }
     .to_account_metas(None);
 
@@ -37,6 +37,12 @@ pub enum Commands {
         command: CollectionSubcommands,
     },
 
+    /// Mint compressed NFTs from the candy machine
+    Compressed {
+        #[clap(subcommand)]
+        command: CompressedSubcommands,
+    },
+
     /// Interactive process to create the config file
     CreateConfig {
         /// Path to the config file
@@ -131,6 +137,12 @@ pub enum Commands {
         cache: String,
     },
 
+    /// Manage the NFTs frozen by the candy machine
+    Freeze {
+        #[clap(subcommand)]
+        command: FreezeSubcommands,
+    },
+
     /// Generate hash of cache file for hidden settings.
     Hash {
         /// Path to the config file, defaults to "config.json"
@@ -210,6 +222,18 @@ pub enum Commands {
         /// Address of candy machine to mint from.
         #[clap(long)]
         candy_machine: Option<String>,
+
+        /// Mint of the NFT to burn, for candy machines with burn-to-mint settings
+        #[clap(long)]
+        burn_nft: Option<String>,
+
+        /// Public key of the referrer credited with the mint
+        #[clap(long)]
+        referrer: Option<String>,
+
+        /// Path to the config file with the bot signer keypair, defaults to "config.json"
+        #[clap(short, long, default_value = DEFAULT_CONFIG)]
+        config: String,
     },
 
     /// Reveal the NFTs from a hidden settings candy machine
@@ -253,7 +277,25 @@ pub enum Commands {
         unminted: bool,
     },
 
-    /// Sign one or all NFTs from candy machine
+    /// List the referrers of a candy machine with their referral earnings
+    Referrals {
+        /// Path to the keypair file, uses Sol config or defaults to "~/.config/solana/id.json"
+        #[clap(short, long)]
+        keypair: Option<String>,
+
+        /// RPC Url
+        #[clap(short, long)]
+        rpc_url: Option<String>,
+
+        /// Path to the cache file, defaults to "cache.json"
+        #[clap(long, default_value = DEFAULT_CACHE)]
+        cache: String,
+
+        /// Address of candy machine
+        candy_machine: Option<String>,
+    },
+
+    /// Sign one or all NFTs from candy machine and report any unverified creators
     Sign {
         /// Path to the keypair file, uses Sol config or defaults to "~/.config/solana/id.json"
         #[clap(short, long)]
@@ -276,6 +318,25 @@ pub enum Commands {
         candy_machine_id: Option<String>,
     },
 
+    /// Close the buyer info accounts of a candy machine after its sale, returning their rent
+    /// to the buyers
+    SweepBuyerInfo {
+        /// Path to the keypair file, uses Sol config or defaults to "~/.config/solana/id.json"
+        #[clap(short, long)]
+        keypair: Option<String>,
+
+        /// RPC Url
+        #[clap(short, long)]
+        rpc_url: Option<String>,
+
+        /// Path to the cache file, defaults to "cache.json"
+        #[clap(long, default_value = DEFAULT_CACHE)]
+        cache: String,
+
+        /// Address of candy machine
+        candy_machine: Option<String>,
+    },
+
     /// Update the candy machine config on-chain
     Update {
         /// Path to the config file, defaults to "config.json"
@@ -428,6 +489,65 @@ pub enum CollectionSubcommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum CompressedSubcommands {
+    /// Create the merkle tree the candy machine mints compressed NFTs into
+    SetTree {
+        /// Path to the keypair file, uses Sol config or defaults to "~/.config/solana/id.json"
+        #[clap(short, long)]
+        keypair: Option<String>,
+
+        /// RPC Url
+        #[clap(short, long)]
+        rpc_url: Option<String>,
+
+        /// Path to the cache file, defaults to "cache.json"
+        #[clap(long, default_value = DEFAULT_CACHE)]
+        cache: String,
+
+        /// Address of candy machine
+        #[clap(long)]
+        candy_machine: Option<String>,
+
+        /// Max depth of the tree, which holds up to 2^max_depth NFTs
+        #[clap(long, default_value = "14")]
+        max_depth: u32,
+
+        /// Number of concurrent changes the tree can take in the same slot
+        #[clap(long, default_value = "64")]
+        max_buffer_size: u32,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FreezeSubcommands {
+    /// Thaw one frozen NFT, or every frozen NFT of the candy machine
+    Thaw {
+        /// Path to the keypair file, uses Sol config or defaults to "~/.config/solana/id.json"
+        #[clap(short, long)]
+        keypair: Option<String>,
+
+        /// RPC Url
+        #[clap(short, long)]
+        rpc_url: Option<String>,
+
+        /// Path to the cache file, defaults to "cache.json"
+        #[clap(long, default_value = DEFAULT_CACHE)]
+        cache: String,
+
+        /// Address of candy machine
+        #[clap(long)]
+        candy_machine: Option<String>,
+
+        /// Thaw every frozen NFT, resuming from previous runs
+        #[clap(long)]
+        all: bool,
+
+        /// Mint of the NFT to thaw
+        nft_mint: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum BundlrAction {
     /// Retrieve the balance on bundlr
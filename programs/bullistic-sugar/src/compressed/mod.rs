@@ -0,0 +1,3 @@
+pub mod set_tree;
+
+pub use set_tree::*;
@@ -0,0 +1,172 @@
+use std::str::FromStr;
+
+use anchor_client::solana_sdk::{pubkey::Pubkey, system_instruction, system_program};
+use anchor_lang::{AnchorDeserialize, Id};
+use anyhow::Result;
+use bullistic_candy_machine::{
+    accounts as nft_accounts, find_compressed_tree_pda, instruction as nft_instruction,
+};
+use console::style;
+use spl_account_compression::{
+    program::SplAccountCompression,
+    state::{
+        merkle_tree_get_size, ConcurrentMerkleTreeHeader, CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1,
+    },
+    Noop,
+};
+
+use crate::{
+    cache::load_cache,
+    candy_machine::{CANDY_MACHINE_ID, *},
+    common::*,
+    utils::{assert_correct_authority, spinner_with_style},
+};
+
+pub struct SetTreeArgs {
+    pub keypair: Option<String>,
+    pub rpc_url: Option<String>,
+    pub cache: String,
+    pub candy_machine: Option<String>,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+}
+
+pub fn process_set_tree(args: SetTreeArgs) -> Result<()> {
+    let sugar_config = sugar_setup(args.keypair, args.rpc_url)?;
+    let client = setup_client(&sugar_config)?;
+    let program = client.program(CANDY_MACHINE_ID);
+
+    // The candy machine id specified takes precedence over the one from the cache.
+    let candy_machine_id = match args.candy_machine {
+        Some(candy_machine_id) => candy_machine_id,
+        None => {
+            let cache = load_cache(&args.cache, false)?;
+            cache.program.candy_machine
+        }
+    };
+
+    let candy_pubkey = match Pubkey::from_str(&candy_machine_id) {
+        Ok(candy_pubkey) => candy_pubkey,
+        Err(_) => {
+            let error = anyhow!("Failed to parse candy machine id: {}", candy_machine_id);
+            error!("{:?}", error);
+            return Err(error);
+        }
+    };
+
+    println!(
+        "{} {}Loading candy machine",
+        style("[1/2]").bold().dim(),
+        LOOKING_GLASS_EMOJI
+    );
+    println!("{} {}", style("Candy machine ID:").bold(), candy_machine_id);
+
+    let pb = spinner_with_style();
+    pb.set_message("Connecting...");
+
+    let candy_machine_state = get_candy_machine_state(&sugar_config, &candy_pubkey)?;
+
+    pb.finish_with_message("Done");
+
+    assert_correct_authority(
+        &sugar_config.keypair.pubkey(),
+        &candy_machine_state.bullistic_authority,
+    )?;
+
+    if candy_machine_state.items_redeemed > 0 {
+        return Err(anyhow!(
+            "You can't set the Candy Machine merkle tree after items have been minted."
+        ));
+    }
+
+    println!(
+        "\n{} {}Creating the merkle tree for compressed NFTs",
+        style("[2/2]").bold().dim(),
+        CANDY_EMOJI
+    );
+
+    let pb = spinner_with_style();
+    pb.set_message("Sending set compressed tree transaction...");
+
+    let merkle_tree = Keypair::new();
+    let set_signature = set_compressed_tree(
+        &program,
+        &candy_pubkey,
+        &merkle_tree,
+        args.max_depth,
+        args.max_buffer_size,
+    )?;
+
+    pb.finish_with_message(format!(
+        "{} {}",
+        style("Set compressed tree signature:").bold(),
+        set_signature
+    ));
+    println!("{} {}", style("Merkle tree:").bold(), merkle_tree.pubkey());
+
+    Ok(())
+}
+
+/// Size of a merkle tree account without canopy, which the account
+/// compression program only supports for some depth and buffer size pairs.
+pub fn get_merkle_tree_space(max_depth: u32, max_buffer_size: u32) -> Result<usize> {
+    let mut header =
+        ConcurrentMerkleTreeHeader::try_from_slice(&[0; CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1])?;
+    header.initialize(max_depth, max_buffer_size, &Pubkey::default(), 0);
+    let tree_size = merkle_tree_get_size(&header).map_err(|_| {
+        anyhow!(
+            "Unsupported merkle tree max depth {} and max buffer size {}.",
+            max_depth,
+            max_buffer_size
+        )
+    })?;
+
+    Ok(CONCURRENT_MERKLE_TREE_HEADER_SIZE_V1 + tree_size)
+}
+
+pub fn set_compressed_tree(
+    program: &Program,
+    candy_pubkey: &Pubkey,
+    merkle_tree: &Keypair,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<Signature> {
+    let payer = program.payer();
+
+    let space = get_merkle_tree_space(max_depth, max_buffer_size)?;
+    let lamports = program
+        .rpc()
+        .get_minimum_balance_for_rent_exemption(space)?;
+    let (tree_authority, _) =
+        Pubkey::find_program_address(&[merkle_tree.pubkey().as_ref()], &mpl_bubblegum::id());
+
+    let builder = program
+        .request()
+        .instruction(system_instruction::create_account(
+            &payer,
+            &merkle_tree.pubkey(),
+            lamports,
+            space as u64,
+            &SplAccountCompression::id(),
+        ))
+        .accounts(nft_accounts::SetCompressedTree {
+            candy_machine: *candy_pubkey,
+            bullistic_authority: payer,
+            compressed_tree: find_compressed_tree_pda(candy_pubkey).0,
+            tree_authority,
+            merkle_tree: merkle_tree.pubkey(),
+            log_wrapper: Noop::id(),
+            compression_program: SplAccountCompression::id(),
+            bubblegum_program: mpl_bubblegum::id(),
+            system_program: system_program::id(),
+        })
+        .args(nft_instruction::SetCompressedTree {
+            max_depth,
+            max_buffer_size,
+        })
+        .signer(merkle_tree);
+
+    let sig = builder.send()?;
+
+    Ok(sig)
+}
@@ -9,9 +9,15 @@ use anchor_client::solana_sdk::{
 pub use anyhow::{anyhow, Result};
 use chrono::prelude::*;
 use bullistic_candy_machine::{
-    Creator as CandyCreator, HiddenSettings as CandyHiddenSettings,
+    BotSigner as CandyBotSigner, BotTaxSettings as CandyBotTaxSettings,
+    BurnToMintMode as CandyBurnToMintMode, BurnToMintSettings as CandyBurnToMintSettings,
+    Creator as CandyCreator, HiddenSettings as CandyHiddenSettings, MintOrder as CandyMintOrder,
+    MintRateLimitSettings as CandyMintRateLimitSettings,
+    OpenEditionSettings as CandyOpenEditionSettings,
+    ProgrammableNftSettings as CandyProgrammableNftSettings,
     SplTokenAllowlistMode as CandySplTokenAllowlistMode,
     SplTokenAllowlistSettings as CandySplTokenAllowlistSettings,
+    UsdPriceSettings as CandyUsdPriceSettings,
 };
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -71,6 +77,8 @@ pub struct ConfigData {
 
     pub hidden_settings: Option<HiddenSettings>,
 
+    pub open_edition_settings: Option<OpenEditionSettings>,
+
     pub upload_method: UploadMethod,
 
     pub is_mutable: bool,
@@ -85,8 +93,53 @@ pub struct ConfigData {
 
     pub bot_protection_enabled: bool,
 
+    pub bot_tax_settings: Option<BotTaxSettings>,
+
     pub sequential_mint_order_enabled: bool,
 
+    #[serde(default)]
+    pub on_chain_reveal_enabled: bool,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "to_option_pubkey")]
+    #[serde(serialize_with = "to_option_string")]
+    pub voucher_signer: Option<Pubkey>,
+
+    #[serde(default)]
+    pub burn_to_mint_settings: Option<BurnToMintSettings>,
+
+    #[serde(default)]
+    pub referral_fee_basis_points: u16,
+
+    #[serde(default)]
+    pub creator_overrides_enabled: bool,
+
+    #[serde(default)]
+    pub usd_price_settings: Option<UsdPriceSettings>,
+
+    #[serde(default)]
+    pub mint_rate_limit_settings: Option<MintRateLimitSettings>,
+
+    #[serde(default)]
+    pub min_items_to_succeed: Option<u64>,
+
+    #[serde(default)]
+    pub programmable_nft_settings: Option<ProgrammableNftSettings>,
+
+    #[serde(default)]
+    #[serde(deserialize_with = "to_option_pubkey")]
+    #[serde(serialize_with = "to_option_string")]
+    pub drop: Option<Pubkey>,
+
+    #[serde(default)]
+    pub mint_order: MintOrder,
+
+    #[serde(default)]
+    pub bot_signers: Vec<BotSigner>,
+
+    #[serde(default)]
+    pub bot_signer_keypair: Option<String>,
+
     #[serde(serialize_with = "to_option_string")]
     pub nft_storage_auth_token: Option<String>,
 
@@ -219,6 +272,145 @@ impl FromStr for SplTokenAllowlistMode {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BurnToMintSettings {
+    mode: BurnToMintMode,
+    #[serde(deserialize_with = "to_pubkey")]
+    #[serde(serialize_with = "to_string")]
+    collection_mint: Pubkey,
+}
+
+impl BurnToMintSettings {
+    pub fn to_candy_format(&self) -> CandyBurnToMintSettings {
+        CandyBurnToMintSettings {
+            mode: self.mode.to_candy_format(),
+            collection_mint: self.collection_mint,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BurnToMintMode {
+    ReplacesPayment,
+    ReplacesAllowlist,
+}
+
+impl BurnToMintMode {
+    pub fn to_candy_format(&self) -> CandyBurnToMintMode {
+        match self {
+            BurnToMintMode::ReplacesPayment => CandyBurnToMintMode::ReplacesPayment,
+            BurnToMintMode::ReplacesAllowlist => CandyBurnToMintMode::ReplacesAllowlist,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MintOrder {
+    Random,
+    Sequential,
+    ReverseSequential,
+    #[serde(rename_all = "camelCase")]
+    ReservedRangePerPhase {
+        allowlist_items: u64,
+    },
+}
+
+impl MintOrder {
+    pub fn to_candy_format(&self) -> CandyMintOrder {
+        match self {
+            MintOrder::Random => CandyMintOrder::Random,
+            MintOrder::Sequential => CandyMintOrder::Sequential,
+            MintOrder::ReverseSequential => CandyMintOrder::ReverseSequential,
+            MintOrder::ReservedRangePerPhase { allowlist_items } => {
+                CandyMintOrder::ReservedRangePerPhase {
+                    allowlist_items: *allowlist_items,
+                }
+            }
+        }
+    }
+}
+
+impl Default for MintOrder {
+    fn default() -> MintOrder {
+        MintOrder::Random
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsdPriceSettings {
+    #[serde(deserialize_with = "to_pubkey")]
+    #[serde(serialize_with = "to_string")]
+    price_feed: Pubkey,
+    max_price_age_seconds: u32,
+    max_confidence_basis_points: u16,
+}
+
+impl UsdPriceSettings {
+    pub fn to_candy_format(&self) -> CandyUsdPriceSettings {
+        CandyUsdPriceSettings {
+            price_feed: self.price_feed,
+            max_price_age_seconds: self.max_price_age_seconds,
+            max_confidence_basis_points: self.max_confidence_basis_points,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintRateLimitSettings {
+    max_mints: u32,
+    window_slots: u64,
+}
+
+impl MintRateLimitSettings {
+    pub fn to_candy_format(&self) -> CandyMintRateLimitSettings {
+        CandyMintRateLimitSettings {
+            max_mints: self.max_mints,
+            window_slots: self.window_slots,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotSigner {
+    #[serde(deserialize_with = "to_pubkey")]
+    #[serde(serialize_with = "to_string")]
+    key: Pubkey,
+    #[serde(default)]
+    expires_at: Option<String>,
+}
+
+impl BotSigner {
+    pub fn to_candy_format(&self) -> Result<CandyBotSigner> {
+        Ok(CandyBotSigner {
+            key: self.key,
+            expires_at: config_time_opt_as_timestamp(&self.expires_at)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgrammableNftSettings {
+    #[serde(default)]
+    #[serde(deserialize_with = "to_option_pubkey")]
+    #[serde(serialize_with = "to_option_string")]
+    rule_set: Option<Pubkey>,
+}
+
+impl ProgrammableNftSettings {
+    pub fn to_candy_format(&self) -> CandyProgrammableNftSettings {
+        CandyProgrammableNftSettings {
+            rule_set: self.rule_set,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HiddenSettings {
     name: String,
@@ -242,6 +434,65 @@ impl HiddenSettings {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenEditionSettings {
+    #[serde(deserialize_with = "to_pubkey")]
+    #[serde(serialize_with = "to_string")]
+    pub master_edition_mint: Pubkey,
+}
+
+impl OpenEditionSettings {
+    pub fn new(master_edition_mint: Pubkey) -> OpenEditionSettings {
+        OpenEditionSettings {
+            master_edition_mint,
+        }
+    }
+    pub fn to_candy_format(&self) -> CandyOpenEditionSettings {
+        CandyOpenEditionSettings {
+            master_edition_mint: self.master_edition_mint,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BotTaxSettings {
+    // Bot tax in SOL.
+    fee: f64,
+    #[serde(default)]
+    #[serde(deserialize_with = "to_option_pubkey")]
+    #[serde(serialize_with = "to_option_string")]
+    destination: Option<Pubkey>,
+    #[serde(default)]
+    allowed_program_ids: Vec<String>,
+    check_top_level_instruction: bool,
+    check_trailing_instructions: bool,
+    check_transaction_programs: bool,
+}
+
+impl BotTaxSettings {
+    pub fn to_candy_format(&self) -> Result<CandyBotTaxSettings> {
+        let allowed_program_ids = self
+            .allowed_program_ids
+            .iter()
+            .map(|program_id| {
+                Pubkey::from_str(program_id)
+                    .map_err(|_| anyhow!("Invalid program id in bot tax settings: {}", program_id))
+            })
+            .collect::<Result<Vec<Pubkey>>>()?;
+
+        Ok(CandyBotTaxSettings {
+            fee: price_as_lamports(self.fee),
+            destination: self.destination,
+            allowed_program_ids,
+            check_top_level_instruction: self.check_top_level_instruction,
+            check_trailing_instructions: self.check_trailing_instructions,
+            check_transaction_programs: self.check_transaction_programs,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UploadMethod {
@@ -6,6 +6,9 @@ pub use mpl_token_metadata::state::{
 /// Metaplex program id.
 pub const METAPLEX_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 
+/// Token auth rules program id, used by programmable NFT rule sets.
+pub const TOKEN_AUTH_RULES_PROGRAM_ID: &str = "auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg";
+
 pub const STRING_LEN_SIZE: usize = 4;
 
 pub const CONFIG_NAME_OFFSET: usize = STRING_LEN_SIZE;
@@ -16,18 +16,25 @@ pub use mpl_token_metadata::state::{
 };
 
 use crate::{
-    cache::*, candy_machine::CANDY_MACHINE_ID, common::*, config::data::*, deploy::errors::*,
-    setup::setup_client, utils::*,
+    cache::*,
+    candy_machine::{get_candy_machine_state, CANDY_MACHINE_ID},
+    common::*,
+    config::data::*,
+    deploy::errors::*,
+    pdas::get_roles_pda_for_signer,
+    setup::setup_client,
+    utils::*,
 };
 
 /// The maximum config line bytes per transaction.
-const MAX_TRANSACTION_BYTES: usize = 1000;
+pub const MAX_TRANSACTION_BYTES: usize = 1000;
 
 /// The maximum number of config lines per transaction.
-const MAX_TRANSACTION_LINES: usize = 17;
+pub const MAX_TRANSACTION_LINES: usize = 17;
 
 pub struct TxInfo {
     candy_pubkey: Pubkey,
+    roles_pda: Option<Pubkey>,
     payer: Keypair,
     chunk: Vec<(u32, ConfigLine)>,
 }
@@ -104,6 +111,13 @@ pub async fn upload_config_lines(
     debug!("Num of config line chunks: {:?}", config_lines.len());
     info!("Uploading config lines in chunks...");
 
+    let candy_machine_state = get_candy_machine_state(&sugar_config, &candy_pubkey)?;
+    let roles_pda = get_roles_pda_for_signer(
+        &candy_machine_state,
+        &candy_pubkey,
+        &sugar_config.keypair.pubkey(),
+    );
+
     let mut transactions = Vec::new();
 
     for chunk in config_lines {
@@ -112,6 +126,7 @@ pub async fn upload_config_lines(
 
         transactions.push(TxInfo {
             candy_pubkey,
+            roles_pda,
             payer,
             chunk,
         });
@@ -222,6 +237,7 @@ pub async fn add_config_lines(config: Arc<SugarConfig>, tx_info: TxInfo) -> Resu
         .accounts(nft_accounts::AddConfigLines {
             candy_machine: tx_info.candy_pubkey,
             bullistic_authority: program.payer(),
+            roles_pda: tx_info.roles_pda,
         })
         .args(nft_instruction::AddConfigLines {
             index: start_index,
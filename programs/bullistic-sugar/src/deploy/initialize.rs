@@ -15,7 +15,12 @@ pub use mpl_token_metadata::state::{
 };
 use solana_program::native_token::LAMPORTS_PER_SOL;
 
-use crate::{candy_machine::parse_config_price, common::*, config::data::*, deploy::errors::*};
+use crate::{
+    candy_machine::{get_bot_signers, parse_config_price, rotate_bot_signers},
+    common::*,
+    config::data::*,
+    deploy::errors::*,
+};
 
 /// Create the candy machine data struct.
 pub fn create_candy_machine_data(
@@ -41,6 +46,17 @@ pub fn create_candy_machine_data(
 
     let hidden_settings = config.hidden_settings.as_ref().map(|s| s.to_candy_format());
 
+    let open_edition_settings = config
+        .open_edition_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let bot_tax_settings = config
+        .bot_tax_settings
+        .as_ref()
+        .map(|s| s.to_candy_format())
+        .transpose()?;
+
     let mut creators: Vec<CandyCreator> = Vec::new();
     let mut share = 0u32;
 
@@ -65,6 +81,26 @@ pub fn create_candy_machine_data(
         ));
     }
 
+    let burn_to_mint_settings = config
+        .burn_to_mint_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let usd_price_settings = config
+        .usd_price_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let mint_rate_limit_settings = config
+        .mint_rate_limit_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let programmable_nft_settings = config
+        .programmable_nft_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
     let price = parse_config_price(client, config, config.price)?;
     let premint_price = match config.premint_price {
         Some(price) => Some(parse_config_price(client, config, price)?),
@@ -98,6 +134,21 @@ pub fn create_candy_machine_data(
         sequential_mint_order_enabled: config.sequential_mint_order_enabled,
         merkle_allowlist_root_list: Vec::new(),
         allowlist_sale_start_time,
+        open_edition_settings,
+        bot_tax_settings,
+        on_chain_reveal_enabled: config.on_chain_reveal_enabled,
+        voucher_signer: config.voucher_signer,
+        burn_to_mint_settings,
+        referral_fee_basis_points: config.referral_fee_basis_points,
+        creator_overrides_enabled: config.creator_overrides_enabled,
+        usd_price_settings,
+        mint_rate_limit_settings,
+        // All-or-nothing sales need freeze, which can only be set once the
+        // candy machine exists, so they're enabled with `sugar update`.
+        min_items_to_succeed: None,
+        programmable_nft_settings,
+        drop: config.drop,
+        mint_order: config.mint_order.to_candy_format(),
     };
 
     Ok(data)
@@ -166,11 +217,18 @@ pub fn initialize_candy_machine(
         });
     }
 
-    match tx.send() {
-        Ok(sig) => Ok(sig),
+    let sig = match tx.send() {
+        Ok(sig) => sig,
         Err(e) => {
             println!("\nAn error occurred creating the candy machine: {:?}", e);
-            Err(anyhow!(e))
+            return Err(anyhow!(e));
         }
+    };
+
+    let bot_signers = get_bot_signers(&config_data.bot_signers)?;
+    if !bot_signers.is_empty() {
+        rotate_bot_signers(&program, &candy_account.pubkey(), bot_signers)?;
     }
+
+    Ok(sig)
 }
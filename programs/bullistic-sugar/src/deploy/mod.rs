@@ -2,10 +2,12 @@ pub mod collection;
 pub mod config_lines;
 pub mod errors;
 pub mod initialize;
+pub mod open_edition;
 pub mod process;
 
 pub use collection::*;
 pub use config_lines::*;
 pub use errors::*;
 pub use initialize::*;
+pub use open_edition::*;
 pub use process::*;
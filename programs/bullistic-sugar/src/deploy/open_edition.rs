@@ -0,0 +1,57 @@
+use anchor_client::{solana_sdk::pubkey::Pubkey, Client};
+use anyhow::Result;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account;
+use spl_token::{instruction::transfer, ID as TOKEN_PROGRAM_ID};
+
+use crate::{
+    candy_machine::CANDY_MACHINE_ID, common::*, config::OpenEditionSettings,
+    pdas::find_candy_machine_creator_pda,
+};
+
+/// Move the master edition token from the payer into a token account owned by
+/// the candy machine creator PDA, which signs for every edition printed.
+pub fn transfer_master_edition_to_candy_machine(
+    client: Client,
+    candy_pubkey: Pubkey,
+    open_edition_settings: &OpenEditionSettings,
+) -> Result<Option<Signature>> {
+    let program = client.program(CANDY_MACHINE_ID);
+    let payer = program.payer();
+    let master_edition_mint = open_edition_settings.master_edition_mint;
+
+    let (candy_machine_creator_pda, _) = find_candy_machine_creator_pda(&candy_pubkey);
+    let source = get_associated_token_address(&payer, &master_edition_mint);
+    let destination =
+        get_associated_token_address(&candy_machine_creator_pda, &master_edition_mint);
+
+    if let Ok(balance) = program.rpc().get_token_account_balance(&destination) {
+        if balance.amount == "1" {
+            return Ok(None);
+        }
+    }
+
+    let mut builder = program.request();
+
+    if program.rpc().get_account(&destination).is_err() {
+        builder = builder.instruction(create_associated_token_account(
+            &payer,
+            &candy_machine_creator_pda,
+            &master_edition_mint,
+            &TOKEN_PROGRAM_ID,
+        ));
+    }
+
+    let sig = builder
+        .instruction(transfer(
+            &TOKEN_PROGRAM_ID,
+            &source,
+            &destination,
+            &payer,
+            &[],
+            1,
+        )?)
+        .send()?;
+
+    Ok(Some(sig))
+}
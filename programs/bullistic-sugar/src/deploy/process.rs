@@ -26,7 +26,7 @@ use crate::{
     config::parser::get_config_data,
     deploy::{
         create_and_set_collection, create_candy_machine_data, errors::*, generate_config_lines,
-        initialize_candy_machine, upload_config_lines,
+        initialize_candy_machine, transfer_master_edition_to_candy_machine, upload_config_lines,
     },
     hash::hash_and_update,
     merkle_allowlist::parse_merkle_allowlist_config,
@@ -90,12 +90,15 @@ pub async fn process_deploy(args: DeployArgs) -> Result<()> {
 
     let num_items = config_data.number;
     let hidden = config_data.hidden_settings.is_some();
+    let open_edition = config_data.open_edition_settings.is_some();
     let collection_in_cache = cache.items.get("-1").is_some();
     let mut item_redeemed = false;
 
     let cache_items_sans_collection = (cache.items.len() - collection_in_cache as usize) as u64;
 
-    if num_items != cache_items_sans_collection {
+    // Open editions are printed from a single master edition, so the number of
+    // items is the edition supply rather than the number of cached assets.
+    if !open_edition && num_items != cache_items_sans_collection {
         return Err(anyhow!(
             "Number of items ({}) do not match cache items ({}). 
             Item number in the config should only include asset files, not the collection file.",
@@ -184,6 +187,14 @@ pub async fn process_deploy(args: DeployArgs) -> Result<()> {
 
         spinner.finish_and_clear();
 
+        if config_data.min_items_to_succeed.is_some() {
+            println!(
+                "\n{}",
+                style("min_items_to_succeed was not set: set freeze on the candy machine, then run 'update' to enable it.")
+                    .yellow()
+            );
+        }
+
         candy_pubkey
     } else {
         println!(
@@ -248,7 +259,29 @@ pub async fn process_deploy(args: DeployArgs) -> Result<()> {
     }
 
     // Hidden Settings check needs to be the last action in this command, so we can update the hash with the final cache state.
-    if !hidden {
+    if let Some(open_edition_settings) = &config_data.open_edition_settings {
+        let step_num = 2 + (collection_in_cache as u8);
+        println!(
+            "\n{} {}Transferring the master edition to the candy machine",
+            style(format!("[{}/{}]", step_num, total_steps))
+                .bold()
+                .dim(),
+            PAPER_EMOJI
+        );
+
+        let pb = spinner_with_style();
+        pb.set_message("Sending master edition transfer transaction...");
+
+        let client = setup_client(&sugar_config)?;
+        let sig =
+            transfer_master_edition_to_candy_machine(client, candy_pubkey, open_edition_settings)?;
+
+        pb.finish_and_clear();
+        match sig {
+            Some(sig) => info!("Master edition transferred with sig: {}", sig),
+            None => println!("\nMaster edition already held by the candy machine."),
+        }
+    } else if !hidden {
         let step_num = 2 + (collection_in_cache as u8);
         println!(
             "\n{} {}Writing config lines",
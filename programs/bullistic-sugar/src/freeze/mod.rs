@@ -0,0 +1,3 @@
+pub mod thaw;
+
+pub use thaw::*;
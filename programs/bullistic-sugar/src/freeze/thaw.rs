@@ -0,0 +1,364 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+use anchor_client::solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    program_option::COption,
+    program_pack::Pack,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::Error;
+use bullistic_candy_machine::{find_freeze_record_pda, CandyError};
+use console::style;
+use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
+use retry::{delay::Exponential, retry};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use spl_token::state::{Account as TokenAccount, AccountState};
+use tokio::sync::Semaphore;
+
+use crate::{
+    cache::load_cache,
+    candy_machine::{get_candy_machine_state, CANDY_MACHINE_ID},
+    common::*,
+    config::SugarConfig,
+    pdas::{find_candy_machine_creator_pda, find_freeze_pda, find_master_edition_pda},
+    utils::*,
+};
+
+/// Number of NFTs thawed by a single thaw_nfts instruction.
+const THAW_NFTS_BATCH_SIZE: usize = 5;
+
+/// Number of thaw transactions in flight at the same time.
+const THAW_PARALLEL_LIMIT: usize = 20;
+
+/// Mints already thawed by previous runs, so an interrupted run can resume.
+const THAW_PROGRESS_FILE: &str = ".sugar-cli-run/thaw-progress.json";
+
+pub struct ThawArgs {
+    pub keypair: Option<String>,
+    pub rpc_url: Option<String>,
+    pub cache: String,
+    pub candy_machine: Option<String>,
+    pub all: bool,
+    pub nft_mint: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThawProgress {
+    candy_machine: String,
+    thawed: Vec<String>,
+}
+
+/// Accounts needed to thaw a single NFT.
+#[derive(Clone, Debug)]
+struct FrozenNft {
+    mint: Pubkey,
+    token_account: Pubkey,
+    owner: Pubkey,
+}
+
+pub async fn process_thaw(args: ThawArgs) -> Result<()> {
+    if !args.all && args.nft_mint.is_none() {
+        return Err(anyhow!(
+            "Specify the mint of the NFT to thaw, or use --all to thaw every frozen NFT."
+        ));
+    }
+
+    println!(
+        "{} {}Loading candy machine",
+        style("[1/3]").bold().dim(),
+        LOOKING_GLASS_EMOJI
+    );
+
+    let pb = spinner_with_style();
+    pb.set_message("Connecting...");
+
+    // the candy machine id specified takes precedence over the one from the cache
+    let candy_machine_id = match args.candy_machine {
+        Some(candy_machine_id) => candy_machine_id,
+        None => {
+            let cache = load_cache(&args.cache, false)?;
+            cache.program.candy_machine
+        }
+    };
+
+    let candy_machine_id = match Pubkey::from_str(&candy_machine_id) {
+        Ok(candy_machine_id) => candy_machine_id,
+        Err(_) => {
+            let error = anyhow!("Failed to parse candy machine id: {}", candy_machine_id);
+            error!("{:?}", error);
+            return Err(error);
+        }
+    };
+
+    let sugar_config = Arc::new(sugar_setup(args.keypair, args.rpc_url)?);
+    let client = setup_client(&sugar_config)?;
+    let program = client.program(CANDY_MACHINE_ID);
+    let (freeze_pda, _) = find_freeze_pda(&candy_machine_id);
+
+    if program.rpc().get_account(&freeze_pda).is_err() {
+        pb.finish_and_clear();
+        return Err(anyhow!(
+            "Freeze is not set for candy machine {}.",
+            candy_machine_id
+        ));
+    }
+
+    // A rolling freeze also needs the freeze record of each NFT; the candy
+    // machine may already be closed, in which case no records are left.
+    let candy_machine_state = get_candy_machine_state(&sugar_config, &candy_machine_id).ok();
+    let rolling_freeze = candy_machine_state
+        .as_ref()
+        .map_or(false, |candy_machine| candy_machine.has_rolling_freeze());
+
+    // Programmable NFTs are unlocked one at a time with thaw_nft.
+    if candy_machine_state.map_or(false, |candy_machine| {
+        candy_machine.data.programmable_nft_settings.is_some()
+    }) {
+        pb.finish_and_clear();
+        return Err(anyhow!(CandyError::ProgrammableNftThawNotSupported));
+    }
+
+    pb.finish_with_message("Done");
+
+    println!(
+        "\n{} {}Finding frozen NFTs",
+        style("[2/3]").bold().dim(),
+        LOOKING_GLASS_EMOJI
+    );
+
+    let pb = spinner_with_style();
+    pb.set_message("Loading...");
+
+    let progress = load_thaw_progress(&candy_machine_id)?;
+    let mints = match args.nft_mint {
+        Some(nft_mint) => vec![Pubkey::from_str(&nft_mint)?],
+        None => {
+            let (creator, _) = find_candy_machine_creator_pda(&candy_machine_id);
+            let creator = bs58::encode(creator).into_string();
+            let metadata_pubkeys = get_cm_creator_accounts(&program.rpc(), &creator, 0)?;
+            let thawed: HashSet<&String> = progress.thawed.iter().collect();
+
+            get_mints(&program.rpc(), &metadata_pubkeys)?
+                .into_iter()
+                .filter(|mint| !thawed.contains(&mint.to_string()))
+                .collect()
+        }
+    };
+    let frozen_nfts = get_frozen_nfts(&program.rpc(), &mints, &freeze_pda)?;
+
+    pb.finish_with_message(format!(
+        "Found {} frozen NFT(s) out of {} to check",
+        frozen_nfts.len(),
+        mints.len()
+    ));
+
+    println!(
+        "\n{} {}Thawing NFTs",
+        style("[3/3]").bold().dim(),
+        CANDY_EMOJI
+    );
+
+    if frozen_nfts.is_empty() {
+        println!("{}", style("No frozen NFTs left to thaw.").green().bold());
+        return Ok(());
+    }
+
+    let pb = progress_bar_with_style(frozen_nfts.len() as u64);
+    let progress = Arc::new(Mutex::new(progress));
+    let semaphore = Arc::new(Semaphore::new(THAW_PARALLEL_LIMIT));
+    let mut join_handles = Vec::new();
+
+    for batch in frozen_nfts.chunks(THAW_NFTS_BATCH_SIZE) {
+        let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
+        let config = sugar_config.clone();
+        let progress = progress.clone();
+        let pb = pb.clone();
+        let batch = batch.to_vec();
+
+        join_handles.push(tokio::spawn(async move {
+            let _permit = permit;
+            let batch_len = batch.len() as u64;
+            let result = thaw_batch(config, candy_machine_id, rolling_freeze, &batch).await;
+            pb.inc(batch_len);
+            result?;
+
+            // Record each completed batch right away so an interrupted run resumes from here.
+            let mut progress = progress.lock().unwrap();
+            progress
+                .thawed
+                .extend(batch.iter().map(|nft| nft.mint.to_string()));
+            save_thaw_progress(&progress)
+        }));
+    }
+
+    let mut errors = Vec::new();
+    for handle in join_handles {
+        match handle.await {
+            Ok(Err(err)) => errors.push(err),
+            Err(err) => errors.push(anyhow!(err)),
+            Ok(Ok(())) => (),
+        }
+    }
+
+    if !errors.is_empty() {
+        pb.abandon_with_message(format!("{}", style("Thaw command failed ").red().bold()));
+        for err in &errors {
+            error!("{:?}", err);
+        }
+        return Err(anyhow!(
+            "{} thaw transaction(s) failed. Re-run the command to resume.",
+            errors.len()
+        ));
+    }
+
+    pb.finish_with_message(format!(
+        "{}",
+        style("All frozen NFTs thawed successfully.").green().bold()
+    ));
+
+    Ok(())
+}
+
+async fn thaw_batch(
+    config: Arc<SugarConfig>,
+    candy_machine_id: Pubkey,
+    rolling_freeze: bool,
+    batch: &[FrozenNft],
+) -> Result<(), Error> {
+    let client = setup_client(&config)?;
+    let program = client.program(CANDY_MACHINE_ID);
+    let (freeze_pda, _) = find_freeze_pda(&candy_machine_id);
+
+    let mut accounts = nft_accounts::ThawNFTs {
+        freeze_pda,
+        candy_machine: candy_machine_id,
+        payer: config.keypair.pubkey(),
+        token_program: spl_token::ID,
+        token_metadata_program: mpl_token_metadata::ID,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    for nft in batch {
+        accounts.extend([
+            AccountMeta::new_readonly(nft.mint, false),
+            AccountMeta::new(nft.token_account, false),
+            AccountMeta::new_readonly(find_master_edition_pda(&nft.mint), false),
+            AccountMeta::new_readonly(nft.owner, false),
+        ]);
+        if rolling_freeze {
+            accounts.push(AccountMeta::new(
+                find_freeze_record_pda(&candy_machine_id, &nft.mint).0,
+                false,
+            ));
+        }
+    }
+
+    let ix = Instruction {
+        program_id: CANDY_MACHINE_ID,
+        accounts,
+        data: nft_instruction::ThawNfts {}.data(),
+    };
+
+    let recent_blockhash = program.rpc().get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&config.keypair.pubkey()),
+        &[&config.keypair],
+        recent_blockhash,
+    );
+
+    // Send tx with retries.
+    retry(
+        Exponential::from_millis_with_factor(250, 2.0).take(3),
+        || program.rpc().send_and_confirm_transaction(&tx),
+    )?;
+
+    Ok(())
+}
+
+fn get_mints(client: &RpcClient, metadata_pubkeys: &[Pubkey]) -> Result<Vec<Pubkey>> {
+    let mut mints = Vec::new();
+
+    for chunk in metadata_pubkeys.chunks(100) {
+        for account in client.get_multiple_accounts(chunk)?.into_iter().flatten() {
+            let metadata = Metadata::safe_deserialize(&account.data)?;
+            mints.push(metadata.mint);
+        }
+    }
+
+    Ok(mints)
+}
+
+/// Find the current holder of each NFT and keep the ones still frozen by the
+/// candy machine.
+fn get_frozen_nfts(
+    client: &RpcClient,
+    mints: &[Pubkey],
+    freeze_pda: &Pubkey,
+) -> Result<Vec<FrozenNft>> {
+    let mut token_accounts = Vec::new();
+    for mint in mints {
+        let holder = client
+            .get_token_largest_accounts(mint)?
+            .into_iter()
+            .find(|balance| balance.amount.amount == "1");
+        if let Some(holder) = holder {
+            token_accounts.push(Pubkey::from_str(&holder.address)?);
+        }
+    }
+
+    let mut frozen_nfts = Vec::new();
+    for chunk in token_accounts.chunks(100) {
+        let accounts = client.get_multiple_accounts(chunk)?;
+        for (pubkey, account) in chunk.iter().zip(accounts) {
+            let account = match account {
+                Some(account) => TokenAccount::unpack(&account.data)?,
+                None => continue,
+            };
+            if account.state == AccountState::Frozen
+                && account.delegate == COption::Some(*freeze_pda)
+            {
+                frozen_nfts.push(FrozenNft {
+                    mint: account.mint,
+                    token_account: *pubkey,
+                    owner: account.owner,
+                });
+            }
+        }
+    }
+
+    Ok(frozen_nfts)
+}
+
+fn load_thaw_progress(candy_machine_id: &Pubkey) -> Result<ThawProgress> {
+    let candy_machine = candy_machine_id.to_string();
+    let progress = if Path::new(THAW_PROGRESS_FILE).exists() {
+        let file = File::open(THAW_PROGRESS_FILE)?;
+        serde_json::from_reader(file).unwrap_or_default()
+    } else {
+        ThawProgress::default()
+    };
+
+    // Progress from another candy machine is discarded.
+    if progress.candy_machine == candy_machine {
+        Ok(progress)
+    } else {
+        Ok(ThawProgress {
+            candy_machine,
+            thawed: Vec::new(),
+        })
+    }
+}
+
+fn save_thaw_progress(progress: &ThawProgress) -> Result<()> {
+    let file = File::create(THAW_PROGRESS_FILE)
+        .map_err(|e| anyhow!("Failed to create thaw progress file: {e}"))?;
+    serde_json::to_writer_pretty(file, progress)?;
+
+    Ok(())
+}
@@ -3,10 +3,12 @@ use std::{
     io::{BufReader, Read},
 };
 
+use bullistic_candy_machine::{get_reveal_hash_commitment, hash_reveal_config_line};
 use console::style;
 use sha2::{Digest, Sha256};
 
 use crate::{
+    cache::load_cache,
     common::*,
     config::{get_config_data, ConfigData, HiddenSettings},
 };
@@ -24,17 +26,7 @@ pub fn process_hash(args: HashArgs) -> Result<()> {
     // "Command successful".
 
     if let Some(hash) = args.compare {
-        let mut hasher = Sha256::new();
-
-        let cache_file = File::open(args.cache)?;
-        let mut reader = BufReader::new(cache_file);
-        let mut buffer = Vec::new();
-        // Read file into vector.
-        reader.read_to_end(&mut buffer)?;
-
-        hasher.update(&buffer);
-        let hash_base58 = bs58::encode(&hasher.finalize()).into_string();
-        let expected_hash = hash_base58.chars().take(32).collect::<String>();
+        let expected_hash = get_cache_hash(&config_data, &args.cache)?;
         if hash != expected_hash {
             println!(
                 "{} {}",
@@ -78,19 +70,7 @@ pub fn hash_and_update(
     config_data: &mut ConfigData,
     cache_file_path: &str,
 ) -> Result<String> {
-    let mut hasher = Sha256::new();
-
-    let cache_file = File::open(cache_file_path)?;
-    let mut reader = BufReader::new(cache_file);
-    let mut buffer = Vec::new();
-    // Read file into vector.
-    reader.read_to_end(&mut buffer)?;
-
-    hasher.update(&buffer);
-    let hash_base58 = bs58::encode(&hasher.finalize()).into_string();
-
-    let hash = hash_base58.chars().take(32).collect::<String>();
-    // Candy machine only allows for 32 characters so we truncate this hash.
+    let hash = get_cache_hash(config_data, cache_file_path)?;
     hidden_settings.set_hash(hash.clone());
     config_data.hidden_settings = Some(hidden_settings);
 
@@ -104,3 +84,33 @@ pub fn hash_and_update(
 
     Ok(hash)
 }
+
+/// Hash the cache file into the 32 character hidden settings hash. When the
+/// on-chain reveal is enabled this is the commitment the candy machine checks
+/// the revealed config lines against.
+pub fn get_cache_hash(config_data: &ConfigData, cache_file_path: &str) -> Result<String> {
+    if config_data.on_chain_reveal_enabled {
+        let cache = load_cache(cache_file_path, false)?;
+        let mut running_hash = [0; 32];
+        for config_line in cache.items.to_reveal_config_lines()? {
+            running_hash = hash_reveal_config_line(&running_hash, &config_line)?;
+        }
+        return Ok(String::from_utf8(
+            get_reveal_hash_commitment(&running_hash).to_vec(),
+        )?);
+    }
+
+    let mut hasher = Sha256::new();
+
+    let cache_file = File::open(cache_file_path)?;
+    let mut reader = BufReader::new(cache_file);
+    let mut buffer = Vec::new();
+    // Read file into vector.
+    reader.read_to_end(&mut buffer)?;
+
+    hasher.update(&buffer);
+    let hash_base58 = bs58::encode(&hasher.finalize()).into_string();
+
+    // Candy machine only allows for 32 characters so we truncate this hash.
+    Ok(hash_base58.chars().take(32).collect::<String>())
+}
@@ -5,11 +5,13 @@ pub mod clear_merkle_allowlist;
 pub mod cli;
 pub mod collections;
 pub mod common;
+pub mod compressed;
 pub mod config;
 pub mod constants;
 pub mod create_config;
 pub mod deploy;
 pub mod errors;
+pub mod freeze;
 pub mod hash;
 pub mod launch;
 pub mod merkle_allowlist;
@@ -17,10 +19,12 @@ pub mod mint;
 pub mod parse;
 pub mod pdas;
 pub mod program_errors;
+pub mod referrals;
 pub mod reveal;
 pub mod setup;
 pub mod show;
 pub mod sign;
+pub mod sweep_buyer_info;
 pub mod update;
 pub mod upload;
 pub mod utils;
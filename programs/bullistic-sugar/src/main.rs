@@ -15,21 +15,25 @@ use console::style;
 use bullistic_sugar_cli::{
     bundlr::{process_bundlr, BundlrArgs},
     clear_merkle_allowlist::{clear_merkle_allowlist, ClearMerkleAllowlistArgs},
-    cli::{Cli, CollectionSubcommands, Commands},
+    cli::{Cli, CollectionSubcommands, Commands, CompressedSubcommands, FreezeSubcommands},
     collections::{
         process_remove_collection, process_set_collection, RemoveCollectionArgs, SetCollectionArgs,
     },
+    compressed::{process_set_tree, SetTreeArgs},
     constants::{COMPLETE_EMOJI, ERROR_EMOJI},
     create_config::{process_create_config, CreateConfigArgs},
     deploy::{process_deploy, DeployArgs},
+    freeze::{process_thaw, ThawArgs},
     hash::{process_hash, HashArgs},
     launch::{process_launch, LaunchArgs},
     merkle_allowlist::{process_merkle_allowlist, ProcessMerkleAllowlistArgs},
     mint::{process_mint, MintArgs},
     parse::parse_sugar_errors,
+    referrals::{process_referrals, ReferralsArgs},
     reveal::{process_reveal, RevealArgs},
     show::{process_show, ShowArgs},
     sign::{process_sign, SignArgs},
+    sweep_buyer_info::{process_sweep_buyer_info, SweepBuyerInfoArgs},
     update::{process_update, UpdateArgs},
     upload::{process_upload, UploadArgs},
     validate::{process_validate, ValidateArgs},
@@ -182,6 +186,23 @@ async fn run() -> Result<()> {
                 candy_machine,
             })?,
         },
+        Commands::Compressed { command } => match command {
+            CompressedSubcommands::SetTree {
+                keypair,
+                rpc_url,
+                cache,
+                candy_machine,
+                max_depth,
+                max_buffer_size,
+            } => process_set_tree(SetTreeArgs {
+                keypair,
+                rpc_url,
+                cache,
+                candy_machine,
+                max_depth,
+                max_buffer_size,
+            })?,
+        },
         Commands::CreateConfig {
             config,
             keypair,
@@ -246,6 +267,26 @@ async fn run() -> Result<()> {
             })
             .await?
         }
+        Commands::Freeze { command } => match command {
+            FreezeSubcommands::Thaw {
+                keypair,
+                rpc_url,
+                cache,
+                candy_machine,
+                all,
+                nft_mint,
+            } => {
+                process_thaw(ThawArgs {
+                    keypair,
+                    rpc_url,
+                    cache,
+                    candy_machine,
+                    all,
+                    nft_mint,
+                })
+                .await?
+            }
+        },
         Commands::Hash {
             config,
             cache,
@@ -287,6 +328,9 @@ async fn run() -> Result<()> {
             number,
             receiver,
             candy_machine,
+            burn_nft,
+            referrer,
+            config,
         } => {
             process_mint(MintArgs {
                 keypair,
@@ -295,6 +339,9 @@ async fn run() -> Result<()> {
                 number,
                 receiver,
                 candy_machine,
+                burn_nft,
+                referrer,
+                config,
             })
             .await?
         }
@@ -325,6 +372,28 @@ async fn run() -> Result<()> {
             candy_machine,
             unminted,
         })?,
+        Commands::Referrals {
+            keypair,
+            rpc_url,
+            cache,
+            candy_machine,
+        } => process_referrals(ReferralsArgs {
+            keypair,
+            rpc_url,
+            cache,
+            candy_machine,
+        })?,
+        Commands::SweepBuyerInfo {
+            keypair,
+            rpc_url,
+            cache,
+            candy_machine,
+        } => process_sweep_buyer_info(SweepBuyerInfoArgs {
+            keypair,
+            rpc_url,
+            cache,
+            candy_machine,
+        })?,
         Commands::Update {
             config,
             keypair,
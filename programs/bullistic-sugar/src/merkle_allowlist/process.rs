@@ -10,6 +10,7 @@ use crate::{
     candy_machine::get_candy_machine_state,
     common::{setup_client, sugar_setup},
     merkle_allowlist::{chunk_root_list_for_update_txs, parse_merkle_allowlist_config},
+    pdas::get_roles_pda_for_signer,
 };
 
 #[derive(Debug)]
@@ -73,10 +74,12 @@ pub async fn process_merkle_allowlist(args: ProcessMerkleAllowlistArgs) -> Resul
     println!("Total transactions required: {}", tx_count);
     println!("\nStarting allowlist update...\n");
 
+    let roles_pda = get_roles_pda_for_signer(&candy_machine_state, &candy_pubkey, &program.payer());
     for roots_to_append in chunked_roots_to_add.iter() {
         let accounts = bullistic_candy_machine::accounts::AppendMerkleAllowlistRoots {
-            bullistic_authority: candy_machine_state.bullistic_authority,
+            bullistic_authority: program.payer(),
             candy_machine: candy_pubkey,
+            roles_pda,
         }
         .to_account_metas(None);
 
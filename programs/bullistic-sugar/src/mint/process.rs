@@ -14,12 +14,17 @@ use anyhow::Result;
 use chrono::Utc;
 use console::style;
 use bullistic_candy_machine::{
-    accounts as nft_accounts, cmp_pubkeys, instruction as nft_instruction, CandyError,
-    CandyMachine, CollectionPda, SplTokenAllowlistMode,
+    cmp_pubkeys, convert_usd_price_to_lamports, find_creator_overrides_pda,
+    find_drop_buyer_info_pda, find_referral_account_pda, find_refund_receipt_pda,
+    instruction as nft_instruction, load_price_feed, BurnToMintMode, CandyError, CandyMachine,
+    CollectionPda, SplTokenAllowlistMode,
 };
-use mpl_token_metadata::pda::find_collection_authority_account;
+use mpl_token_metadata::pda::{find_collection_authority_account, find_token_record_account};
 use solana_client::rpc_response::Response;
-use spl_associated_token_account::get_associated_token_address;
+use spl_account_compression::Noop;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
 use spl_token::{state::Account, ID as TOKEN_PROGRAM_ID};
 use tokio::sync::Semaphore;
 
@@ -32,6 +37,9 @@ use crate::{
     utils::*,
 };
 
+// Slippage allowed on the SOL price of candy machines priced in USD.
+const USD_PRICE_SLIPPAGE_BASIS_POINTS: u64 = 100;
+
 pub struct MintArgs {
     pub keypair: Option<String>,
     pub rpc_url: Option<String>,
@@ -39,6 +47,9 @@ pub struct MintArgs {
     pub number: Option<u64>,
     pub receiver: Option<String>,
     pub candy_machine: Option<String>,
+    pub burn_nft: Option<String>,
+    pub referrer: Option<String>,
+    pub config: String,
 }
 
 pub async fn process_mint(args: MintArgs) -> Result<()> {
@@ -94,7 +105,31 @@ pub async fn process_mint(args: MintArgs) -> Result<()> {
     };
     println!("\nMinting to {}", &receiver_pubkey);
 
+    let burn_nft = match args.burn_nft {
+        Some(burn_nft) => Some(
+            Pubkey::from_str(&burn_nft)
+                .map_err(|_| anyhow!("Failed to parse NFT mint to burn: {}", burn_nft))?,
+        ),
+        None => None,
+    };
+
+    let referrer = match args.referrer {
+        Some(referrer) => Some(
+            Pubkey::from_str(&referrer)
+                .map_err(|_| anyhow!("Failed to parse referrer pubkey: {}", referrer))?,
+        ),
+        None => None,
+    };
+
+    let bot_signer_authority = Arc::new(get_bot_signer_keypair(&args.config)?);
+
     let number = args.number.unwrap_or(1);
+
+    if burn_nft.is_some() && number > 1 {
+        let error = anyhow!("Only one item can be minted when burning an NFT");
+        error!("{:?}", error);
+        return Err(error);
+    }
     let available = candy_machine_state.data.items_available - candy_machine_state.items_redeemed;
 
     if number > available || number == 0 {
@@ -119,6 +154,10 @@ pub async fn process_mint(args: MintArgs) -> Result<()> {
             candy_pubkey,
             Arc::clone(&candy_machine_state),
             Arc::clone(&collection_pda_info),
+            Arc::clone(&bot_signer_authority),
+            receiver_pubkey,
+            burn_nft,
+            referrer,
         )
         .await
         {
@@ -143,6 +182,7 @@ pub async fn process_mint(args: MintArgs) -> Result<()> {
             let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
             let candy_machine_state = candy_machine_state.clone();
             let collection_pda_info = collection_pda_info.clone();
+            let bot_signer_authority = bot_signer_authority.clone();
             let pb = pb.clone();
 
             // Start tasks
@@ -153,6 +193,10 @@ pub async fn process_mint(args: MintArgs) -> Result<()> {
                     candy_pubkey,
                     candy_machine_state,
                     collection_pda_info,
+                    bot_signer_authority,
+                    receiver_pubkey,
+                    None,
+                    referrer,
                 )
                 .await;
                 pb.inc(1);
@@ -196,10 +240,21 @@ pub async fn mint(
     candy_machine_id: Pubkey,
     candy_machine_state: Arc<CandyMachine>,
     collection_pda_info: Arc<Option<PdaInfo<CollectionPda>>>,
+    bot_signer_authority: Arc<Keypair>,
+    receiver: Pubkey,
+    burn_nft: Option<Pubkey>,
+    referrer: Option<Pubkey>,
 ) -> Result<Signature> {
     let client = setup_client(&config)?;
     let program = client.program(CANDY_MACHINE_ID);
     let buyer = program.payer();
+    // The buyer pays for the mint, the receiver gets the NFT and is the one
+    // checked against the allowlist and the limit per address.
+    let recipient = if receiver != buyer {
+        Some(receiver)
+    } else {
+        None
+    };
     let treasury_wallet = candy_machine_state.treasury_wallet;
 
     let candy_machine_data = &candy_machine_state.data;
@@ -208,6 +263,12 @@ pub async fn mint(
         return Err(anyhow!(CandyError::CandyMachineEmpty));
     }
 
+    if recipient.is_some() && candy_machine_data.limit_per_address > 0 {
+        return Err(anyhow!(
+            "Cannot mint to another receiver when there is a limit per address, the receiver has to sign."
+        ));
+    }
+
     if candy_machine_state.bullistic_authority != buyer {
         // Apply validation for regular buyers.
         // TODO[@]: Make this more consistent with omni_mint_wallets and mint_phase logic.
@@ -224,14 +285,24 @@ pub async fn mint(
     }
 
     let nft_mint = Keypair::new();
+    // Compressed NFTs are leaves of the candy machine's merkle tree, without a
+    // mint, metadata or master edition account.
+    let is_compressed = candy_machine_state.is_compressed();
     let metaplex_program_id = Pubkey::from_str(METAPLEX_PROGRAM_ID)?;
 
     let mut additional_accounts: Vec<AccountMeta> = Vec::new();
 
     // Check SPL token allowlist settings
     if let Some(spl_token_allowlist_settings) = &candy_machine_data.spl_token_allowlist_settings {
-        let allowlist_token_account =
-            get_associated_token_address(&buyer, &spl_token_allowlist_settings.mint);
+        let allowlist_token_program = program
+            .rpc()
+            .get_account(&spl_token_allowlist_settings.mint)?
+            .owner;
+        let allowlist_token_account = get_associated_token_address_with_program_id(
+            &receiver,
+            &spl_token_allowlist_settings.mint,
+            &allowlist_token_program,
+        );
 
         additional_accounts.push(AccountMeta {
             pubkey: allowlist_token_account,
@@ -240,19 +311,25 @@ pub async fn mint(
         });
 
         if spl_token_allowlist_settings.mode == SplTokenAllowlistMode::BurnEveryTime {
+            if recipient.is_some() {
+                return Err(anyhow!(
+                    "Cannot mint to another receiver when the allowlist token is burned, the receiver has to sign."
+                ));
+            }
+
             let mut token_found = false;
 
             match program.rpc().get_account_data(&allowlist_token_account) {
                 Ok(ata_data) => {
-                    if !ata_data.is_empty() {
-                        let account = Account::unpack_unchecked(&ata_data)?;
+                    // Token-2022 accounts share the base layout, extensions follow it.
+                    if ata_data.len() >= Account::LEN {
+                        let account = Account::unpack_unchecked(&ata_data[..Account::LEN])?;
 
                         if account.amount > 0 {
-                            additional_accounts.push(AccountMeta {
-                                pubkey: spl_token_allowlist_settings.mint,
-                                is_signer: false,
-                                is_writable: true,
-                            });
+                            additional_accounts.extend([
+                                AccountMeta::new(spl_token_allowlist_settings.mint, false),
+                                AccountMeta::new_readonly(allowlist_token_program, false),
+                            ]);
 
                             token_found = true;
                         }
@@ -274,13 +351,31 @@ pub async fn mint(
     }
 
     if let Some(treasury_mint) = candy_machine_state.treasury_mint {
-        let user_token_account_info = get_associated_token_address(&buyer, &treasury_mint);
+        let treasury_token_program = program.rpc().get_account(&treasury_mint)?.owner;
+        let user_token_account_info = get_associated_token_address_with_program_id(
+            &buyer,
+            &treasury_mint,
+            &treasury_token_program,
+        );
+
+        additional_accounts.extend([
+            AccountMeta::new(user_token_account_info, false),
+            AccountMeta::new_readonly(treasury_mint, false),
+            AccountMeta::new_readonly(treasury_token_program, false),
+        ]);
+    }
 
-        additional_accounts.push(AccountMeta {
-            pubkey: user_token_account_info,
-            is_signer: false,
-            is_writable: true,
-        });
+    if let Some((collection_pda_pubkey, collection_pda)) = collection_pda_info.as_ref() {
+        let collection_authority_record =
+            find_collection_authority_account(&collection_pda.mint, collection_pda_pubkey).0;
+
+        additional_accounts.extend([
+            AccountMeta::new(*collection_pda_pubkey, false),
+            AccountMeta::new_readonly(collection_pda.mint, false),
+            AccountMeta::new(find_metadata_pda(&collection_pda.mint), false),
+            AccountMeta::new_readonly(find_master_edition_pda(&collection_pda.mint), false),
+            AccountMeta::new_readonly(collection_authority_record, false),
+        ]);
     }
 
     let metadata_pda = find_metadata_pda(&nft_mint.pubkey());
@@ -288,22 +383,135 @@ pub async fn mint(
     let (candy_machine_creator_pda, creator_bump) =
         find_candy_machine_creator_pda(&candy_machine_id);
 
-    let bot_signer_authority = get_bot_signer_keypair();
+    if let Some(open_edition_settings) = &candy_machine_data.open_edition_settings {
+        let master_edition_mint = open_edition_settings.master_edition_mint;
+        let edition = candy_machine_state.items_redeemed + 1;
+
+        additional_accounts.extend([
+            AccountMeta::new(find_metadata_pda(&master_edition_mint), false),
+            AccountMeta::new(find_master_edition_pda(&master_edition_mint), false),
+            AccountMeta::new_readonly(
+                get_associated_token_address(&candy_machine_creator_pda, &master_edition_mint),
+                false,
+            ),
+            AccountMeta::new(
+                find_edition_marker_pda(&master_edition_mint, edition),
+                false,
+            ),
+        ]);
+    }
+
+    if let Some(burn_to_mint_settings) = &candy_machine_data.burn_to_mint_settings {
+        match burn_nft {
+            Some(burn_nft_mint) => {
+                if recipient.is_some() {
+                    return Err(anyhow!(
+                        "Cannot mint to another receiver when burning an NFT, the receiver has to sign."
+                    ));
+                }
+
+                additional_accounts.extend([
+                    AccountMeta::new(find_metadata_pda(&burn_nft_mint), false),
+                    AccountMeta::new(burn_nft_mint, false),
+                    AccountMeta::new(get_associated_token_address(&buyer, &burn_nft_mint), false),
+                    AccountMeta::new(find_master_edition_pda(&burn_nft_mint), false),
+                    AccountMeta::new(
+                        find_metadata_pda(&burn_to_mint_settings.collection_mint),
+                        false,
+                    ),
+                ]);
+            }
+            None if burn_to_mint_settings.mode == BurnToMintMode::ReplacesPayment => {
+                return Err(anyhow!(
+                    "This candy machine burns an NFT for every mint, use --burn-nft to pick it."
+                ));
+            }
+            None => (),
+        }
+    }
+
+    if is_compressed {
+        let (compressed_tree_pda, compressed_tree) =
+            get_compressed_tree_pda(&candy_machine_id, &program)?;
+        let (tree_authority, _) = Pubkey::find_program_address(
+            &[compressed_tree.merkle_tree.as_ref()],
+            &mpl_bubblegum::id(),
+        );
+
+        additional_accounts.extend([
+            AccountMeta::new_readonly(compressed_tree_pda, false),
+            AccountMeta::new(tree_authority, false),
+            AccountMeta::new(compressed_tree.merkle_tree, false),
+            AccountMeta::new_readonly(Noop::id(), false),
+            AccountMeta::new_readonly(spl_account_compression::id(), false),
+            AccountMeta::new_readonly(mpl_bubblegum::id(), false),
+        ]);
+    }
+
+    if let Some(programmable_nft_settings) = &candy_machine_data.programmable_nft_settings {
+        let (token_record_pda, _) = find_token_record_account(
+            &nft_mint.pubkey(),
+            &get_associated_token_address(&receiver, &nft_mint.pubkey()),
+        );
+        additional_accounts.push(AccountMeta::new(token_record_pda, false));
+
+        if let Some(rule_set) = programmable_nft_settings.rule_set {
+            additional_accounts.extend([
+                AccountMeta::new_readonly(Pubkey::from_str(TOKEN_AUTH_RULES_PROGRAM_ID)?, false),
+                AccountMeta::new_readonly(rule_set, false),
+            ]);
+        }
+    }
+
+    if candy_machine_data.creator_overrides_enabled {
+        let (creator_overrides_pda, _) = find_creator_overrides_pda(&candy_machine_id);
+        additional_accounts.push(AccountMeta::new_readonly(creator_overrides_pda, false));
+    }
+
+    if let Some(usd_price_settings) = &candy_machine_data.usd_price_settings {
+        additional_accounts.push(AccountMeta::new_readonly(
+            usd_price_settings.price_feed,
+            false,
+        ));
+    }
+
+    if candy_machine_data.min_items_to_succeed.is_some() {
+        let (refund_receipt_pda, _) =
+            find_refund_receipt_pda(&candy_machine_id, &nft_mint.pubkey());
+        additional_accounts.push(AccountMeta::new(refund_receipt_pda, false));
+    }
+
+    if let Some(drop) = candy_machine_data.drop {
+        let (drop_buyer_info_pda, _) = find_drop_buyer_info_pda(&drop, &receiver);
+        additional_accounts.extend([
+            AccountMeta::new(drop, false),
+            AccountMeta::new(drop_buyer_info_pda, false),
+        ]);
+    }
+
+    // The bot tax destination is always passed last.
+    if let Some(destination) = candy_machine_data
+        .bot_tax_settings
+        .as_ref()
+        .and_then(|bot_tax_settings| bot_tax_settings.destination)
+    {
+        additional_accounts.push(AccountMeta::new(destination, false));
+    }
 
     let (buyer_info_account, buyer_info_account_bump) =
-        find_buyer_info_account_pda(&candy_machine_id, &buyer);
+        find_buyer_info_account_pda(&candy_machine_id, &receiver);
 
-    let buyer_token_account = get_associated_token_address(&buyer, &nft_mint.pubkey());
+    let buyer_token_account = get_associated_token_address(&receiver, &nft_mint.pubkey());
 
     let mut accounts = bullistic_candy_machine::accounts::MintNFT {
         candy_machine: candy_machine_id,
         candy_machine_creator: candy_machine_creator_pda,
         buyer,
         treasury_wallet,
-        metadata: metadata_pda,
-        mint: nft_mint.pubkey(),
+        metadata: (!is_compressed).then_some(metadata_pda),
+        mint: (!is_compressed).then(|| nft_mint.pubkey()),
         creator_authority: candy_machine_state.creator_authority,
-        master_edition: master_edition_pda,
+        master_edition: (!is_compressed).then_some(master_edition_pda),
         token_metadata_program: metaplex_program_id,
         token_program: TOKEN_PROGRAM_ID,
         system_program: system_program::id(),
@@ -314,20 +522,34 @@ pub async fn mint(
         bot_signer_authority: bot_signer_authority.pubkey(),
         buyer_token_account,
         ata_program: AssociatedToken::id(),
+        recipient,
+        referrer,
+        referral_account: referrer
+            .map(|referrer| find_referral_account_pda(&candy_machine_id, &referrer).0),
     }
     .to_account_metas(None);
 
     let bot_signer_authority_should_sign = candy_machine_state.data.bot_protection_enabled;
     if bot_signer_authority_should_sign {
         for account in accounts.iter_mut() {
-            if cmp_pubkeys(&account.pubkey, &get_bot_signer_keypair().pubkey()) {
+            if cmp_pubkeys(&account.pubkey, &bot_signer_authority.pubkey()) {
                 account.is_signer = true;
             }
         }
     }
 
     let mint_phase = CandyMachine::get_mint_phase(&candy_machine_state, Utc::now().timestamp());
-    let mint_price = CandyMachine::get_mint_price(&candy_machine_state, &mint_phase);
+    let mut mint_price = CandyMachine::get_mint_price(&candy_machine_state, &mint_phase);
+    if let Some(usd_price_settings) = &candy_machine_state.data.usd_price_settings {
+        // The price is in USD, so cap what we pay at the current SOL price plus
+        // some slippage for price feed updates before the mint lands.
+        let price_feed_data = program
+            .rpc()
+            .get_account_data(&usd_price_settings.price_feed)?;
+        let price_feed_price = load_price_feed(&price_feed_data)?;
+        let lamports = convert_usd_price_to_lamports(mint_price, &price_feed_price)?;
+        mint_price = lamports + lamports * USD_PRICE_SLIPPAGE_BASIS_POINTS / 10000;
+    }
 
     let mut mint_ix = program
         .request()
@@ -345,50 +567,34 @@ pub async fn mint(
     }
     let mint_ix = mint_ix.instructions()?;
 
-    let mut builder = program
-        .request()
-        .instruction(mint_ix[0].clone())
-        .signer(&nft_mint);
-
-    if bot_signer_authority_should_sign {
-        builder = builder.signer(&bot_signer_authority);
+    let mut builder = program.request().instruction(mint_ix[0].clone());
+    if !is_compressed {
+        builder = builder.signer(&nft_mint);
     }
 
-    if let Some((collection_pda_pubkey, collection_pda)) = collection_pda_info.as_ref() {
-        let collection_authority_record =
-            find_collection_authority_account(&collection_pda.mint, collection_pda_pubkey).0;
-        builder = builder
-            .accounts(nft_accounts::SetCollectionDuringMint {
-                candy_machine: candy_machine_id,
-                metadata: metadata_pda,
-                buyer,
-                creator_authority: candy_machine_state.creator_authority,
-                collection_pda: *collection_pda_pubkey,
-                token_metadata_program: mpl_token_metadata::ID,
-                instruction_sysvar_account: sysvar::instructions::ID,
-                collection_mint: collection_pda.mint,
-                collection_metadata: find_metadata_pda(&collection_pda.mint),
-                collection_master_edition: find_master_edition_pda(&collection_pda.mint),
-                collection_authority_record,
-            })
-            .args(nft_instruction::SetCollectionDuringMint {});
+    if bot_signer_authority_should_sign {
+        builder = builder.signer(bot_signer_authority.as_ref());
     }
 
     let sig = builder.send()?;
 
-    if let Err(_) | Ok(Response { value: None, .. }) = program
-        .rpc()
-        .get_account_with_commitment(&metadata_pda, CommitmentConfig::confirmed())
-    {
-        let cluster_param = match get_cluster(program.rpc()).unwrap_or(Cluster::Mainnet) {
-            Cluster::Devnet => "?devnet",
-            _ => "",
-        };
-        return Err(anyhow!(
-            "Minting most likely failed with a bot tax. Check the transaction link for more details: https://explorer.solana.com/tx/{}{}",
-            sig.to_string(),
-            cluster_param,
-        ));
+    // A bot tax leaves the metadata uncreated. Compressed NFTs have no
+    // metadata account to look up.
+    if !is_compressed {
+        if let Err(_) | Ok(Response { value: None, .. }) = program
+            .rpc()
+            .get_account_with_commitment(&metadata_pda, CommitmentConfig::confirmed())
+        {
+            let cluster_param = match get_cluster(program.rpc()).unwrap_or(Cluster::Mainnet) {
+                Cluster::Devnet => "?devnet",
+                _ => "",
+            };
+            return Err(anyhow!(
+                "Minting most likely failed with a bot tax. Check the transaction link for more details: https://explorer.solana.com/tx/{}{}",
+                sig.to_string(),
+                cluster_param,
+            ));
+        }
     }
 
     info!("Minted! TxId: {}", sig);
@@ -1,9 +1,15 @@
 use anchor_client::{solana_sdk::pubkey::Pubkey, ClientError, Program};
 use anyhow::{anyhow, Result};
-use bullistic_candy_machine::{CollectionPda, BUYER_INFO_ACCOUNT_PREFIX};
+use bullistic_candy_machine::{
+    find_compressed_tree_pda, find_roles_pda, CandyMachine, CollectionPda, CompressedTree,
+    FreezePda, RevealPda, BUYER_INFO_ACCOUNT_PREFIX,
+};
 use mpl_token_metadata::{
     pda::{find_master_edition_account, find_metadata_account},
-    state::{Key, MasterEditionV2, Metadata, TokenMetadataAccount, MAX_MASTER_EDITION_LEN},
+    state::{
+        Key, MasterEditionV2, Metadata, TokenMetadataAccount, EDITION, EDITION_MARKER_BIT_SIZE,
+        MAX_MASTER_EDITION_LEN, PREFIX,
+    },
     utils::try_from_slice_checked,
 };
 
@@ -70,6 +76,22 @@ pub fn get_master_edition_pda(
         })
 }
 
+pub fn find_edition_marker_pda(master_edition_mint: &Pubkey, edition: u64) -> Pubkey {
+    let edition_marker_number = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    let (pda, _bump) = Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            mpl_token_metadata::ID.as_ref(),
+            master_edition_mint.as_ref(),
+            EDITION.as_bytes(),
+            edition_marker_number.as_bytes(),
+        ],
+        &mpl_token_metadata::ID,
+    );
+
+    pda
+}
+
 pub fn find_candy_machine_creator_pda(candy_machine_id: &Pubkey) -> (Pubkey, u8) {
     // Derive metadata account
     let creator_seeds = &["candy_machine".as_bytes(), candy_machine_id.as_ref()];
@@ -101,6 +123,49 @@ pub fn get_collection_pda(
         })
 }
 
+pub fn get_compressed_tree_pda(
+    candy_machine: &Pubkey,
+    program: &Program,
+) -> Result<PdaInfo<CompressedTree>> {
+    let compressed_tree_pubkey = find_compressed_tree_pda(candy_machine).0;
+    program
+        .account(compressed_tree_pubkey)
+        .map(|t| (compressed_tree_pubkey, t))
+        .map_err(|e| match e {
+            ClientError::AccountNotFound => anyhow!("Candy Machine compressed tree is not set!"),
+            _ => anyhow!(
+                "Failed to deserialize compressed tree PDA account: {}",
+                &compressed_tree_pubkey.to_string()
+            ),
+        })
+}
+
+pub fn find_reveal_pda(candy_machine_id: &Pubkey) -> (Pubkey, u8) {
+    let reveal_seeds = &[RevealPda::PREFIX.as_bytes(), candy_machine_id.as_ref()];
+
+    Pubkey::find_program_address(reveal_seeds, &CANDY_MACHINE_ID)
+}
+
+pub fn find_freeze_pda(candy_machine_id: &Pubkey) -> (Pubkey, u8) {
+    let freeze_seeds = &[FreezePda::PREFIX.as_bytes(), candy_machine_id.as_ref()];
+
+    Pubkey::find_program_address(freeze_seeds, &CANDY_MACHINE_ID)
+}
+
+pub fn get_reveal_pda(candy_machine: &Pubkey, program: &Program) -> Result<PdaInfo<RevealPda>> {
+    let reveal_pda_pubkey = find_reveal_pda(candy_machine).0;
+    program
+        .account(reveal_pda_pubkey)
+        .map(|r| (reveal_pda_pubkey, r))
+        .map_err(|e| match e {
+            ClientError::AccountNotFound => anyhow!("Candy Machine reveal PDA is not initialized!"),
+            _ => anyhow!(
+                "Failed to deserialize reveal PDA account: {}",
+                &reveal_pda_pubkey.to_string()
+            ),
+        })
+}
+
 pub fn find_buyer_info_account_pda(candy_machine: &Pubkey, buyer: &Pubkey) -> (Pubkey, u8) {
     let seeds = &[
         BUYER_INFO_ACCOUNT_PREFIX.as_bytes(),
@@ -109,3 +174,17 @@ pub fn find_buyer_info_account_pda(candy_machine: &Pubkey, buyer: &Pubkey) -> (P
     ];
     Pubkey::find_program_address(seeds, &bullistic_candy_machine::id())
 }
+
+/// Signers other than the candy machine authority act through their role in
+/// the roles PDA.
+pub fn get_roles_pda_for_signer(
+    candy_machine_state: &CandyMachine,
+    candy_machine: &Pubkey,
+    signer: &Pubkey,
+) -> Option<Pubkey> {
+    if candy_machine_state.bullistic_authority == *signer {
+        None
+    } else {
+        Some(find_roles_pda(candy_machine).0)
+    }
+}
@@ -0,0 +1,121 @@
+use anchor_client::solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    native_token::LAMPORTS_PER_SOL,
+};
+use anchor_lang::Discriminator;
+use bullistic_candy_machine::ReferralAccount;
+use console::style;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+
+use crate::{cache::load_cache, candy_machine::CANDY_MACHINE_ID, common::*, utils::*};
+
+pub struct ReferralsArgs {
+    pub keypair: Option<String>,
+    pub rpc_url: Option<String>,
+    pub cache: String,
+    pub candy_machine: Option<String>,
+}
+
+pub fn process_referrals(args: ReferralsArgs) -> Result<()> {
+    println!(
+        "{} {}Looking up referrals",
+        style("[1/1]").bold().dim(),
+        LOOKING_GLASS_EMOJI
+    );
+
+    let pb = spinner_with_style();
+    pb.set_message("Connecting...");
+
+    // the candy machine id specified takes precedence over the one from the cache
+
+    let candy_machine_id = if let Some(candy_machine) = args.candy_machine {
+        candy_machine
+    } else {
+        let cache = load_cache(&args.cache, false)?;
+        cache.program.candy_machine
+    };
+
+    let candy_machine_id = match Pubkey::from_str(&candy_machine_id) {
+        Ok(candy_machine_id) => candy_machine_id,
+        Err(_) => {
+            let error = anyhow!("Failed to parse candy machine id: {}", candy_machine_id);
+            error!("{:?}", error);
+            return Err(error);
+        }
+    };
+
+    let sugar_config = sugar_setup(args.keypair, args.rpc_url)?;
+    let client = setup_client(&sugar_config)?;
+    let program = client.program(CANDY_MACHINE_ID);
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new(
+                0,
+                MemcmpEncodedBytes::Base58(
+                    bs58::encode(ReferralAccount::discriminator()).into_string(),
+                ),
+            )),
+            RpcFilterType::Memcmp(Memcmp::new(
+                8, // candy machine, after the discriminator
+                MemcmpEncodedBytes::Base58(candy_machine_id.to_string()),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            min_context_slot: None,
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(CommitmentConfig {
+                commitment: CommitmentLevel::Confirmed,
+            }),
+        },
+        with_context: None,
+    };
+
+    let accounts = program
+        .rpc()
+        .get_program_accounts_with_config(&program.id(), config)?;
+
+    let mut referral_accounts = accounts
+        .iter()
+        .map(|(_pubkey, account)| ReferralAccount::try_deserialize(&mut account.data.as_slice()))
+        .collect::<std::result::Result<Vec<ReferralAccount>, _>>()?;
+    referral_accounts.sort_by(|a, b| b.total_earned.cmp(&a.total_earned));
+
+    pb.finish_and_clear();
+
+    let total_earned: u64 = referral_accounts.iter().map(|r| r.total_earned).sum();
+    let total_claimed: u64 = referral_accounts.iter().map(|r| r.total_claimed).sum();
+
+    println!(
+        "\nFound {} referrers, total earned: ◎ {}, unclaimed: ◎ {}",
+        referral_accounts.len(),
+        total_earned as f64 / LAMPORTS_PER_SOL as f64,
+        (total_earned - total_claimed) as f64 / LAMPORTS_PER_SOL as f64
+    );
+
+    if !referral_accounts.is_empty() {
+        println!(
+            "\n{:48} {:>8} {:>14} {:>14}",
+            "Referrer", "Mints", "Earned", "Unclaimed"
+        );
+        println!("{:-<87}", "-");
+
+        for referral_account in referral_accounts {
+            println!(
+                "{:48} {:>8} {:>14.8} {:>14.8}",
+                referral_account.referrer.to_string(),
+                referral_account.number_referred,
+                referral_account.total_earned as f64 / LAMPORTS_PER_SOL as f64,
+                (referral_account.total_earned - referral_account.total_claimed) as f64
+                    / LAMPORTS_PER_SOL as f64
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
 
-use anchor_client::solana_sdk::account::Account;
-use anchor_lang::AnchorDeserialize;
+use anchor_client::solana_sdk::{account::Account, instruction::Instruction};
+use anchor_lang::{AnchorDeserialize, InstructionData, ToAccountMetas};
 use console::style;
 use futures::future::join_all;
 use mpl_token_metadata::{
@@ -19,7 +19,8 @@ use crate::{
     candy_machine::CANDY_MACHINE_ID,
     common::*,
     config::{get_config_data, Cluster},
-    pdas::{find_candy_machine_creator_pda, find_metadata_pda},
+    deploy::{MAX_TRANSACTION_BYTES, MAX_TRANSACTION_LINES},
+    pdas::{find_candy_machine_creator_pda, find_metadata_pda, find_reveal_pda, get_reveal_pda},
     utils::*,
 };
 
@@ -87,6 +88,12 @@ pub async fn process_reveal(args: RevealArgs) -> Result<()> {
 
     spinner.finish_with_message("Done");
 
+    // With the on-chain reveal, the config lines are uploaded to the reveal PDA
+    // and verified against the hidden settings hash before any NFT is revealed.
+    if config.on_chain_reveal_enabled {
+        upload_reveal_config_lines(&program, &candy_machine_id, &cache)?;
+    }
+
     println!(
         "\n{} {}Getting minted NFTs for candy machine {}",
         style("[2/4]").bold().dim(),
@@ -202,10 +209,17 @@ pub async fn process_reveal(args: RevealArgs) -> Result<()> {
     );
 
     let pattern = regex::Regex::new(r"#([0-9]+)").expect("Failed to create regex pattern.");
+    let (candy_machine_creator, _) = find_candy_machine_creator_pda(&candy_machine_id);
 
     let spinner = spinner_with_style();
     spinner.set_message("Setting up transactions...");
     for m in metadata {
+        // NFTs revealed on-chain no longer have the candy machine creator as
+        // their update authority.
+        if config.on_chain_reveal_enabled && m.update_authority != candy_machine_creator {
+            continue;
+        }
+
         let name = m.data.name.trim_matches(char::from(0)).to_string();
         let capture = pattern
             .captures(&name)
@@ -235,7 +249,7 @@ pub async fn process_reveal(args: RevealArgs) -> Result<()> {
     let reveal_results = Arc::new(Mutex::new(Vec::new()));
     let mut tx_tasks = Vec::new();
 
-    let pb = progress_bar_with_style(metadata_pubkeys.len() as u64);
+    let pb = progress_bar_with_style(update_values.len() as u64);
     pb.set_message("Updating NFTs... ");
 
     for item in update_values {
@@ -244,6 +258,7 @@ pub async fn process_reveal(args: RevealArgs) -> Result<()> {
         let keypair = keypair.clone();
         let reveal_results = reveal_results.clone();
         let pb = pb.clone();
+        let on_chain_reveal_enabled = config.on_chain_reveal_enabled;
 
         tx_tasks.push(tokio::spawn(async move {
             // Move permit into the closure so it is dropped when the task is dropped.
@@ -254,7 +269,13 @@ pub async fn process_reveal(args: RevealArgs) -> Result<()> {
                 result: RevealResult::Success,
             };
 
-            match update_metadata_value(client, keypair, item).await {
+            let result = if on_chain_reveal_enabled {
+                reveal_nft_on_chain(client, keypair, candy_machine_id, item).await
+            } else {
+                update_metadata_value(client, keypair, item).await
+            };
+
+            match result {
                 Ok(_) => reveal_results.lock().unwrap().push(tx),
                 Err(e) => {
                     tx.result = RevealResult::Failure(e.to_string());
@@ -343,6 +364,110 @@ async fn update_metadata_value(
     Ok(())
 }
 
+/// Initialize the reveal PDA if needed and upload the config lines it is
+/// missing, in mint order.
+fn upload_reveal_config_lines(
+    program: &Program,
+    candy_machine_id: &Pubkey,
+    cache: &Cache,
+) -> Result<()> {
+    let (reveal_pda, _) = find_reveal_pda(candy_machine_id);
+    let lines_uploaded = match get_reveal_pda(candy_machine_id, program) {
+        Ok((_, reveal)) if reveal.verified => return Ok(()),
+        Ok((_, reveal)) => reveal.lines_uploaded as usize,
+        Err(_) => {
+            program
+                .request()
+                .accounts(nft_accounts::InitializeReveal {
+                    candy_machine: *candy_machine_id,
+                    bullistic_authority: program.payer(),
+                    reveal_pda,
+                    system_program: system_program::id(),
+                })
+                .args(nft_instruction::InitializeReveal {})
+                .send()?;
+            0
+        }
+    };
+
+    let config_lines = cache.items.to_reveal_config_lines()?;
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut tx_size = 0;
+
+    for config_line in config_lines.into_iter().skip(lines_uploaded) {
+        let size = (2 * STRING_LEN_SIZE) + config_line.name.len() + config_line.uri.len();
+        if (tx_size + size) > MAX_TRANSACTION_BYTES || current.len() == MAX_TRANSACTION_LINES {
+            chunks.push(current);
+            current = Vec::new();
+            tx_size = 0;
+        }
+        tx_size += size;
+        current.push(config_line);
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    let pb = progress_bar_with_style(chunks.len() as u64);
+    pb.set_message("Uploading reveal config lines... ");
+
+    let mut index = lines_uploaded;
+    for chunk in chunks {
+        let chunk_len = chunk.len();
+        program
+            .request()
+            .accounts(nft_accounts::AddRevealConfigLines {
+                candy_machine: *candy_machine_id,
+                bullistic_authority: program.payer(),
+                reveal_pda,
+                system_program: system_program::id(),
+            })
+            .args(nft_instruction::AddRevealConfigLines {
+                index: index as u32,
+                config_lines: chunk,
+            })
+            .send()?;
+        index += chunk_len;
+        pb.inc(1);
+    }
+    pb.finish();
+
+    Ok(())
+}
+
+async fn reveal_nft_on_chain(
+    client: Arc<RpcClient>,
+    payer: Arc<Keypair>,
+    candy_machine_id: Pubkey,
+    value: MetadataUpdateValues,
+) -> Result<(), ClientError> {
+    let ix = Instruction {
+        program_id: CANDY_MACHINE_ID,
+        accounts: nft_accounts::RevealNft {
+            candy_machine: candy_machine_id,
+            candy_machine_creator: find_candy_machine_creator_pda(&candy_machine_id).0,
+            reveal_pda: find_reveal_pda(&candy_machine_id).0,
+            metadata: value.metadata_pubkey,
+            token_metadata_program: TOKEN_METADATA_PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: nft_instruction::RevealNft {}.data(),
+    };
+
+    let recent_blockhash = client.get_latest_blockhash()?;
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&*payer],
+        recent_blockhash,
+    );
+
+    client.send_and_confirm_transaction(&tx)?;
+
+    Ok(())
+}
+
 fn increment_key(key: &str) -> String {
     (key.parse::<u32>()
         .expect("Key parsing out of bounds for u32.")
@@ -5,12 +5,20 @@ use anyhow::Result;
 use chrono::NaiveDateTime;
 use console::style;
 use bullistic_candy_machine::{
-    constants::{CONFIG_ARRAY_START, CONFIG_LINE_SIZE},
+    constants::{
+        COMPRESSED_FEATURE_INDEX, CONFIG_ARRAY_START, CONFIG_LINE_SIZE, USD_PRICE_DECIMALS,
+    },
     utils::is_feature_active,
-    SplTokenAllowlistMode,
+    MintOrder, SplTokenAllowlistMode,
 };
 
-use crate::{cache::load_cache, candy_machine::*, common::*, pdas::get_collection_pda, utils::*};
+use crate::{
+    cache::load_cache,
+    candy_machine::*,
+    common::*,
+    pdas::{get_collection_pda, get_compressed_tree_pda},
+    utils::*,
+};
 
 pub struct ShowArgs {
     pub keypair: Option<String>,
@@ -93,6 +101,15 @@ pub fn process_show(args: ShowArgs) -> Result<()> {
         None => print_with_style("", "collection mint", "none".to_string()),
     };
 
+    if is_feature_active(&candy_data.uuid, COMPRESSED_FEATURE_INDEX) {
+        match get_compressed_tree_pda(&candy_machine_id, &program) {
+            Ok((_, compressed_tree)) => {
+                print_with_style("", "merkle tree", compressed_tree.merkle_tree.to_string())
+            }
+            Err(_) => print_with_style("", "merkle tree", "none".to_string()),
+        };
+    }
+
     if let Some(treasury_mint) = candy_state.treasury_mint {
         print_with_style("", "spl token", treasury_mint.to_string());
     } else {
@@ -108,15 +125,20 @@ pub fn process_show(args: ShowArgs) -> Result<()> {
     );
 
     print_with_style("", "uuid", candy_data.uuid.to_string());
-    print_with_style(
-        "",
-        "price",
+    let price = if candy_data.usd_price_settings.is_some() {
+        format!(
+            "$ {} ({})",
+            candy_data.price as f64 / 10u64.pow(USD_PRICE_DECIMALS) as f64,
+            candy_data.price
+        )
+    } else {
         format!(
             "◎ {} ({})",
             candy_data.price as f64 / LAMPORTS_PER_SOL as f64,
             candy_data.price
-        ),
-    );
+        )
+    };
+    print_with_style("", "price", price);
     print_with_style("", "symbol", candy_data.symbol.to_string());
     print_with_style(
         "",
@@ -167,6 +189,197 @@ pub fn process_show(args: ShowArgs) -> Result<()> {
     } else {
         print_with_style("", "hidden settings", "none".to_string());
     }
+    print_with_style(
+        "",
+        "on-chain reveal enabled",
+        candy_data.on_chain_reveal_enabled.to_string(),
+    );
+    print_with_style(
+        "",
+        "voucher signer",
+        candy_data
+            .voucher_signer
+            .map_or("none".to_string(), |voucher_signer| {
+                voucher_signer.to_string()
+            }),
+    );
+
+    // open edition settings
+    if let Some(open_edition_settings) = candy_data.open_edition_settings {
+        print_with_style("", "open edition settings", "".to_string());
+        print_with_style(
+            ":   ",
+            "master edition mint",
+            open_edition_settings.master_edition_mint.to_string(),
+        );
+    } else {
+        print_with_style("", "open edition settings", "none".to_string());
+    }
+
+    // burn-to-mint settings
+    if let Some(burn_to_mint_settings) = candy_data.burn_to_mint_settings {
+        print_with_style("", "burn-to-mint settings", "".to_string());
+        print_with_style(":   ", "mode", format!("{:?}", burn_to_mint_settings.mode));
+        print_with_style(
+            ":   ",
+            "collection mint",
+            burn_to_mint_settings.collection_mint.to_string(),
+        );
+    } else {
+        print_with_style("", "burn-to-mint settings", "none".to_string());
+    }
+    print_with_style(
+        "",
+        "referral fee basis points",
+        candy_data.referral_fee_basis_points.to_string(),
+    );
+    print_with_style(
+        "",
+        "creator overrides enabled",
+        candy_data.creator_overrides_enabled.to_string(),
+    );
+
+    // usd price settings
+    if let Some(usd_price_settings) = candy_data.usd_price_settings {
+        print_with_style("", "usd price settings", "".to_string());
+        print_with_style(
+            ":   ",
+            "price feed",
+            usd_price_settings.price_feed.to_string(),
+        );
+        print_with_style(
+            ":   ",
+            "max price age seconds",
+            usd_price_settings.max_price_age_seconds.to_string(),
+        );
+        print_with_style(
+            ":   ",
+            "max confidence basis points",
+            usd_price_settings.max_confidence_basis_points.to_string(),
+        );
+    } else {
+        print_with_style("", "usd price settings", "none".to_string());
+    }
+
+    // mint rate limit settings
+    if let Some(mint_rate_limit_settings) = candy_data.mint_rate_limit_settings {
+        print_with_style("", "mint rate limit settings", "".to_string());
+        print_with_style(
+            ":   ",
+            "max mints",
+            mint_rate_limit_settings.max_mints.to_string(),
+        );
+        print_with_style(
+            ":   ",
+            "window slots",
+            mint_rate_limit_settings.window_slots.to_string(),
+        );
+    } else {
+        print_with_style("", "mint rate limit settings", "none".to_string());
+    }
+
+    // all-or-nothing reserve
+    if let Some(min_items_to_succeed) = candy_data.min_items_to_succeed {
+        print_with_style("", "min items to succeed", min_items_to_succeed.to_string());
+    } else {
+        print_with_style("", "min items to succeed", "none".to_string());
+    }
+
+    // programmable nft settings
+    if let Some(programmable_nft_settings) = candy_data.programmable_nft_settings {
+        print_with_style("", "programmable nft settings", "".to_string());
+        print_with_style(
+            ":   ",
+            "rule set",
+            match programmable_nft_settings.rule_set {
+                Some(rule_set) => rule_set.to_string(),
+                None => "none".to_string(),
+            },
+        );
+    } else {
+        print_with_style("", "programmable nft settings", "none".to_string());
+    }
+
+    print_with_style(
+        "",
+        "drop",
+        candy_data
+            .drop
+            .map_or("none".to_string(), |drop| drop.to_string()),
+    );
+
+    print_with_style(
+        "",
+        "mint order",
+        match candy_data.mint_order {
+            MintOrder::Random => "random".to_string(),
+            MintOrder::Sequential => "sequential".to_string(),
+            MintOrder::ReverseSequential => "reverse sequential".to_string(),
+            MintOrder::ReservedRangePerPhase { allowlist_items } => format!(
+                "reserved range per phase ({} allowlist items)",
+                allowlist_items
+            ),
+        },
+    );
+
+    // bot tax settings
+    if let Some(bot_tax_settings) = candy_data.bot_tax_settings {
+        print_with_style("", "bot tax settings", "".to_string());
+        print_with_style(
+            ":   ",
+            "fee",
+            format!(
+                "◎ {}",
+                bot_tax_settings.fee as f64 / LAMPORTS_PER_SOL as f64
+            ),
+        );
+        print_with_style(
+            ":   ",
+            "destination",
+            bot_tax_settings
+                .destination
+                .map_or("candy machine".to_string(), |destination| {
+                    destination.to_string()
+                }),
+        );
+        for program_id in bot_tax_settings.allowed_program_ids {
+            print_with_style(":   ", "allowed program", program_id.to_string());
+        }
+        print_with_style(
+            ":   ",
+            "check top level instruction",
+            bot_tax_settings.check_top_level_instruction.to_string(),
+        );
+        print_with_style(
+            ":   ",
+            "check trailing instructions",
+            bot_tax_settings.check_trailing_instructions.to_string(),
+        );
+        print_with_style(
+            ":   ",
+            "check transaction programs",
+            bot_tax_settings.check_transaction_programs.to_string(),
+        );
+    } else {
+        print_with_style("", "bot tax settings", "default".to_string());
+    }
+
+    // bot signers
+    if candy_state.bot_signers.is_empty() {
+        print_with_style("", "bot signers", "default".to_string());
+    } else {
+        print_with_style("", "bot signers", "".to_string());
+        for bot_signer in &candy_state.bot_signers {
+            print_with_style(
+                ":   ",
+                "bot signer",
+                match bot_signer.expires_at {
+                    Some(expires_at) => format!("{} (expires at {})", bot_signer.key, expires_at),
+                    None => bot_signer.key.to_string(),
+                },
+            );
+        }
+    }
 
     // SPL token allowlist settings
     if let Some(spl_token_allowlist_settings) = candy_data.spl_token_allowlist_settings {
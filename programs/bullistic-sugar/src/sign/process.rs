@@ -14,7 +14,11 @@ pub use anchor_client::{
 };
 use anyhow::Error;
 use console::style;
-use mpl_token_metadata::{instruction::sign_metadata, ID as METAPLEX_PROGRAM_ID};
+use mpl_token_metadata::{
+    instruction::sign_metadata,
+    state::{Metadata, TokenMetadataAccount},
+    ID as METAPLEX_PROGRAM_ID,
+};
 use retry::{delay::Exponential, retry};
 use solana_client::rpc_client::RpcClient;
 // use solana_transaction_crawler::crawler::Crawler;
@@ -30,6 +34,9 @@ use crate::{
     utils::*,
 };
 
+/// Number of sign_metadata instructions packed into a single transaction.
+const SIGN_METADATA_BATCH_SIZE: usize = 8;
+
 pub struct SignArgs {
     pub candy_machine_id: Option<String>,
     pub keypair: Option<String>,
@@ -45,7 +52,7 @@ pub async fn process_sign(args: SignArgs) -> Result<()> {
         if args.mint.is_some() {
             style("[1/2]").bold().dim()
         } else {
-            style("[1/3]").bold().dim()
+            style("[1/4]").bold().dim()
         },
         COMPUTER_EMOJI
     );
@@ -84,7 +91,7 @@ pub async fn process_sign(args: SignArgs) -> Result<()> {
     } else {
         println!(
             "\n{} {}Fetching mint ids",
-            style("[2/3]").bold().dim(),
+            style("[2/4]").bold().dim(),
             LOOKING_GLASS_EMOJI,
         );
 
@@ -143,54 +150,111 @@ pub async fn process_sign(args: SignArgs) -> Result<()> {
             pb.finish_with_message(format!("Found {:?} accounts", account_keys.len() as u64));
             println!(
                 "\n{} {}Signing mint accounts",
-                style("[3/3]").bold().dim(),
+                style("[3/4]").bold().dim(),
                 SIGNING_EMOJI
             );
         }
 
-        let pb = progress_bar_with_style(account_keys.len() as u64);
+        // Only sign the NFTs where the keypair is a creator that is not verified yet.
+        let signer = sugar_config.keypair.pubkey();
+        let metadata_accounts = get_metadata_accounts(program.rpc(), &account_keys)?;
+        let accounts_to_sign: Vec<Pubkey> = metadata_accounts
+            .iter()
+            .filter(|(_, metadata)| get_unverified_creators(metadata).contains(&signer))
+            .map(|(pubkey, _)| *pubkey)
+            .collect();
+
+        let pb = progress_bar_with_style(accounts_to_sign.len() as u64);
 
         let semaphore = Arc::new(Semaphore::new(100));
         let mut join_handles = Vec::new();
-        for account in account_keys {
+        for batch in accounts_to_sign.chunks(SIGN_METADATA_BATCH_SIZE) {
             let permit = Arc::clone(&semaphore).acquire_owned().await.unwrap();
             let config = sugar_config.clone();
             let pb = pb.clone();
+            let batch = batch.to_vec();
 
             join_handles.push(tokio::spawn(async move {
                 let _permit = permit;
-                sign(Arc::clone(&config), account).await.ok();
-                pb.inc(1);
+                let batch_len = batch.len() as u64;
+                let result = sign_batch(Arc::clone(&config), batch).await;
+                pb.inc(batch_len);
+                result
             }));
         }
 
         for handle in join_handles {
-            handle.await.map_err(|err| errors.push(err)).ok();
+            match handle.await {
+                Ok(Err(err)) => errors.push(err),
+                Err(err) => errors.push(anyhow!(err)),
+                Ok(Ok(())) => (),
+            }
         }
 
         if !errors.is_empty() {
             pb.abandon_with_message(format!("{}", style("Signing command failed ").red().bold()));
-            return Err(anyhow!("Not all NFTs were signed.".to_string()));
         } else {
             pb.finish_with_message(format!(
                 "{}",
                 style("All NFTs signed successfully.").green().bold()
             ));
         }
+
+        println!(
+            "\n{} {}Checking for unverified creators",
+            style("[4/4]").bold().dim(),
+            LOOKING_GLASS_EMOJI
+        );
+
+        let metadata_accounts = get_metadata_accounts(program.rpc(), &account_keys)?;
+        let unverified: Vec<(Pubkey, Vec<Pubkey>)> = metadata_accounts
+            .iter()
+            .map(|(_, metadata)| (metadata.mint, get_unverified_creators(metadata)))
+            .filter(|(_, creators)| !creators.is_empty())
+            .collect();
+
+        if unverified.is_empty() {
+            println!(
+                "{}",
+                style("All creators are verified for every NFT.")
+                    .green()
+                    .bold()
+            );
+        } else {
+            println!(
+                "{} NFT(s) still have unverified creators:",
+                style(unverified.len()).yellow().bold()
+            );
+            for (mint, creators) in &unverified {
+                let creators: Vec<String> = creators.iter().map(|c| c.to_string()).collect();
+                println!("  {} {}", mint, style(creators.join(", ")).dim());
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(anyhow!("Not all NFTs were signed.".to_string()));
+        }
     }
 
     Ok(())
 }
 
 async fn sign(config: Arc<SugarConfig>, metadata: Pubkey) -> Result<(), Error> {
+    sign_batch(config, vec![metadata]).await
+}
+
+async fn sign_batch(config: Arc<SugarConfig>, metadata: Vec<Pubkey>) -> Result<(), Error> {
     let client = setup_client(&config)?;
     let program = client.program(CANDY_MACHINE_ID);
 
     let recent_blockhash = program.rpc().get_latest_blockhash()?;
 
-    let ix = sign_metadata(METAPLEX_PROGRAM_ID, metadata, config.keypair.pubkey());
+    let ixs: Vec<_> = metadata
+        .iter()
+        .map(|metadata| sign_metadata(METAPLEX_PROGRAM_ID, *metadata, config.keypair.pubkey()))
+        .collect();
     let tx = Transaction::new_signed_with_payer(
-        &[ix],
+        &ixs,
         Some(&config.keypair.pubkey()),
         &[&config.keypair],
         recent_blockhash,
@@ -204,3 +268,33 @@ async fn sign(config: Arc<SugarConfig>, metadata: Pubkey) -> Result<(), Error> {
 
     Ok(())
 }
+
+fn get_metadata_accounts(
+    client: RpcClient,
+    metadata_pubkeys: &[Pubkey],
+) -> Result<Vec<(Pubkey, Metadata)>> {
+    let mut metadata_accounts = Vec::new();
+
+    for chunk in metadata_pubkeys.chunks(100) {
+        let accounts = client.get_multiple_accounts(chunk)?;
+        for (pubkey, account) in chunk.iter().zip(accounts) {
+            if let Some(account) = account {
+                let metadata = Metadata::safe_deserialize(&account.data)?;
+                metadata_accounts.push((*pubkey, metadata));
+            }
+        }
+    }
+
+    Ok(metadata_accounts)
+}
+
+fn get_unverified_creators(metadata: &Metadata) -> Vec<Pubkey> {
+    match &metadata.data.creators {
+        Some(creators) => creators
+            .iter()
+            .filter(|creator| !creator.verified)
+            .map(|creator| creator.address)
+            .collect(),
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,229 @@
+use anchor_client::solana_sdk::{
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+};
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use bullistic_candy_machine::{BuyerInfoAccount, BUYER_INFO_ACCOUNT_SPACE};
+use chrono::Utc;
+use console::style;
+use retry::{delay::Exponential, retry};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+
+use crate::{
+    cache::load_cache,
+    candy_machine::{get_candy_machine_state, CANDY_MACHINE_ID},
+    common::*,
+    pdas::find_buyer_info_account_pda,
+    utils::*,
+};
+
+/// Number of buyer info accounts closed by a single transaction.
+const SWEEP_BATCH_SIZE: usize = 8;
+
+pub struct SweepBuyerInfoArgs {
+    pub keypair: Option<String>,
+    pub rpc_url: Option<String>,
+    pub cache: String,
+    pub candy_machine: Option<String>,
+}
+
+/// Buyer info account to close, along with the wallet its rent goes back to.
+struct SweptAccount {
+    buyer: Pubkey,
+    rent_receiver: Pubkey,
+    lamports: u64,
+}
+
+pub fn process_sweep_buyer_info(args: SweepBuyerInfoArgs) -> Result<()> {
+    println!(
+        "{} {}Looking up buyer info accounts",
+        style("[1/2]").bold().dim(),
+        LOOKING_GLASS_EMOJI
+    );
+
+    let pb = spinner_with_style();
+    pb.set_message("Connecting...");
+
+    // the candy machine id specified takes precedence over the one from the cache
+
+    let candy_machine_id = if let Some(candy_machine) = args.candy_machine {
+        candy_machine
+    } else {
+        let cache = load_cache(&args.cache, false)?;
+        cache.program.candy_machine
+    };
+
+    let candy_machine_id = match Pubkey::from_str(&candy_machine_id) {
+        Ok(candy_machine_id) => candy_machine_id,
+        Err(_) => {
+            let error = anyhow!("Failed to parse candy machine id: {}", candy_machine_id);
+            error!("{:?}", error);
+            return Err(error);
+        }
+    };
+
+    let sugar_config = sugar_setup(args.keypair, args.rpc_url)?;
+    let client = setup_client(&sugar_config)?;
+    let program = client.program(CANDY_MACHINE_ID);
+
+    // Until the funds are withdrawn, only the authority can close the buyer
+    // info accounts, and only once the sale is over.
+    if program.rpc().get_account(&candy_machine_id).is_ok() {
+        let candy_machine_state = get_candy_machine_state(&sugar_config, &candy_machine_id)?;
+        if candy_machine_state.data.public_sale_end_time > Utc::now().timestamp() {
+            pb.finish_and_clear();
+            return Err(anyhow!(
+                "The public sale of candy machine {} has not ended yet.",
+                candy_machine_id
+            ));
+        }
+        if candy_machine_state.bullistic_authority != sugar_config.keypair.pubkey() {
+            pb.finish_and_clear();
+            return Err(anyhow!(
+                "Keypair {} is not the authority of candy machine {}.",
+                sugar_config.keypair.pubkey(),
+                candy_machine_id
+            ));
+        }
+    }
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(BUYER_INFO_ACCOUNT_SPACE as u64),
+            RpcFilterType::Memcmp(Memcmp::new(
+                0,
+                MemcmpEncodedBytes::Base58(
+                    bs58::encode(BuyerInfoAccount::discriminator()).into_string(),
+                ),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            min_context_slot: None,
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: None,
+            commitment: Some(CommitmentConfig {
+                commitment: CommitmentLevel::Confirmed,
+            }),
+        },
+        with_context: None,
+    };
+
+    let accounts = program
+        .rpc()
+        .get_program_accounts_with_config(&program.id(), config)?;
+
+    // Buyer info accounts don't store their candy machine, but only the ones
+    // of this candy machine are derived from it and their buyer. Accounts
+    // created before the buyer was recorded can't be found this way.
+    let mut swept_accounts = Vec::new();
+    for (pubkey, account) in accounts {
+        let buyer_info = BuyerInfoAccount::try_deserialize(&mut account.data.as_slice())?;
+        if buyer_info.buyer == Pubkey::default()
+            || find_buyer_info_account_pda(&candy_machine_id, &buyer_info.buyer).0 != pubkey
+        {
+            continue;
+        }
+        swept_accounts.push(SweptAccount {
+            buyer: buyer_info.buyer,
+            rent_receiver: buyer_info.payer,
+            lamports: account.lamports,
+        });
+    }
+
+    let total_lamports: u64 = swept_accounts.iter().map(|a| a.lamports).sum();
+    pb.finish_with_message(format!(
+        "Found {} buyer info accounts holding ◎ {}",
+        swept_accounts.len(),
+        total_lamports as f64 / LAMPORTS_PER_SOL as f64
+    ));
+
+    println!(
+        "\n{} {}Closing buyer info accounts",
+        style("[2/2]").bold().dim(),
+        WITHDRAW_EMOJI
+    );
+
+    if swept_accounts.is_empty() {
+        println!(
+            "{}",
+            style("No buyer info accounts left to close.")
+                .green()
+                .bold()
+        );
+        return Ok(());
+    }
+
+    let pb = progress_bar_with_style(swept_accounts.len() as u64);
+    let mut errors = Vec::new();
+
+    for batch in swept_accounts.chunks(SWEEP_BATCH_SIZE) {
+        let instructions: Vec<Instruction> = batch
+            .iter()
+            .map(|swept_account| Instruction {
+                program_id: CANDY_MACHINE_ID,
+                accounts: nft_accounts::CloseBuyerInfoAccount {
+                    candy_machine: candy_machine_id,
+                    buyer_info_account: find_buyer_info_account_pda(
+                        &candy_machine_id,
+                        &swept_account.buyer,
+                    )
+                    .0,
+                    buyer: swept_account.buyer,
+                    rent_receiver: swept_account.rent_receiver,
+                    authority: sugar_config.keypair.pubkey(),
+                }
+                .to_account_metas(None),
+                data: nft_instruction::CloseBuyerInfoAccount {}.data(),
+            })
+            .collect();
+
+        let result = program
+            .rpc()
+            .get_latest_blockhash()
+            .map_err(|err| anyhow!(err))
+            .and_then(|recent_blockhash| {
+                let tx = Transaction::new_signed_with_payer(
+                    &instructions,
+                    Some(&sugar_config.keypair.pubkey()),
+                    &[&sugar_config.keypair],
+                    recent_blockhash,
+                );
+
+                // Send tx with retries.
+                retry(
+                    Exponential::from_millis_with_factor(250, 2.0).take(3),
+                    || program.rpc().send_and_confirm_transaction(&tx),
+                )
+                .map_err(|err| anyhow!(err))
+            });
+        if let Err(err) = result {
+            errors.push(err);
+        }
+        pb.inc(batch.len() as u64);
+    }
+
+    if !errors.is_empty() {
+        pb.abandon_with_message(format!("{}", style("Sweep command failed ").red().bold()));
+        for err in &errors {
+            error!("{:?}", err);
+        }
+        return Err(anyhow!(
+            "{} sweep transaction(s) failed. Re-run the command to resume.",
+            errors.len()
+        ));
+    }
+
+    pb.finish_with_message(format!(
+        "{}",
+        style("All buyer info accounts closed successfully.")
+            .green()
+            .bold()
+    ));
+
+    Ok(())
+}
@@ -12,7 +12,10 @@ use bullistic_candy_machine::{
 use spl_associated_token_account::get_associated_token_address;
 
 use crate::{
-    candy_machine::{get_candy_machine_state, parse_config_price, CANDY_MACHINE_ID},
+    candy_machine::{
+        get_bot_signers, get_candy_machine_state, parse_config_price, rotate_bot_signers,
+        CANDY_MACHINE_ID,
+    },
     common::*,
     config::{
         data::{ConfigData, *},
@@ -175,6 +178,19 @@ pub fn process_update(args: UpdateArgs) -> Result<()> {
         update_signature
     ));
 
+    let bot_signers = get_bot_signers(&config_data.bot_signers)?;
+    if bot_signers != candy_machine_state.bot_signers {
+        let pb = spinner_with_style();
+        pb.set_message("Sending rotate bot signers transaction...");
+
+        let bot_signers_signature = rotate_bot_signers(&program, &candy_pubkey, bot_signers)?;
+        pb.finish_with_message(format!(
+            "{} {}",
+            style("Bot signers signature:").bold(),
+            bot_signers_signature
+        ));
+    }
+
     if let Some(new_authority) = args.new_authority {
         let pb = spinner_with_style();
         pb.set_message("Sending update authority transaction...");
@@ -219,6 +235,37 @@ fn create_candy_machine_data(
 
     let hidden_settings = config.hidden_settings.as_ref().map(|s| s.to_candy_format());
 
+    let open_edition_settings = config
+        .open_edition_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let bot_tax_settings = config
+        .bot_tax_settings
+        .as_ref()
+        .map(|s| s.to_candy_format())
+        .transpose()?;
+
+    let burn_to_mint_settings = config
+        .burn_to_mint_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let usd_price_settings = config
+        .usd_price_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let mint_rate_limit_settings = config
+        .mint_rate_limit_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
+    let programmable_nft_settings = config
+        .programmable_nft_settings
+        .as_ref()
+        .map(|s| s.to_candy_format());
+
     let price = parse_config_price(client, config, config.price)?;
     let premint_price = match config.premint_price {
         Some(price) => Some(parse_config_price(client, config, price)?),
@@ -261,6 +308,19 @@ fn create_candy_machine_data(
         sequential_mint_order_enabled: config.sequential_mint_order_enabled,
         merkle_allowlist_root_list: Vec::new(),
         allowlist_sale_start_time,
+        open_edition_settings,
+        bot_tax_settings,
+        on_chain_reveal_enabled: config.on_chain_reveal_enabled,
+        voucher_signer: config.voucher_signer,
+        burn_to_mint_settings,
+        referral_fee_basis_points: config.referral_fee_basis_points,
+        creator_overrides_enabled: config.creator_overrides_enabled,
+        usd_price_settings,
+        mint_rate_limit_settings,
+        min_items_to_succeed: config.min_items_to_succeed,
+        programmable_nft_settings,
+        drop: config.drop,
+        mint_order: config.mint_order.to_candy_format(),
     };
     Ok(data)
 }
@@ -6,6 +6,7 @@ use anchor_client::{
         commitment_config::{CommitmentConfig, CommitmentLevel},
         program_pack::{IsInitialized, Pack},
         pubkey::Pubkey,
+        signature::read_keypair_file,
     },
     Program,
 };
@@ -25,7 +26,7 @@ use spl_token::state::{Account, Mint};
 
 use crate::{
     common::*,
-    config::{data::Cluster, to_pubkey, to_string},
+    config::{data::Cluster, parser::get_config_data, to_pubkey, to_string},
 };
 
 /// Hash for devnet cluster
@@ -53,10 +54,11 @@ pub fn get_cluster(rpc_client: RpcClient) -> Result<Cluster> {
 pub fn check_spl_token(program: &Program, input: &str) -> Result<Mint> {
     let pubkey = Pubkey::from_str(input)?;
     let token_data = program.rpc().get_account_data(&pubkey)?;
-    if token_data.len() != 82 {
+    // Token-2022 mints may carry extensions after the base mint layout.
+    if token_data.len() < Mint::LEN {
         return Err(anyhow!("Invalid spl-token passed in."));
     }
-    let token_mint = Mint::unpack_from_slice(&token_data)?;
+    let token_mint = Mint::unpack_from_slice(&token_data[..Mint::LEN])?;
 
     if token_mint.is_initialized {
         Ok(token_mint)
@@ -72,7 +74,10 @@ pub fn check_spl_token(program: &Program, input: &str) -> Result<Mint> {
 pub fn check_spl_token_account(program: &Program, input: &str) -> Result<()> {
     let pubkey = Pubkey::from_str(input)?;
     let ata_data = program.rpc().get_account_data(&pubkey)?;
-    let ata_account = Account::unpack_unchecked(&ata_data)?;
+    if ata_data.len() < Account::LEN {
+        return Err(anyhow!("Invalid spl-token account passed in."));
+    }
+    let ata_account = Account::unpack_unchecked(&ata_data[..Account::LEN])?;
 
     if IsInitialized::is_initialized(&ata_account) {
         Ok(())
@@ -217,8 +222,26 @@ struct CandyMachinePubkeyJson {
     candy_machine_pubkey: Pubkey,
 }
 
-pub fn get_bot_signer_keypair() -> Keypair {
-    Keypair::from_bytes(BOT_SIGNER_AUTHORITY_SECRET).unwrap()
+/// Read the bot signer keypair from the botSignerKeypair path of the config
+/// file. Without one, the bundled dev keypair is used, which is only accepted
+/// by candy machines without their own bot signers on test deployments.
+pub fn get_bot_signer_keypair(config_path: &str) -> Result<Keypair> {
+    let bot_signer_keypair = if Path::new(config_path).exists() {
+        get_config_data(config_path)?.bot_signer_keypair
+    } else {
+        None
+    };
+
+    match bot_signer_keypair {
+        Some(keypair_path) => read_keypair_file(&*shellexpand::tilde(&keypair_path)).map_err(|e| {
+            anyhow!(
+                "Failed to read bot signer keypair file: {}, {}",
+                keypair_path,
+                e
+            )
+        }),
+        None => Ok(Keypair::from_bytes(BOT_SIGNER_AUTHORITY_SECRET)?),
+    }
 }
 
 pub fn write_candy_machine_pubkey_to_file(key: Pubkey) -> Result<()> {
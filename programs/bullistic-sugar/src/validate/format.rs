@@ -21,7 +21,11 @@ pub struct Metadata {
 }
 
 impl Metadata {
-    pub fn validate(&self, config_data_creators: &Vec<Creator>) -> Result<(), ValidateParserError> {
+    pub fn validate(
+        &self,
+        config_data_creators: &Vec<Creator>,
+        creator_overrides_enabled: bool,
+    ) -> Result<(), ValidateParserError> {
         parser::check_name(&self.name)?;
         parser::check_url(&self.image)?;
 
@@ -36,7 +40,10 @@ impl Metadata {
         match &self.properties.creators {
             Some(creators) => {
                 parser::check_creators_shares(creators)?;
-                parser::validate_metadata_creators(config_data_creators, creators)?;
+                // With creator overrides each asset can have its own creators.
+                if !creator_overrides_enabled {
+                    parser::validate_metadata_creators(config_data_creators, creators)?;
+                }
             }
             None => return Err(ValidateParserError::MissingCreators),
         }
@@ -117,7 +117,7 @@ pub fn process_validate(args: ValidateArgs) -> Result<()> {
 
         // To be replaced with the strict validator once JSON standard is finalized.
         if args.strict {
-            match metadata.validate(&config_data.creators) {
+            match metadata.validate(&config_data.creators, config_data.creator_overrides_enabled) {
                 Ok(()) => {}
                 Err(e) => {
                     error!("{}: {}", path.display(), e);
@@ -128,7 +128,7 @@ pub fn process_validate(args: ValidateArgs) -> Result<()> {
                 }
             }
         } else {
-            match metadata.validate(&config_data.creators) {
+            match metadata.validate(&config_data.creators, config_data.creator_overrides_enabled) {
                 Ok(()) => {}
                 Err(e) => {
                     error!("{}: {}", path.display(), e);
@@ -87,7 +87,7 @@ pub fn process_verify(args: VerifyArgs) -> Result<()> {
         PAPER_EMOJI
     );
 
-    if candy_machine.data.hidden_settings.is_none() {
+    if candy_machine.data.has_config_lines() {
         let num_items = candy_machine.data.items_available;
         let cache_items = &mut cache.items;
         let mut errors = Vec::new();
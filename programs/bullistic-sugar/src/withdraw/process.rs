@@ -196,6 +196,8 @@ fn do_withdraw(program: Rc<Program>, candy_machine: Pubkey, payer: Pubkey) -> Re
         .accounts(nft_accounts::WithdrawFunds {
             candy_machine,
             bullistic_authority: payer,
+            authority_wallet: payer,
+            roles_pda: None,
         })
         .args(nft_instruction::WithdrawFunds {})
         .send()?;
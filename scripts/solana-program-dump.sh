@@ -1,4 +1,7 @@
 #!/bin/bash
 echo "Running 'solana program dump' and dumping program binaries to artifacts/* folder..."
 solana program dump metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s artifacts/mpl_token_metadata.so
+solana program dump BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY artifacts/mpl_bubblegum.so
+solana program dump cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK artifacts/spl_account_compression.so
+solana program dump noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV artifacts/spl_noop.so
 echo "Done!"
\ No newline at end of file
@@ -10,7 +10,7 @@ import dayjs from "dayjs";
 import { CandyMachineProgram, BULLISTIC_CANDY_MACHINE_IDL } from "sdk/idl";
 import { BullisticCandyMachine } from "sdk/idl/BullisticCandyMachine";
 import candyMachineMintNftIx from "sdk/instructions/candyMachineMintNftIx";
-import candyMachineSetCollectionDuringMintIx from "sdk/instructions/candyMachineSetCollectionDuringMintIx";
+import candyMachineUnlockFundsIx from "sdk/instructions/candyMachineUnlockFundsIx";
 import findCandyMachineCollectionPda from "sdk/pdas/findCandyMachineCollectionPda";
 import findCandyMachineCreatorPda from "sdk/pdas/findCandyMachineCreatorPda";
 import BuyerWithAllowlistProofData from "sdk/types/BuyerWithAllowlistProofData";
@@ -155,18 +155,24 @@ export default class BullisticCandyMachineSdk {
         program: this.program,
       }
     );
-    const setCollectionDuringMintIx =
-      await candyMachineSetCollectionDuringMintIx(
-        {
-          buyer,
-          candyMachine,
-          mint,
-        },
-        {
-          program: this.program,
-        }
-      );
-
-    return ixsToTx([mintNftIx, setCollectionDuringMintIx]);
+
+    return ixsToTx([mintNftIx]);
+  }
+
+  async unlockFunds({
+    bullisticAuthority,
+    candyMachine,
+    destinationTokenAccount,
+  }: {
+    bullisticAuthority: PublicKey;
+    candyMachine: PublicKey;
+    destinationTokenAccount?: PublicKey;
+  }): Promise<Transaction> {
+    const unlockFundsIx = await candyMachineUnlockFundsIx(
+      { bullisticAuthority, candyMachine, destinationTokenAccount },
+      { program: this.program }
+    );
+
+    return ixsToTx([unlockFundsIx]);
   }
 }
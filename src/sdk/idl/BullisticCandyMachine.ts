@@ -235,7 +235,7 @@ export type BullisticCandyMachine = {
     },
     {
       code: 8040;
-      msg: "Missing required remaining accounts for remove_freeze with token mint.";
+      msg: "Missing token program, freeze token account, destination token account or treasury mint in the remaining accounts.";
       name: "MissingRemoveFreezeTokenAccounts";
     },
     {
@@ -964,7 +964,7 @@ export const IDL: BullisticCandyMachine = {
     },
     {
       code: 8040,
-      msg: "Missing required remaining accounts for remove_freeze with token mint.",
+      msg: "Missing token program, freeze token account, destination token account or treasury mint in the remaining accounts.",
       name: "MissingRemoveFreezeTokenAccounts",
     },
     {
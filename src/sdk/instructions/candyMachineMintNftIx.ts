@@ -24,6 +24,8 @@ import BN from "bn.js";
 import dayjs from "dayjs";
 import { CandyMachineProgram } from "sdk/idl";
 import findBuyerInfoAccountPda from "sdk/pdas/findBuyerInfoAccountPda";
+import findCandyMachineCollectionAuthorityPda from "sdk/pdas/findCandyMachineCollectionAuthorityPda";
+import findCandyMachineCollectionPda from "sdk/pdas/findCandyMachineCollectionPda";
 import findCandyMachineCreatorPda from "sdk/pdas/findCandyMachineCreatorPda";
 import BuyerWithAllowlistProofData from "sdk/types/BuyerWithAllowlistProofData";
 import CandyMachineAccount from "sdk/types/candy-machine/CandyMachineAccount";
@@ -53,7 +55,8 @@ function getIxMerkleAllowlistProofData(
 
 async function getSplAllowlistSettingRemainingAccounts(
   candyMachineState: CandyMachineAccount,
-  buyerAllowlistTokenAccount: Maybe<PublicKey>
+  buyerAllowlistTokenAccount: Maybe<PublicKey>,
+  program: CandyMachineProgram
 ): Promise<Array<AccountMeta>> {
   if (buyerAllowlistTokenAccount == null) {
     return [];
@@ -91,12 +94,55 @@ async function getSplAllowlistSettingRemainingAccounts(
       isWritable: true,
       pubkey: mint,
     };
-    remainingAccounts.push(splTokenAllowlistMintAccount);
+    // The allowlist mint may be owned by either SPL token or Token-2022.
+    const mintAccountInfo = await program.provider.connection.getAccountInfo(
+      mint
+    );
+    const splTokenAllowlistTokenProgram: AccountMeta = {
+      isSigner: false,
+      isWritable: false,
+      pubkey: mintAccountInfo?.owner ?? TOKEN_PROGRAM_ID,
+    };
+    remainingAccounts.push(
+      splTokenAllowlistMintAccount,
+      splTokenAllowlistTokenProgram
+    );
   }
 
   return remainingAccounts;
 }
 
+async function getCollectionRemainingAccounts(
+  candyMachine: PublicKey,
+  program: CandyMachineProgram
+): Promise<Array<AccountMeta>> {
+  const [collectionPda] = findCandyMachineCollectionPda(
+    candyMachine,
+    program.programId
+  );
+  const collectionPdaAccount =
+    await program.account.collectionPda.fetchNullable(collectionPda);
+  if (collectionPdaAccount == null) {
+    return [];
+  }
+
+  const collectionMint = collectionPdaAccount.mint;
+  const [collectionMetadata] = findTokenMetadataPda(collectionMint);
+  const [collectionMasterEdition] = findEditionPda(collectionMint);
+  const [collectionAuthorityRecord] = findCandyMachineCollectionAuthorityPda(
+    collectionMint,
+    collectionPda
+  );
+
+  return [
+    { isSigner: false, isWritable: true, pubkey: collectionPda },
+    { isSigner: false, isWritable: false, pubkey: collectionMint },
+    { isSigner: false, isWritable: true, pubkey: collectionMetadata },
+    { isSigner: false, isWritable: false, pubkey: collectionMasterEdition },
+    { isSigner: false, isWritable: false, pubkey: collectionAuthorityRecord },
+  ];
+}
+
 type Accounts = {
   botSignerAuthority: PublicKey;
   buyer: PublicKey;
@@ -139,8 +185,13 @@ export default async function candyMachineMintNftIx(
   const splTokenRemainingAccounts =
     await getSplAllowlistSettingRemainingAccounts(
       candyMachineState,
-      buyerAllowlistTokenAccount
+      buyerAllowlistTokenAccount,
+      program
     );
+  const collectionRemainingAccounts = await getCollectionRemainingAccounts(
+    candyMachine,
+    program
+  );
 
   const ix = await program.methods
     .mintNft(
@@ -169,7 +220,10 @@ export default async function candyMachineMintNftIx(
       tokenProgram: TOKEN_PROGRAM_ID,
       treasuryWallet: candyMachineState.treasuryWallet,
     })
-    .remainingAccounts(splTokenRemainingAccounts)
+    .remainingAccounts([
+      ...splTokenRemainingAccounts,
+      ...collectionRemainingAccounts,
+    ])
     .instruction();
 
   // If bot protection measures are enabled for this candy machine, we want to
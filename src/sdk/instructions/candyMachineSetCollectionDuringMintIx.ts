@@ -22,6 +22,11 @@ type Args = {
   program: CandyMachineProgram;
 };
 
+/**
+ * @deprecated The mint instruction from candyMachineMintNftIx sets and
+ * verifies the collection itself. This is only kept for transactions that
+ * still send setCollectionDuringMint right after mintNft.
+ */
 export default async function candyMachineSetCollectionDuringMintIx(
   { buyer, candyMachine, mint }: Accounts,
   { program }: Args
@@ -0,0 +1,103 @@
+import {
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import {
+  AccountMeta,
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { CandyMachineProgram } from "sdk/idl";
+import findCandyMachineFreezePda from "sdk/pdas/findCandyMachineFreezePda";
+
+/**
+ * With a treasury mint the escrowed tokens are moved out of the freeze PDA's
+ * token account, which needs, in this order: the treasury mint's token
+ * program, the freeze PDA's token account, the destination token account and
+ * the treasury mint.
+ */
+async function getTreasuryMintRemainingAccounts(
+  treasuryMint: PublicKey,
+  freezePda: PublicKey,
+  destinationTokenAccount: PublicKey | undefined,
+  bullisticAuthority: PublicKey,
+  program: CandyMachineProgram
+): Promise<Array<AccountMeta>> {
+  // The treasury mint may be owned by either SPL token or Token-2022.
+  const mintAccountInfo = await program.provider.connection.getAccountInfo(
+    treasuryMint
+  );
+  const tokenProgram = mintAccountInfo?.owner ?? TOKEN_PROGRAM_ID;
+  const freezeTokenAccount = getAssociatedTokenAddressSync(
+    treasuryMint,
+    freezePda,
+    true,
+    tokenProgram
+  );
+
+  return [
+    { isSigner: false, isWritable: false, pubkey: tokenProgram },
+    { isSigner: false, isWritable: true, pubkey: freezeTokenAccount },
+    {
+      isSigner: false,
+      isWritable: true,
+      pubkey:
+        destinationTokenAccount ??
+        getAssociatedTokenAddressSync(
+          treasuryMint,
+          bullisticAuthority,
+          false,
+          tokenProgram
+        ),
+    },
+    { isSigner: false, isWritable: false, pubkey: treasuryMint },
+  ];
+}
+
+type Accounts = {
+  bullisticAuthority: PublicKey;
+  candyMachine: PublicKey;
+  // Defaults to the authority's associated token account for the treasury
+  // mint. Unused if the candy machine has no treasury mint.
+  destinationTokenAccount?: PublicKey;
+};
+
+type Args = {
+  program: CandyMachineProgram;
+};
+
+export default async function candyMachineUnlockFundsIx(
+  { bullisticAuthority, candyMachine, destinationTokenAccount }: Accounts,
+  { program }: Args
+): Promise<TransactionInstruction> {
+  const [freezePda] = findCandyMachineFreezePda(
+    candyMachine,
+    program.programId
+  );
+  const candyMachineState = await program.account.candyMachine.fetch(
+    candyMachine
+  );
+
+  const remainingAccounts =
+    candyMachineState.treasuryMint == null
+      ? []
+      : await getTreasuryMintRemainingAccounts(
+          candyMachineState.treasuryMint,
+          freezePda,
+          destinationTokenAccount,
+          bullisticAuthority,
+          program
+        );
+
+  return program.methods
+    .unlockFunds()
+    .accounts({
+      bullisticAuthority,
+      candyMachine,
+      freezePda,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(remainingAccounts)
+    .instruction();
+}
@@ -0,0 +1,12 @@
+import { PdaResult } from "@bullistic-hq/bullistic-program-shared";
+import { PublicKey } from "@solana/web3.js";
+
+export default function findCandyMachineFreezePda(
+  candyMachine: PublicKey,
+  candyMachineProgramId: PublicKey
+): PdaResult {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("freeze"), candyMachine.toBuffer()],
+    candyMachineProgramId
+  );
+}